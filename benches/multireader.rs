@@ -0,0 +1,57 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use filetrack::Multireader;
+
+const TOTAL_SIZE: usize = 100 * 1024 * 1024;
+const LINE: &[u8] = b"Oct 16 12:00:00 host postfix/smtpd[1234]: connect from unknown[192.0.2.1]\n";
+
+/// Write `size` bytes worth of log lines into `path`, returning number of lines written.
+fn write_log(path: &Path, size: usize) -> u64 {
+    let mut file = BufWriter::new(File::create(path).unwrap());
+    let line_count = size / LINE.len();
+    for _ in 0..line_count {
+        file.write_all(LINE).unwrap();
+    }
+    file.flush().unwrap();
+    line_count as u64
+}
+
+/// Rotated file followed by the current one, half of total size each.
+fn two_file_setup(dir: &Path) -> (Vec<PathBuf>, u64) {
+    let paths = vec![dir.join("mail.log.1"), dir.join("mail.log")];
+    let line_count = paths
+        .iter()
+        .map(|path| write_log(path, TOTAL_SIZE / 2))
+        .sum();
+    (paths, line_count)
+}
+
+fn read_lines(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let (paths, line_count) = two_file_setup(dir.path());
+
+    let mut group = c.benchmark_group("multireader");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(line_count));
+    group.bench_function("read_line over two files", |b| {
+        b.iter(|| {
+            let mut reader = Multireader::from_paths(&paths).unwrap();
+            let mut line = String::new();
+            let mut lines_read = 0;
+            while reader.read_line(&mut line).unwrap() != 0 {
+                lines_read += 1;
+                line.clear();
+            }
+            assert_eq!(lines_read, line_count);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, read_lines);
+criterion_main!(benches);
@@ -11,7 +11,7 @@ fn main() -> Result<(), anyhow::Error> {
     if input == "third" {
         println!("stumbled upon a third line, performing rollback");
         let offset = -(size as i64);
-        reader.seek(std::io::SeekFrom::Current(offset))?;
+        reader.seek_relative(offset)?;
     } else {
         println!("read `{input}`");
     }
@@ -0,0 +1,190 @@
+use std::{
+    fmt::{self, Debug},
+    fs::File,
+    io::{self, BufReader, Write},
+    path::Path,
+    sync::Arc,
+};
+
+/// Function that decompresses whole content of `input` into `output`.
+///
+/// Closures that do not capture anything can be used as well:
+///
+/// ```rust
+/// # use std::io;
+/// # use filetrack::{CodecRegistry, Decoder};
+/// // pretend that `.raw` is a compression format
+/// let decoder: Decoder = |mut input, output| io::copy(&mut input, output).map(|_| ());
+/// let mut codecs = CodecRegistry::new();
+/// codecs.register(".raw", decoder);
+/// assert_eq!(codecs.suffixes(), vec![".raw"]);
+/// ```
+pub type Decoder = fn(input: File, output: &mut dyn Write) -> io::Result<()>;
+
+/// Opens files of logs for `CodecRegistry::open_log`. Replaced in tests to count files that are actually opened.
+pub(crate) trait FileOpener: Send + Sync {
+    fn open(&self, path: &Path) -> io::Result<File>;
+}
+
+/// Opener used unless replaced, see `FileOpener`.
+struct FsOpener;
+
+impl FileOpener for FsOpener {
+    fn open(&self, path: &Path) -> io::Result<File> {
+        File::open(path)
+    }
+}
+
+/// Compression formats of rotated logs supported out of the box. Each of them is only available with the feature of the
+/// same name enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressedCodec {
+    #[cfg(feature = "gzip")]
+    Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+    #[cfg(feature = "xz")]
+    Xz,
+}
+
+impl CompressedCodec {
+    /// All codecs enabled by features.
+    pub const ENABLED: &'static [CompressedCodec] = &[
+        #[cfg(feature = "gzip")]
+        CompressedCodec::Gzip,
+        #[cfg(feature = "zstd")]
+        CompressedCodec::Zstd,
+        #[cfg(feature = "xz")]
+        CompressedCodec::Xz,
+    ];
+
+    /// Suffix that logrotate appends to compressed files, including leading dot.
+    pub fn suffix(self) -> &'static str {
+        match self {
+            #[cfg(feature = "gzip")]
+            CompressedCodec::Gzip => ".gz",
+            #[cfg(feature = "zstd")]
+            CompressedCodec::Zstd => ".zst",
+            #[cfg(feature = "xz")]
+            CompressedCodec::Xz => ".xz",
+        }
+    }
+
+    /// Function decompressing files of this format.
+    pub fn decoder(self) -> Decoder {
+        match self {
+            #[cfg(feature = "gzip")]
+            CompressedCodec::Gzip => |input, output| {
+                io::copy(&mut flate2::read::MultiGzDecoder::new(input), output).map(|_| ())
+            },
+            #[cfg(feature = "zstd")]
+            CompressedCodec::Zstd => |input, output| zstd::stream::copy_decode(input, output),
+            #[cfg(feature = "xz")]
+            CompressedCodec::Xz => |input, output| {
+                io::copy(&mut xz2::read::XzDecoder::new_multi_decoder(input), output).map(|_| ())
+            },
+        }
+    }
+}
+
+/// Mapping from file suffix to decoder used to find and open compressed rotated logs.
+///
+/// Default registry contains all codecs enabled by features (see `CompressedCodec`), custom ones can be added with
+/// `register`. When looking for rotated log `path.N`, uncompressed version is preferred, then suffixes are tried in order
+/// of registration.
+#[derive(Clone)]
+pub struct CodecRegistry {
+    codecs: Vec<(String, Decoder)>,
+    opener: Arc<dyn FileOpener>,
+}
+
+impl CodecRegistry {
+    /// Create registry without any codecs, so that only uncompressed logs are found.
+    pub fn new() -> Self {
+        Self {
+            codecs: vec![],
+            opener: Arc::new(FsOpener),
+        }
+    }
+
+    /// Add decoder for files ending with `suffix` (including leading dot, eg. `.lz4`), replacing previously registered one.
+    pub fn register(&mut self, suffix: impl Into<String>, decoder: Decoder) -> &mut Self {
+        let suffix = suffix.into();
+        self.codecs
+            .retain(|(known_suffix, _)| *known_suffix != suffix);
+        self.codecs.push((suffix, decoder));
+        self
+    }
+
+    /// Suffixes of registered codecs in order of registration.
+    pub fn suffixes(&self) -> Vec<&str> {
+        self.codecs
+            .iter()
+            .map(|(suffix, _)| suffix.as_str())
+            .collect()
+    }
+
+    /// Open logs with `opener` instead of `File::open`.
+    #[cfg(test)]
+    pub(crate) fn set_opener(&mut self, opener: Arc<dyn FileOpener>) -> &mut Self {
+        self.opener = opener;
+        self
+    }
+
+    /// Open a (possibly compressed) log for reading with buffer of given `capacity`.
+    ///
+    /// Compressed logs are decompressed eagerly into an anonymous temporary file, so they can be read and seeked in the
+    /// same way as plain ones.
+    pub(crate) fn open_log(&self, path: &Path, capacity: usize) -> io::Result<BufReader<File>> {
+        let file = self.opener.open(path)?;
+        match self.find_decoder(path) {
+            Some(decoder) => decompress_to_tempfile(file, decoder, capacity),
+            None => Ok(BufReader::with_capacity(capacity, file)),
+        }
+    }
+
+    /// Check whether log at `path` is decompressed when opened.
+    pub(crate) fn is_compressed(&self, path: &Path) -> bool {
+        self.find_decoder(path).is_some()
+    }
+
+    fn find_decoder(&self, path: &Path) -> Option<Decoder> {
+        let file_name = path.as_os_str().as_encoded_bytes();
+        self.codecs
+            .iter()
+            .find(|(suffix, _)| file_name.ends_with(suffix.as_bytes()))
+            .map(|(_, decoder)| *decoder)
+    }
+}
+
+impl Default for CodecRegistry {
+    /// Registry with all codecs enabled by features.
+    fn default() -> Self {
+        let mut registry = Self::new();
+        for codec in CompressedCodec::ENABLED {
+            registry.register(codec.suffix(), codec.decoder());
+        }
+        registry
+    }
+}
+
+impl Debug for CodecRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CodecRegistry")
+            .field("suffixes", &self.suffixes())
+            .finish()
+    }
+}
+
+fn decompress_to_tempfile(
+    input: File,
+    decoder: Decoder,
+    capacity: usize,
+) -> io::Result<BufReader<File>> {
+    use std::io::Seek;
+
+    let mut file = tempfile::tempfile()?;
+    decoder(input, &mut file)?;
+    file.rewind()?;
+    Ok(BufReader::with_capacity(capacity, file))
+}
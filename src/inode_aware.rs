@@ -1,20 +1,234 @@
 use std::{
     cmp::Ordering,
-    fs::File,
-    io::{self, BufReader},
+    collections::HashMap,
+    fmt,
+    fs::{self, Metadata},
+    io::{self, BufRead, Read, Seek},
     ops::{Deref, DerefMut},
-    path::Path,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use crate::{path_utils::glob_rotated_logs, Multireader};
+use crate::{
+    compression::CodecRegistry,
+    log_file::LogFile,
+    multireader::{read_record_limited, LimitedRecord, UnfinishedSkip},
+    path_utils::{
+        file_id, glob_rotated_logs_with_scheme, OldDir, RotationNaming, RotationScheme, SkipReason,
+    },
+    Multireader,
+};
 
 /// Structure that can be used as persistent offset into rotated logs. See `InodeAwareReader` for more info.
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct InodeAwareOffset {
+    /// file identifier as returned by `path_utils::file_id` (inode on unix, file index on Windows)
+    pub inode: u64,
+    pub offset: u64,
+    /// fingerprint of the file used to find it when inode changed, see `InodeAwareReader::set_fingerprint_length`
+    #[serde(default)]
+    pub fingerprint: Option<Fingerprint>,
+    /// size and modification time of the file used to detect inode reuse, see `InodeAwareReader::set_record_file_stamps`
+    #[serde(default)]
+    pub file_stamp: Option<FileStamp>,
+}
+
+/// Size and modification time of a file at the moment persistent offset was taken.
+///
+/// Log files only grow and get modified, so a file that is smaller or was modified earlier than recorded must be a
+/// different one that got the same inode after the original file was removed.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct FileStamp {
+    pub size: u64,
+    /// modification time in nanoseconds since unix epoch
+    pub modified: u64,
+}
+
+impl FileStamp {
+    fn from_metadata(metadata: &Metadata) -> io::Result<Self> {
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Self {
+            size: metadata.len(),
+            modified: modified.as_nanos() as u64,
+        })
+    }
+
+    /// Check whether file stamped with `current` may be the same file as the one stamped with `self` earlier.
+    fn may_be_followed_by(&self, current: &FileStamp) -> bool {
+        current.size >= self.size && current.modified >= self.modified
+    }
+}
+
+/// Identity of file content that survives inode changes (eg. when file was copied or restored from backup).
+///
+/// Log files are append-only, so a file still matches its fingerprint after it grew.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    /// FNV-1a hash of the first `prefix_len` bytes
+    pub hash: u64,
+    /// number of bytes hashed, limited by fingerprint length and file size
+    pub prefix_len: u64,
+    /// size of the file at the moment fingerprint was computed
+    pub size: u64,
+}
+
+/// What `InodeAwareReader::seek_persistent` does when persistent offset points past the end of its file, which happens
+/// when the log was truncated in place (eg. by `copytruncate` option of logrotate).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TruncationPolicy {
+    /// Start reading the file from the beginning, since its old content is gone.
+    #[default]
+    RestartFromBeginning,
+    /// Skip whatever was written to the file after truncation.
+    SeekToEnd,
+    /// Fail with InvalidData io error carrying `Truncation`.
+    Error,
+}
+
+/// Truncation detected by `InodeAwareReader::seek_persistent`.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Hash)]
+#[error(
+    "offset {offset} is past the end of {} (inode {inode}) of size {size}, file was probably truncated",
+    .path.display()
+)]
+pub struct Truncation {
     pub inode: u64,
+    /// path the file had when it was opened
+    pub path: PathBuf,
+    /// persistent offset that was requested
     pub offset: u64,
+    /// actual size of the file
+    pub size: u64,
+}
+
+/// Truncation of current file detected on EOF when `InodeAwareReader::set_detect_truncation` is enabled.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Hash)]
+#[error(
+    "{} (inode {inode}) shrank to {new_size} bytes while being read at offset {previous_offset}, file was probably truncated",
+    .path.display()
+)]
+pub struct TruncationDetected {
+    pub inode: u64,
+    /// path the file had when it was opened
+    pub path: PathBuf,
+    /// local offset reader was at
+    pub previous_offset: u64,
+    /// actual size of the file
+    pub new_size: u64,
+}
+
+/// Line longer than limit set by `InodeAwareReader::set_max_line_length`. Rest of the line was skipped.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Hash)]
+#[error(
+    "line at offset {} of inode {} is longer than {max} bytes, skipped {length} bytes of it",
+    .start.offset,
+    .start.inode
+)]
+pub struct LineTooLong {
+    /// position of the line start
+    pub start: InodeAwareOffset,
+    /// number of bytes skipped so far, including delimiter if it was found
+    pub length: u64,
+    pub max: usize,
+}
+
+/// What `InodeAwareReader::seek_persistent_or` does when file of persistent offset cannot be found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MissingInodePolicy {
+    /// Start from the beginning of the oldest file still available.
+    StartOfOldest,
+    /// Skip everything and continue from the end of the newest file.
+    EndOfNewest,
+    /// Fail with NotFound io error, same as `seek_persistent`.
+    #[default]
+    Error,
+}
+
+/// How `InodeAwareReader` treats path to the newest log that is a symbolic link.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SymlinkPolicy {
+    /// Link is followed when files are opened, while rotated logs are searched for next to the link (eg. `current.1` for
+    /// `current`). Link pointing to another file is picked up by `InodeAwareReader::refresh` like a rotation.
+    #[default]
+    Follow,
+    /// Link is resolved once on construction, after that the target is read and its rotated versions are searched for
+    /// (eg. `app.log.1` for `current -> app.log`) as if its path was given. Pointing link elsewhere has no effect.
+    FollowAndTrackTarget,
+    /// Link target is read without searching for any rotated logs. On `InodeAwareReader::refresh`, the link is resolved
+    /// again and if it points to another file, that file is appended after the previous target, so rotation that is done
+    /// by repointing the link is followed.
+    WatchLinkTarget,
+}
+
+/// Result of `InodeAwareReader::seek_persistent_or`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SeekOutcome {
+    /// Exact position was restored.
+    Restored,
+    /// File was found truncated and position was chosen by `TruncationPolicy`.
+    Truncated(Truncation),
+    /// File was not found and position was chosen by given policy, so some data may have been skipped.
+    FellBack(MissingInodePolicy),
+}
+
+/// Result of `InodeAwareReader::reopen_current`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReopenOutcome {
+    /// Current file was found and reopened, position is unchanged.
+    Reopened,
+    /// Current file is no longer among rotated logs, so the rest of it cannot be read and next file should be read instead.
+    Gone,
+    /// Current file was reopened, but it is now smaller than current local offset, so reading it yields nothing.
+    Shrunk { size: u64 },
+}
+
+/// State of one file found by `InodeAwareReader::verify_integrity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ItemIntegrity {
+    /// File is still at its path and contains at least as much data as was known.
+    Ok,
+    /// File is still at its path, but is `by` bytes smaller than known, eg. because it was truncated.
+    Shrunk { by: u64 },
+    /// Path now points to a different file, eg. because logs were rotated.
+    Replaced { new_inode: u64 },
+    /// Nothing exists at the path anymore.
+    Missing,
+}
+
+/// Result of `InodeAwareReader::verify_integrity`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntegrityReport {
+    /// findings in the same order as items of the reader
+    pub items: Vec<ItemIntegrity>,
+}
+
+impl IntegrityReport {
+    /// Check whether every file is intact.
+    pub fn is_ok(&self) -> bool {
+        self.items.iter().all(|item| *item == ItemIntegrity::Ok)
+    }
+}
+
+/// Result of `InodeAwareReader::validate_offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OffsetValidity {
+    /// Offset can be restored exactly.
+    Valid,
+    /// File of the offset was found neither by inode nor by fingerprint.
+    UnknownInode,
+    /// File was found, but offset is past its end (eg. because it was truncated).
+    PastEndOfFile { file_size: u64 },
 }
 
 /// Reader that keeps track of what inode it reads from.
@@ -25,7 +239,7 @@ pub struct InodeAwareOffset {
 /// ```rust no_run
 /// # use std::io::{Read, BufRead, self};
 /// # use filetrack::{InodeAwareOffset, InodeAwareReader};
-/// # fn load_state() -> io::Result<InodeAwareOffset> {Ok(InodeAwareOffset{inode: 0, offset: 0})}
+/// # fn load_state() -> io::Result<InodeAwareOffset> {Ok(InodeAwareOffset{inode: 0, offset: 0, fingerprint: None, file_stamp: None})}
 /// # fn save_state(state: InodeAwareOffset) -> io::Result<()> {Ok(())}
 /// let mut reader = InodeAwareReader::from_rotated_logs("/var/log/mail.log")?;
 /// reader.seek_persistent(load_state()?)?;
@@ -36,110 +250,4627 @@ pub struct InodeAwareOffset {
 /// ```
 ///
 /// During initialization, this reader searches for rotated versions of provided path and notes their inodes. After that inodes can be
-/// used for simple persistent indexing when combined with local offset.
+/// used for simple persistent indexing when combined with local offset. On Windows file index is used in place of inode, see
+/// `path_utils::file_id`.
 pub struct InodeAwareReader {
-    inner: Multireader<BufReader<File>>,
+    inner: Multireader<LogFile>,
     inodes: Vec<u64>,
+    /// item index by inode, the first one if refresh found the same file twice
+    item_indices: HashMap<u64, usize>,
+    /// paths of items at the moment they were opened, in the same order as `inodes`
+    paths: Vec<PathBuf>,
+    /// path to the newest log used to search for rotated versions
+    path: PathBuf,
+    max_depth: usize,
+    scheme: Box<dyn RotationScheme + Send>,
+    codecs: CodecRegistry,
+    auto_refresh: bool,
+    detect_truncation: bool,
+    auto_release: bool,
+    /// number of leading bytes used for fingerprints, None if fingerprinting is disabled
+    fingerprint_length: Option<usize>,
+    /// fingerprint of each item, computed when fingerprinting is enabled
+    fingerprints: Vec<Option<Fingerprint>>,
+    truncation_policy: TruncationPolicy,
+    missing_inode_policy: MissingInodePolicy,
+    record_file_stamps: bool,
+    /// capacity of buffers of files opened on refresh
+    buffer_capacity: usize,
+    /// whether rotated files are memory-mapped
+    #[cfg(feature = "mmap")]
+    map_rotated: bool,
+    /// whether newest log may be missing, see `InodeAwareReaderBuilder::allow_missing`
+    allow_missing: bool,
+    symlink_policy: SymlinkPolicy,
+    /// rotated logs that were too old to be opened, see `InodeAwareReaderBuilder::min_mtime`
+    skipped_files: Vec<PathBuf>,
+    resolution_report: ResolutionReport,
+    line_tracking: bool,
+    /// None if line tracking is disabled or cursor was moved other than by reading
+    line_count: Option<LineCount>,
+    max_line_length: Option<usize>,
+    /// too long line whose rest is skipped once it is written, see `set_max_line_length`
+    unfinished_skip: Option<UnfinishedSkip>,
+}
+
+/// Capacity of buffers of opened files unless configured otherwise, same as `BufReader` uses.
+const DEFAULT_BUFFER_CAPACITY: usize = 8 * 1024;
+
+/// Newlines read so far, see `InodeAwareReader::enable_line_tracking`.
+#[derive(Clone, Copy, Debug)]
+struct LineCount {
+    /// global offset up to which newlines were counted
+    offset: u64,
+    /// newlines before `offset`
+    lines: u64,
+    /// item that `offset` was in when it was counted
+    item_index: usize,
+    /// newlines before start of `item_index`
+    item_start_lines: u64,
+}
+
+fn count_newlines(bytes: &[u8]) -> u64 {
+    bytes.iter().filter(|&&byte| byte == b'\n').count() as u64
+}
+
+/// Result of `InodeAwareReader::refresh`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RefreshOutcome {
+    /// number of files that appeared since construction or previous refresh
+    pub new_files: usize,
+    /// true if file that used to be the newest one was rotated
+    pub active_file_changed: bool,
+}
+
+/// What `InodeAwareReader` knows about one of its files, see `InodeAwareReader::describe_items`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct ItemDescription {
+    pub index: usize,
+    /// path the file had when it was opened
+    pub path: PathBuf,
+    pub inode: u64,
+    pub size: u64,
+    /// whether the file is currently read
+    pub current: bool,
+}
+
+/// How far reading got through all files, see `InodeAwareReader::progress`.
+///
+/// Displayed as eg. `3/4 files, 87.3%`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Progress {
+    /// global offset of current position
+    pub bytes_read: u64,
+    /// total size of all files at the moment of call
+    pub bytes_total: u64,
+    /// index of file that is currently read, starting from the oldest one
+    pub current_item: usize,
+    pub item_count: usize,
+}
+
+impl Progress {
+    /// Fraction of data that was read, from 0 to 1. Reader without any data is considered fully read.
+    pub fn fraction(&self) -> f64 {
+        match self.bytes_total {
+            0 => 1.0,
+            total => (self.bytes_read as f64 / total as f64).min(1.0),
+        }
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let current_file = (self.current_item + 1).min(self.item_count);
+        write!(
+            f,
+            "{current_file}/{} files, {:.1}%",
+            self.item_count,
+            self.fraction() * 100.0
+        )
+    }
+}
+
+/// Path considered while searching for logs on construction of `InodeAwareReader`, see `ResolutionReport`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResolutionEntry {
+    pub path: PathBuf,
+    pub existed: bool,
+    /// None if file does not exist
+    pub inode: Option<u64>,
+    /// None if file does not exist
+    pub size: Option<u64>,
+    /// why existing file is not read, None if it is read or does not exist
+    pub skipped: Option<SkipReason>,
+}
+
+impl ResolutionEntry {
+    fn probe(path: PathBuf, skipped: Option<SkipReason>) -> io::Result<Self> {
+        let (inode, size) = match fs::metadata(&path) {
+            Ok(metadata) => (Some(file_id(&path)?), Some(metadata.len())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (None, None),
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            existed: inode.is_some(),
+            path,
+            inode,
+            size,
+            skipped,
+        })
+    }
+}
+
+/// Paths considered while searching for logs on construction of `InodeAwareReader`, useful to find out why an expected
+/// rotated log is not read. Obtained with `InodeAwareReader::resolution_report`.
+///
+/// Displayed as eg. `log (inode 12, 40 bytes), log.1 (inode 7, 1024 bytes), log.2 (missing), log.3 (inode 9, 512 bytes,
+/// skipped: after a gap in numbering)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ResolutionReport {
+    /// logs that are read from newest to oldest, followed by paths that were rejected by `RotationScheme`
+    pub entries: Vec<ResolutionEntry>,
+}
+
+impl ResolutionReport {
+    /// Probe found logs and paths rejected by scheme.
+    fn collect(
+        live_path: &Path,
+        found: &[(PathBuf, u64)],
+        rejected: Vec<(PathBuf, Option<SkipReason>)>,
+    ) -> io::Result<Self> {
+        let mut entries = vec![];
+        if found.is_empty() {
+            entries.push(ResolutionEntry::probe(live_path.to_path_buf(), None)?);
+        }
+        for (path, _) in found.iter().rev() {
+            entries.push(ResolutionEntry::probe(path.clone(), None)?);
+        }
+        for (path, skipped) in rejected {
+            entries.push(ResolutionEntry::probe(path, skipped)?);
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl fmt::Display for ResolutionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, entry) in self.entries.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} (", entry.path.display())?;
+            match (entry.inode, entry.size) {
+                (Some(inode), Some(size)) => write!(f, "inode {inode}, {size} bytes")?,
+                _ => f.write_str("missing")?,
+            }
+            if let Some(reason) = entry.skipped {
+                write!(f, ", skipped: {reason}")?;
+            }
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
 }
 
 impl InodeAwareReader {
     /// Construct `InodeAwareMultireader` searching for up to two rotated logs.
     pub fn from_rotated_logs(path: impl AsRef<Path>) -> io::Result<Self> {
-        Self::from_rotated_logs_with_depth(path, 2)
+        Self::builder().path(path).open()
     }
 
     /// Construct `InodeAwareMultireader` searching for up to `max_depth` rotated logs.
+    ///
+    /// With compression features (`gzip`, `zstd`, `xz`) enabled, compressed rotated logs (eg. `path.N.gz`) are found as
+    /// well, see `from_rotated_logs_with_codecs`.
     pub fn from_rotated_logs_with_depth(
         path: impl AsRef<Path>,
         max_depth: usize,
     ) -> io::Result<Self> {
-        let paths_and_inodes = glob_rotated_logs(path, max_depth)?;
-        let (paths, inodes): (Vec<_>, Vec<_>) = paths_and_inodes.into_iter().unzip();
-        let files = paths
+        Self::builder().path(path).search_depth(max_depth).open()
+    }
+
+    /// Like `from_rotated_logs_with_depth`, but allows specifying codecs used to find and decompress compressed rotated logs.
+    ///
+    /// Compressed logs are decompressed into temporary files and keyed by inode of compressed file, so persistent offsets
+    /// into them refer to decompressed content. Uncompressed version of rotated log is preferred if both exist.
+    pub fn from_rotated_logs_with_codecs(
+        path: impl AsRef<Path>,
+        max_depth: usize,
+        codecs: CodecRegistry,
+    ) -> io::Result<Self> {
+        Self::builder()
+            .path(path)
+            .search_depth(max_depth)
+            .codecs(codecs)
+            .open()
+    }
+
+    /// Like `from_rotated_logs_with_depth`, but allows specifying how rotated logs are named (eg. `path-20240115` with
+    /// `dateext` option of logrotate). Up to `max_depth` most recent rotated logs are used.
+    pub fn from_rotated_logs_with_naming(
+        path: impl AsRef<Path>,
+        max_depth: usize,
+        naming: RotationNaming,
+    ) -> io::Result<Self> {
+        Self::from_rotated_logs_with_scheme(path, max_depth, naming)
+    }
+
+    /// Like `from_rotated_logs_with_depth`, but rotated logs are found by custom `scheme` (see `RotationScheme` for
+    /// ordering requirements). Scheme is used again on `refresh`.
+    pub fn from_rotated_logs_with_scheme(
+        path: impl AsRef<Path>,
+        max_depth: usize,
+        scheme: impl RotationScheme + Send + 'static,
+    ) -> io::Result<Self> {
+        Self::builder()
+            .path(path)
+            .search_depth(max_depth)
+            .scheme(scheme)
+            .open()
+    }
+
+    /// Like `from_rotated_logs_with_depth`, but each opened file is read through a buffer of `capacity` bytes instead of
+    /// default 8 KiB. Larger buffers reduce number of syscalls on high-throughput logs, smaller ones save memory when many
+    /// logs are watched.
+    pub fn from_rotated_logs_with_capacity(
+        path: impl AsRef<Path>,
+        max_depth: usize,
+        capacity: usize,
+    ) -> io::Result<Self> {
+        Self::builder()
+            .path(path)
+            .search_depth(max_depth)
+            .buffer_capacity(capacity)
+            .open()
+    }
+
+    /// Like `from_rotated_logs_with_depth`, but also restores previously saved `offset` with `seek_persistent_or`, returning
+    /// how it was restored. `missing` policy is used by later calls to `seek_persistent` as well.
+    ///
+    /// Without offset reading starts from the oldest file and `SeekOutcome::Restored` is reported, since nothing is skipped.
+    ///
+    /// ```rust no_run
+    /// # use std::io;
+    /// # use filetrack::{InodeAwareOffset, InodeAwareReader, MissingInodePolicy, SeekOutcome};
+    /// # fn load_state() -> io::Result<Option<InodeAwareOffset>> {Ok(None)}
+    /// let (reader, outcome) = InodeAwareReader::from_rotated_logs_at(
+    ///     "/var/log/mail.log",
+    ///     2,
+    ///     load_state()?,
+    ///     MissingInodePolicy::StartOfOldest,
+    /// )?;
+    /// if let SeekOutcome::FellBack(_) = outcome {
+    ///     eprintln!("saved position is gone, reading from the oldest log");
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_rotated_logs_at(
+        path: impl AsRef<Path>,
+        max_depth: usize,
+        offset: Option<InodeAwareOffset>,
+        missing: MissingInodePolicy,
+    ) -> io::Result<(Self, SeekOutcome)> {
+        let mut reader = Self::builder()
+            .path(path)
+            .search_depth(max_depth)
+            .missing_inode_policy(missing)
+            .open()?;
+        let outcome = match offset {
+            Some(offset) => reader.seek_persistent_or(offset, missing)?,
+            None => SeekOutcome::Restored,
+        };
+        Ok((reader, outcome))
+    }
+
+    /// Construct `InodeAwareMultireader` searching for up to two rotated logs, reading rotated ones from read-only memory
+    /// maps. Only available with `mmap` feature.
+    ///
+    /// This speeds up scanning large rotated logs, especially with `BufRead` methods, since their whole content is
+    /// available without copying. Newest file is still read through a buffer since it grows, and gets mapped once it is
+    /// rotated and `refresh` is called.
+    ///
+    /// **Note**: mapped file must not be truncated or otherwise shrink while it is mapped. Reads never go past the size file
+    /// had when it was mapped, but its content past the new end is not backed by anything, so accessing it usually kills the
+    /// process with SIGBUS. Logrotate only truncates the newest file (with `copytruncate`), which is never mapped.
+    #[cfg(feature = "mmap")]
+    pub fn from_rotated_logs_mmap(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::builder().path(path).mmap(true).open()
+    }
+
+    /// Start configuring reader with options not covered by other constructors, see `InodeAwareReaderBuilder`.
+    pub fn builder() -> InodeAwareReaderBuilder {
+        InodeAwareReaderBuilder::default()
+    }
+
+    /// Construct `InodeAwareReader` from explicit list of files ordered from oldest to newest, eg. obtained from external
+    /// inventory when rotated logs cannot be found by their names.
+    ///
+    /// Files are opened the same way as by `from_rotated_logs` (compressed ones are decompressed with default codecs).
+    /// Since there is no naming scheme, `refresh` only picks up a new file appearing under the last path. Fails with
+    /// InvalidInput io error if the list is empty or contains the same file (inode) twice, which would break persistent
+    /// indexing.
+    ///
+    /// ```rust
+    /// # use std::{fs, io::Read};
+    /// # use filetrack::InodeAwareReader;
+    /// # let dir = tempfile::tempdir()?;
+    /// # let (archived, current) = (dir.path().join("archived.log"), dir.path().join("mail.log"));
+    /// # fs::write(&archived, "first\n")?;
+    /// # fs::write(&current, "second\n")?;
+    /// let mut reader = InodeAwareReader::from_files([archived, current])?;
+    /// let mut content = String::new();
+    /// reader.read_to_string(&mut content)?;
+    /// assert_eq!(content, "first\nsecond\n");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_files<I: IntoIterator<Item = impl AsRef<Path>>>(paths: I) -> io::Result<Self> {
+        let mut paths_and_inodes: Vec<(PathBuf, u64)> = vec![];
+        for path in paths {
+            let path = path.as_ref().to_path_buf();
+            let inode = file_id(&path)?;
+            if let Some((duplicate, _)) = paths_and_inodes.iter().find(|&&(_, i)| i == inode) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "{} and {} are the same file (inode {inode})",
+                        duplicate.display(),
+                        path.display()
+                    ),
+                ));
+            }
+            paths_and_inodes.push((path, inode));
+        }
+        let Some((newest_path, _)) = paths_and_inodes.last() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no files were provided",
+            ));
+        };
+        let newest_path = newest_path.clone();
+        let no_rotated = |_: &Path, _: usize| -> io::Result<Vec<PathBuf>> { Ok(vec![]) };
+        Self::open_files(
+            paths_and_inodes,
+            newest_path,
+            0,
+            Box::new(no_rotated),
+            CodecRegistry::default(),
+            DEFAULT_BUFFER_CAPACITY,
+            false,
+        )
+    }
+
+    /// Construct `InodeAwareReader` from already opened files with made up inodes, allowing to simulate rotation scenarios
+    /// deterministically (eg. with anonymous `tempfile::tempfile()` items) in tests of code that consumes this reader.
+    ///
+    /// **Not for production use**: readers are not associated with any path, so `refresh` fails and auto refresh does
+    /// nothing. Fails with InvalidInput io error if lengths of `items` and `inodes` differ or inodes are not unique.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn from_readers_with_inodes(
+        items: Vec<io::BufReader<std::fs::File>>,
+        inodes: Vec<u64>,
+    ) -> io::Result<Self> {
+        if items.len() != inodes.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("got {} items but {} inodes", items.len(), inodes.len()),
+            ));
+        }
+        let item_indices = index_inodes(&inodes)?;
+        let items = items
             .into_iter()
-            .map(|path| -> io::Result<BufReader<File>> { Ok(BufReader::new(File::open(path)?)) })
-            .collect::<io::Result<Vec<BufReader<File>>>>()?;
-        let multireader = Multireader::new(files)?;
+            .zip(&inodes)
+            .map(|(item, &inode)| LogFile::from_reader(item, inode))
+            .collect();
+        let mut multireader = Multireader::new(items)?;
+        measure_rotated_files(&mut multireader)?;
+        let fingerprints = vec![None; inodes.len()];
+        let no_rotated = |_: &Path, _: usize| -> io::Result<Vec<PathBuf>> { Ok(vec![]) };
+        Ok(Self {
+            inner: multireader,
+            paths: vec![PathBuf::new(); inodes.len()],
+            inodes,
+            item_indices,
+            path: PathBuf::new(),
+            max_depth: 0,
+            scheme: Box::new(no_rotated),
+            codecs: CodecRegistry::new(),
+            auto_refresh: false,
+            detect_truncation: false,
+            auto_release: false,
+            fingerprint_length: None,
+            fingerprints,
+            truncation_policy: TruncationPolicy::default(),
+            missing_inode_policy: MissingInodePolicy::default(),
+            record_file_stamps: false,
+            #[cfg(feature = "mmap")]
+            map_rotated: false,
+            allow_missing: false,
+            symlink_policy: SymlinkPolicy::default(),
+            skipped_files: vec![],
+            resolution_report: ResolutionReport::default(),
+            line_tracking: false,
+            line_count: None,
+            max_line_length: None,
+            unfinished_skip: None,
+            buffer_capacity: DEFAULT_BUFFER_CAPACITY,
+        })
+    }
+
+    /// Open files found by `scheme` (or provided explicitly) and remember how to find them again on refresh.
+    fn open_files(
+        paths_and_inodes: Vec<(PathBuf, u64)>,
+        path: impl AsRef<Path>,
+        max_depth: usize,
+        scheme: Box<dyn RotationScheme + Send>,
+        codecs: CodecRegistry,
+        buffer_capacity: usize,
+        lazy: bool,
+    ) -> io::Result<Self> {
+        let (paths, inodes): (Vec<_>, Vec<_>) = paths_and_inodes.into_iter().unzip();
+        let item_indices = index_inodes(&inodes)?;
+        let multireader = match lazy {
+            true => open_lazily(&paths, &inodes, buffer_capacity, &codecs)?,
+            false => {
+                let files = paths
+                    .iter()
+                    .zip(&inodes)
+                    .map(|(path, &inode)| LogFile::open(path, inode, buffer_capacity, &codecs))
+                    .collect::<io::Result<Vec<_>>>()?;
+                let mut multireader = Multireader::new(files)?;
+                measure_rotated_files(&mut multireader)?;
+                multireader
+            }
+        };
+
+        let fingerprints = vec![None; inodes.len()];
 
         Ok(Self {
             inner: multireader,
             inodes,
+            item_indices,
+            paths,
+            path: path.as_ref().to_path_buf(),
+            max_depth,
+            scheme,
+            codecs,
+            auto_refresh: false,
+            detect_truncation: false,
+            auto_release: false,
+            fingerprint_length: None,
+            fingerprints,
+            truncation_policy: TruncationPolicy::default(),
+            missing_inode_policy: MissingInodePolicy::default(),
+            record_file_stamps: false,
+            #[cfg(feature = "mmap")]
+            map_rotated: false,
+            allow_missing: false,
+            symlink_policy: SymlinkPolicy::default(),
+            skipped_files: vec![],
+            resolution_report: ResolutionReport::default(),
+            line_tracking: false,
+            line_count: None,
+            max_line_length: None,
+            unfinished_skip: None,
+            buffer_capacity,
+        })
+    }
+
+    /// Search for rotated logs again and append files that appeared since construction (or previous refresh), allowing
+    /// to keep reading after the log was rotated without losing position.
+    ///
+    /// Files that were already known are kept open even if they were moved or removed. Persistent position is preserved.
+    pub fn refresh(&mut self) -> io::Result<RefreshOutcome> {
+        let paths_and_inodes = find_logs(
+            &self.path,
+            self.max_depth,
+            self.scheme.as_ref(),
+            &self.codecs,
+            self.allow_missing,
+            self.symlink_policy,
+        )?;
+        let newest_known_inode = self.inodes.last().cloned();
+        // files following the newest known one are new, if it is gone we can only rely on inodes we do not know
+        let new_files_start = paths_and_inodes
+            .iter()
+            .rposition(|&(_, inode)| Some(inode) == newest_known_inode)
+            .map(|position| position + 1)
+            .unwrap_or_default();
+        let new_files = paths_and_inodes[new_files_start..]
+            .iter()
+            .filter(|(_, inode)| !self.item_indices.contains_key(inode))
+            .map(|(path, inode)| {
+                Ok((
+                    LogFile::open(path, *inode, self.buffer_capacity, &self.codecs)?,
+                    path.clone(),
+                    *inode,
+                ))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let outcome = RefreshOutcome {
+            new_files: new_files.len(),
+            active_file_changed: paths_and_inodes.last().map(|&(_, inode)| inode)
+                != newest_known_inode,
+        };
+        if new_files.is_empty() {
+            return Ok(outcome);
+        }
+        let position = (!self.inodes.is_empty()).then(|| self.get_persistent_offset());
+        for (file, path, inode) in new_files {
+            self.inner.push(file)?;
+            // same file may be found twice while being rotated, index of the first one is kept
+            self.item_indices.entry(inode).or_insert(self.inodes.len());
+            self.inodes.push(inode);
+            self.paths.push(path);
+            let fingerprint = match self.fingerprint_length {
+                Some(length) => self.compute_fingerprint(self.inodes.len() - 1, length)?,
+                None => None,
+            };
+            self.fingerprints.push(fingerprint);
+        }
+        measure_rotated_files(&mut self.inner)?;
+        #[cfg(feature = "mmap")]
+        self.map_rotated_files()?;
+        if let Some(position) = position {
+            self.seek_persistent(position)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Enable or disable automatic refresh on EOF (disabled by default).
+    ///
+    /// When enabled, a read that reaches the end of the newest file checks whether the original path now points to another
+    /// file and, if so, performs `refresh` and continues reading from the new file. Refresh is attempted at most once per
+    /// read, so reaching EOF when there is genuinely no data left is cheap and does not loop.
+    pub fn set_auto_refresh(&mut self, enabled: bool) {
+        self.auto_refresh = enabled;
+    }
+
+    /// Check whether automatic refresh on EOF is enabled, see `set_auto_refresh`.
+    pub fn get_auto_refresh(&self) -> bool {
+        self.auto_refresh
+    }
+
+    /// Enable or disable detection of truncation of current file on EOF (disabled by default).
+    ///
+    /// When enabled, a read that returns nothing (after auto refresh, if enabled) checks size of current file. If it is
+    /// smaller than local offset, eg. because log was truncated by `copytruncate` option of logrotate, InvalidData io error
+    /// carrying `TruncationDetected` is returned instead of EOF until `rewind_after_truncation` is called. Truncation
+    /// cannot be noticed if the file already grew past local offset again.
+    pub fn set_detect_truncation(&mut self, enabled: bool) {
+        self.detect_truncation = enabled;
+    }
+
+    /// Check whether truncation is detected on EOF, see `set_detect_truncation`.
+    pub fn get_detect_truncation(&self) -> bool {
+        self.detect_truncation
+    }
+
+    /// Seek to the start of current file to read it again after `TruncationDetected` was reported, returning new global
+    /// offset.
+    pub fn rewind_after_truncation(&mut self) -> io::Result<u64> {
+        self.inner.rewind_current_item()
+    }
+
+    /// Fail with InvalidData io error carrying `TruncationDetected` if detection is enabled and current file is smaller
+    /// than local offset.
+    fn check_truncation(&self) -> io::Result<()> {
+        if !self.detect_truncation || self.is_empty() {
+            return Ok(());
+        }
+        let item_index = self.get_current_item_index();
+        let previous_offset = self.inner.get_local_offset();
+        let new_size = self.stat_item(item_index)?.len();
+        if new_size >= previous_offset {
+            return Ok(());
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            TruncationDetected {
+                inode: self.inodes[item_index],
+                path: self.paths[item_index].clone(),
+                previous_offset,
+                new_size,
+            },
+        ))
+    }
+
+    /// Limit length of lines read by `BufRead` methods (`read_line`, `read_until`, `lines` and `split`) to `length` bytes
+    /// including delimiter, or remove the limit with None (default).
+    ///
+    /// Longer line is not kept in memory: it is skipped up to and including its delimiter and InvalidData io error carrying
+    /// `LineTooLong` is returned, so that reading may continue from the next line. Nothing is appended to the buffer in
+    /// that case. If line is not complete yet, the rest of it is skipped once it is written, so that it is not mistaken for
+    /// a line of its own. Same rules as for `Multireader::read_until_limited` apply.
+    pub fn set_max_line_length(&mut self, length: Option<usize>) {
+        self.max_line_length = length;
+    }
+
+    /// Get line length limit set by `set_max_line_length`.
+    pub fn get_max_line_length(&self) -> Option<usize> {
+        self.max_line_length
+    }
+
+    /// Read up to and including `delim` through `fill_buf` and `consume`, skipping record longer than `max` bytes.
+    fn read_until_limited(
+        &mut self,
+        delim: u8,
+        buf: &mut Vec<u8>,
+        max: usize,
+    ) -> io::Result<usize> {
+        let mut unfinished_skip = self.unfinished_skip.take();
+        let record = read_record_limited(
+            self,
+            &mut unfinished_skip,
+            |reader| reader.inner.get_global_offset(),
+            |reader| reader.to_persistent(reader.inner.get_global_offset()),
+            delim,
+            buf,
+            max,
+        );
+        self.unfinished_skip = unfinished_skip;
+        match record? {
+            LimitedRecord::Read(size_read) => Ok(size_read),
+            LimitedRecord::TooLong { start, length } => {
+                let start = start.ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("line longer than {max} bytes starts outside of read files"),
+                    )
+                })?;
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    LineTooLong { start, length, max },
+                ))
+            }
+        }
+    }
+
+    /// Capacity of buffer of each file opened by this reader, see `InodeAwareReaderBuilder::buffer_capacity`.
+    pub fn get_buffer_capacity(&self) -> usize {
+        self.buffer_capacity
+    }
+
+    /// Refresh if auto refresh is enabled and original path points to an unknown file. Returns true if new files were added.
+    fn refresh_on_eof(&mut self) -> io::Result<bool> {
+        if !self.auto_refresh {
+            return Ok(false);
+        }
+        let newest_inode = match file_id(&self.path) {
+            Ok(inode) => inode,
+            // log may be absent for a moment during rotation
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        if self.inodes.last() == Some(&newest_inode) {
+            return Ok(false);
+        }
+        Ok(self.refresh()?.new_files > 0)
+    }
+
+    /// Add the newest log if it appeared after reader was created with `InodeAwareReaderBuilder::allow_missing`, along with
+    /// any new rotated logs. Returns true if the log exists, ie. reader is attached to it.
+    ///
+    /// Auto refresh (see `set_auto_refresh`) attaches to the log on EOF as well.
+    pub fn try_attach(&mut self) -> io::Result<bool> {
+        match file_id(&self.path) {
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        }
+        self.refresh()?;
+        Ok(true)
+    }
+
+    /// Follow the log like `tail -F`: iterate over lines, waiting for new data on EOF and picking up rotations.
+    ///
+    /// On EOF reader sleeps for `poll` and then checks for both growth of the newest file and rotation (see `refresh`).
+    /// Iteration never ends on its own, use `Follow::with_deadline` or `Follow::with_stop_flag` to stop it. Incomplete last
+    /// line is not yielded until it is terminated, reader is left at its start meanwhile so persistent offset never points
+    /// into the middle of a line.
+    ///
+    /// ```rust no_run
+    /// # use std::time::{Duration, Instant};
+    /// # use filetrack::InodeAwareReader;
+    /// let mut reader = InodeAwareReader::from_rotated_logs("/var/log/mail.log")?;
+    /// let deadline = Instant::now() + Duration::from_secs(60);
+    /// for line in reader.follow(Duration::from_millis(100)).with_deadline(deadline) {
+    ///     println!("{}", line?);
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn follow(&mut self, poll: Duration) -> Follow<'_> {
+        Follow {
+            reader: self,
+            poll,
+            deadline: None,
+            stop: None,
+        }
+    }
+
+    /// Iterate over lines (without trailing newline) together with persistent offsets of their first bytes, eg. to
+    /// acknowledge each line precisely. Line continuing from one file into the next carries offset into the first one.
+    ///
+    /// ```rust no_run
+    /// # use filetrack::InodeAwareReader;
+    /// let mut reader = InodeAwareReader::from_rotated_logs("/var/log/mail.log")?;
+    /// for line in reader.lines_persistent() {
+    ///     let (offset, line) = line?;
+    ///     println!("{}:{} {}", offset.inode, offset.offset, line);
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn lines_persistent(
+        &mut self,
+    ) -> impl Iterator<Item = io::Result<(InodeAwareOffset, String)>> + '_ {
+        std::iter::from_fn(move || {
+            // derived from cached index, so no seek is performed
+            let offset = self.get_persistent_offset();
+            let mut line = String::new();
+            match self.read_line(&mut line) {
+                Ok(0) => None,
+                Ok(_) => {
+                    if line.ends_with('\n') {
+                        line.pop();
+                        if line.ends_with('\r') {
+                            line.pop();
+                        }
+                    }
+                    Some(Ok((offset, line)))
+                }
+                Err(e) => Some(Err(e)),
+            }
         })
     }
 
     /// Get offset that can be used across restarts and log rotations.
+    ///
+    /// Offset includes fingerprint of current file if fingerprinting is enabled, see `set_fingerprint_length`.
     pub fn get_persistent_offset(&self) -> InodeAwareOffset {
-        let inode = self.get_current_inode();
+        let item_index = self.get_current_item_index();
+        let Some(&inode) = self.inodes.get(item_index) else {
+            // reader waiting for the log to appear, see `InodeAwareReaderBuilder::allow_missing`
+            return InodeAwareOffset::default();
+        };
         let offset = self.get_local_offset();
-        InodeAwareOffset { inode, offset }
+        let fingerprint = self.fingerprints.get(item_index).cloned().flatten();
+        let file_stamp = match self.record_file_stamps {
+            true => self.file_stamp(item_index).ok(),
+            false => None,
+        };
+        InodeAwareOffset {
+            inode,
+            offset,
+            fingerprint,
+            file_stamp,
+        }
     }
 
-    /// Seek by persistent offset.
+    /// Enable or disable recording of size and modification time of current file in persistent offsets (disabled by
+    /// default).
     ///
-    /// Will return NotFound io error if file with given inode was not found.
-    pub fn seek_persistent(&mut self, offset: InodeAwareOffset) -> io::Result<()> {
-        let Some(inode_index) = self.get_item_index_by_inode(offset.inode) else {
-            return Err(io::Error::new(
-                io::ErrorKind::NotFound,
-                "provided inode does not exist",
-            ));
-        };
-        self.seek_by_local_index(inode_index, io::SeekFrom::Start(offset.offset))?;
-        Ok(())
+    /// On restore, file found by inode is only accepted if it did not shrink and was not modified earlier than recorded.
+    /// Otherwise inode is considered reused by an unrelated file and it is treated as unknown (see
+    /// `set_missing_inode_policy`). Note that this includes files truncated in place, so `TruncationPolicy` only applies
+    /// to offsets without file stamps.
+    pub fn set_record_file_stamps(&mut self, enabled: bool) {
+        self.record_file_stamps = enabled;
     }
 
-    /// Get slice of inodes for current execution.
-    pub fn get_inodes(&self) -> &[u64] {
-        &self.inodes
+    /// Check whether file stamps are recorded, see `set_record_file_stamps`.
+    pub fn get_record_file_stamps(&self) -> bool {
+        self.record_file_stamps
     }
 
-    // Destroy struct and return underlying reader and inodes.
-    pub fn into_inner(self) -> (Multireader<BufReader<File>>, Vec<u64>) {
-        (self.inner, self.inodes)
+    fn file_stamp(&self, item_index: usize) -> io::Result<FileStamp> {
+        let item = self
+            .inner
+            .get_item(item_index)
+            .expect("item index should be valid");
+        FileStamp::from_metadata(&item.metadata()?)
     }
 
-    /// Get inode of an item that is currently read.
-    pub fn get_current_inode(&self) -> u64 {
-        let item_index = self.get_current_item_index();
-        self.inodes[item_index]
+    /// Seek by persistent offset.
+    ///
+    /// If no file has given inode but offset carries a fingerprint, file with matching content is searched for instead.
+    /// Will return NotFound io error mentioning the log path if file was found neither by inode nor by fingerprint.
+    ///
+    /// If offset is past the end of the file, the file is considered truncated and `TruncationPolicy` (see
+    /// `set_truncation_policy`) decides where to seek. Detected truncation is returned so that it can be reported.
+    ///
+    /// Fallback for missing files can be configured with `set_missing_inode_policy`, use `seek_persistent_or` to find out
+    /// whether it was applied.
+    pub fn seek_persistent(&mut self, offset: InodeAwareOffset) -> io::Result<Option<Truncation>> {
+        match self.seek_persistent_or(offset, self.missing_inode_policy)? {
+            SeekOutcome::Truncated(truncation) => Ok(Some(truncation)),
+            SeekOutcome::Restored | SeekOutcome::FellBack(_) => Ok(None),
+        }
     }
 
-    /// Search for item index by given inode.
-    pub fn get_item_index_by_inode(&self, inode: u64) -> Option<usize> {
-        self.get_inodes()
-            .iter()
-            .cloned()
-            .enumerate()
-            .find(|&(_, i)| i == inode)
-            .map(|(idx, _)| idx)
+    /// Like `seek_persistent`, but if file of `offset` cannot be found (typically because reader was not run for longer
+    /// than rotation window), `fallback` decides what to do instead of failing.
+    ///
+    /// Returned `SeekOutcome` tells whether exact position was restored, so that possibly skipped data can be reported.
+    pub fn seek_persistent_or(
+        &mut self,
+        offset: InodeAwareOffset,
+        fallback: MissingInodePolicy,
+    ) -> io::Result<SeekOutcome> {
+        let Some(item_index) = self.find_persistent_item(offset)? else {
+            match fallback {
+                MissingInodePolicy::StartOfOldest => self.seek_to_oldest()?,
+                MissingInodePolicy::EndOfNewest => self.seek_to_latest()?,
+                MissingInodePolicy::Error => return Err(self.missing_inode_error(offset.inode)),
+            };
+            return Ok(SeekOutcome::FellBack(fallback));
+        };
+        Ok(match self.seek_persistent_in_item(item_index, offset)? {
+            Some(truncation) => SeekOutcome::Truncated(truncation),
+            None => SeekOutcome::Restored,
+        })
     }
 
-    /// Compare two offsets as if they were pointing into one large buffer. Returns None if any of the offsets do not belong
-    /// to underlying files.
-    pub fn compare_offsets(
-        &self,
-        first: InodeAwareOffset,
-        second: InodeAwareOffset,
-    ) -> Option<Ordering> {
-        let first_index = self.get_item_index_by_inode(first.inode)?;
-        let second_index = self.get_item_index_by_inode(second.inode)?;
-        if first_index == second_index {
-            return Some(first.offset.cmp(&second.offset));
+    fn missing_inode_error(&self, inode: u64) -> io::Error {
+        let mut message = format!(
+            "provided inode {inode} does not exist among rotated versions of {}",
+            self.path.display()
+        );
+        if !self.resolution_report.entries.is_empty() {
+            message += &format!(", found on construction: {}", self.resolution_report);
         }
-        Some(first_index.cmp(&second_index))
+        io::Error::new(io::ErrorKind::NotFound, message)
     }
-}
 
-impl Deref for InodeAwareReader {
-    type Target = Multireader<BufReader<File>>;
+    /// Check whether `offset` can be restored by `seek_persistent` exactly, without changing current position.
+    ///
+    /// File is searched for the same way as by `seek_persistent`, ie. by inode and then by fingerprint.
+    pub fn validate_offset(&mut self, offset: InodeAwareOffset) -> io::Result<OffsetValidity> {
+        let Some(item_index) = self.find_persistent_item(offset)? else {
+            return Ok(OffsetValidity::UnknownInode);
+        };
+        let file_size = self.inner.get_item_size(item_index)?;
+        if offset.offset > file_size {
+            return Ok(OffsetValidity::PastEndOfFile { file_size });
+        }
+        Ok(OffsetValidity::Valid)
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.inner
+    /// Find item by inode of persistent offset, falling back to its fingerprint.
+    /// File found by inode is rejected if it does not match file stamp of the offset.
+    fn find_persistent_item(&mut self, offset: InodeAwareOffset) -> io::Result<Option<usize>> {
+        if let Some(item_index) = self.get_item_index_by_inode(offset.inode) {
+            let same_file = match offset.file_stamp {
+                Some(recorded) => recorded.may_be_followed_by(&self.file_stamp(item_index)?),
+                None => true,
+            };
+            if same_file {
+                return Ok(Some(item_index));
+            }
+        }
+        match offset.fingerprint {
+            Some(fingerprint) => self.get_item_index_by_fingerprint(fingerprint),
+            None => Ok(None),
+        }
     }
-}
 
-impl DerefMut for InodeAwareReader {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.inner
+    fn seek_persistent_in_item(
+        &mut self,
+        item_index: usize,
+        offset: InodeAwareOffset,
+    ) -> io::Result<Option<Truncation>> {
+        let size = self.inner.get_item_size(item_index)?;
+        if offset.offset <= size {
+            self.seek_by_local_index(item_index, io::SeekFrom::Start(offset.offset))?;
+            return Ok(None);
+        }
+        let truncation = Truncation {
+            inode: self.inodes[item_index],
+            path: self.paths[item_index].clone(),
+            offset: offset.offset,
+            size,
+        };
+        let local_offset = match self.truncation_policy {
+            TruncationPolicy::RestartFromBeginning => 0,
+            TruncationPolicy::SeekToEnd => size,
+            TruncationPolicy::Error => {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, truncation))
+            }
+        };
+        self.seek_by_local_index(item_index, io::SeekFrom::Start(local_offset))?;
+        Ok(Some(truncation))
+    }
+
+    /// Enable or disable automatic release of files once cursor leaves them behind (disabled by default), so that at most
+    /// one file is kept open while reading sequentially. See `release_consumed`.
+    pub fn set_auto_release(&mut self, enabled: bool) {
+        self.auto_release = enabled;
+    }
+
+    /// Check whether files are released once read, see `set_auto_release`.
+    pub fn get_auto_release(&self) -> bool {
+        self.auto_release
+    }
+
+    /// Release files behind cursor if auto release is enabled.
+    fn release_left_behind(&mut self) -> io::Result<()> {
+        if !self.auto_release {
+            return Ok(());
+        }
+        self.release_consumed().map(|_| ())
+    }
+
+    /// Close files that are entirely behind current position to free their descriptors, returning how many were closed.
+    ///
+    /// Sizes of closed files are kept, so global and persistent offsets stay valid. Seeking back into a closed file reopens
+    /// it by path, looking it up among rotated logs if it was rotated further. Reading fails with NotFound io error if the
+    /// file was removed.
+    pub fn release_consumed(&mut self) -> io::Result<usize> {
+        let mut released = 0;
+        for item_index in 0..self.get_current_item_index() {
+            // sizes of items before current one are always known, so released items are never measured again
+            let item = self
+                .inner
+                .get_item_mut(item_index)
+                .expect("item index should be valid");
+            if item.release()? {
+                released += 1;
+            }
+        }
+        Ok(released)
+    }
+
+    /// Open current file anew, eg. after reading it failed because it was deleted on a network filesystem.
+    ///
+    /// File is looked up by its inode at the path it was last seen at and then among rotated logs, in case it was rotated
+    /// further. Reopened file is read from the same local offset.
+    pub fn reopen_current(&mut self) -> io::Result<ReopenOutcome> {
+        let item_index = self.get_current_item_index();
+        let Some(&inode) = self.inodes.get(item_index) else {
+            return Ok(ReopenOutcome::Gone);
+        };
+        let Some(path) = self.locate_inode(item_index, inode)? else {
+            return Ok(ReopenOutcome::Gone);
+        };
+        let mut file = LogFile::open(&path, inode, self.buffer_capacity, &self.codecs)?;
+        #[cfg(feature = "mmap")]
+        if self.map_rotated && item_index + 1 < self.len() {
+            file.map()?;
+        }
+        let size = file.seek(io::SeekFrom::End(0))?;
+        let local_offset = self.inner.get_local_offset();
+        // replaced item is positioned at local offset on next read
+        *self
+            .inner
+            .get_item_mut(item_index)
+            .expect("item index should be valid") = file;
+        self.paths[item_index] = path;
+        if size < local_offset {
+            return Ok(ReopenOutcome::Shrunk { size });
+        }
+        Ok(ReopenOutcome::Reopened)
+    }
+
+    /// Read into `buf`, reopening current file with `reopen_current` and retrying once if reading fails.
+    ///
+    /// If current file is gone, the rest of it is skipped and reading continues from the start of the next file. Original
+    /// error is returned if there is no next file.
+    pub fn read_resilient(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let error = match self.read(buf) {
+            Err(e) if e.kind() != io::ErrorKind::Interrupted => e,
+            result => return result,
+        };
+        if self.reopen_current()? == ReopenOutcome::Gone
+            && self.inner.skip_to_next_item()?.is_none()
+        {
+            return Err(error);
+        }
+        self.read(buf)
+    }
+
+    /// Read the rest of current file into `buf`, returning number of bytes read. Useful to finish processing of one file
+    /// before moving on to the next one.
+    ///
+    /// Current file is measured first, so data appended to the newest file meanwhile is left for later reads. Auto refresh
+    /// is not performed.
+    pub fn read_current_item_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let remaining = self.inner.remaining_in_current_item()?;
+        self.inner.take_scoped(remaining).read_to_end(buf)
+    }
+
+    /// Call `f` with each line of the rest of current file (including line break, if any), returning number of lines. See
+    /// `read_current_item_to_end`.
+    ///
+    /// Last line of the newest file may be incomplete if it is still being written. Fails with InvalidData io error if a
+    /// line is not valid UTF-8.
+    pub fn for_each_line_in_current_item(&mut self, mut f: impl FnMut(&str)) -> io::Result<usize> {
+        let remaining = self.inner.remaining_in_current_item()?;
+        let mut reader = self.inner.take_scoped(remaining);
+        let mut line = String::new();
+        let mut line_count = 0;
+        while reader.read_line(&mut line)? > 0 {
+            f(&line);
+            line.clear();
+            line_count += 1;
+        }
+        Ok(line_count)
+    }
+
+    /// Memory-map all files except for the newest one if enabled, see `from_rotated_logs_mmap`.
+    #[cfg(feature = "mmap")]
+    fn map_rotated_files(&mut self) -> io::Result<()> {
+        if !self.map_rotated {
+            return Ok(());
+        }
+        for item_index in 0..self.len().saturating_sub(1) {
+            if self
+                .inner
+                .get_item(item_index)
+                .is_some_and(LogFile::is_mapped)
+            {
+                continue;
+            }
+            self.inner
+                .get_item_mut(item_index)
+                .expect("item index should be valid")
+                .map()?;
+        }
+        Ok(())
+    }
+
+    /// Find path of file with `inode`, checking the path item was opened from first.
+    fn locate_inode(&self, item_index: usize, inode: u64) -> io::Result<Option<PathBuf>> {
+        let known_path = &self.paths[item_index];
+        if file_id(known_path).ok() == Some(inode) {
+            return Ok(Some(known_path.clone()));
+        }
+        Ok(self
+            .find_current_logs()?
+            .into_iter()
+            .find(|&(_, found_inode)| found_inode == inode)
+            .map(|(path, _)| path))
+    }
+
+    /// Search for the newest log and its rotated versions as they are now, empty list if there are none.
+    fn find_current_logs(&self) -> io::Result<Vec<(PathBuf, u64)>> {
+        match find_logs(
+            &self.path,
+            self.max_depth,
+            self.scheme.as_ref(),
+            &self.codecs,
+            self.allow_missing,
+            self.symlink_policy,
+        ) {
+            Ok(paths_and_inodes) => Ok(paths_and_inodes),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Point released files that are no longer at their recorded paths to where they are now, eg. if they were rotated
+    /// before being opened lazily. Returns whether any file was found elsewhere.
+    fn relocate_released_files(&mut self) -> io::Result<bool> {
+        let moved = (0..self.inner.len())
+            .filter(|&item_index| {
+                self.inner
+                    .get_item(item_index)
+                    .is_some_and(LogFile::is_released)
+                    && file_id(&self.paths[item_index]).ok() != Some(self.inodes[item_index])
+            })
+            .collect::<Vec<_>>();
+        if moved.is_empty() {
+            return Ok(false);
+        }
+        let paths_and_inodes = self.find_current_logs()?;
+        let mut relocated = false;
+        for item_index in moved {
+            let inode = self.inodes[item_index];
+            let Some((path, _)) = paths_and_inodes.iter().find(|&&(_, found)| found == inode)
+            else {
+                continue;
+            };
+            self.inner
+                .get_item_mut(item_index)
+                .expect("item index should be valid")
+                .relocate(path);
+            self.paths[item_index] = path.clone();
+            relocated = true;
+        }
+        Ok(relocated)
+    }
+
+    /// Run `f` on underlying reader, retrying once if it failed with NotFound io error and some released files were
+    /// found elsewhere by `relocate_released_files`.
+    fn with_relocation<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Multireader<LogFile>) -> io::Result<T>,
+    ) -> io::Result<T> {
+        match f(&mut self.inner) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                match self.relocate_released_files()? {
+                    true => f(&mut self.inner),
+                    false => Err(e),
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// Seek to the end of the newest file, skipping everything written so far, and return resulting persistent offset so
+    /// that it can be saved right away.
+    ///
+    /// Offset always refers to the newest file, even if it is empty.
+    pub fn seek_to_latest(&mut self) -> io::Result<InodeAwareOffset> {
+        let last_index = self.len().saturating_sub(1);
+        self.seek_by_local_index(last_index, io::SeekFrom::End(0))?;
+        Ok(self.get_persistent_offset())
+    }
+
+    /// Seek to the start of the oldest file to reprocess everything, returning resulting persistent offset.
+    pub fn seek_to_oldest(&mut self) -> io::Result<InodeAwareOffset> {
+        self.seek_by_local_index(0, io::SeekFrom::Start(0))?;
+        Ok(self.get_persistent_offset())
+    }
+
+    /// Seek to the start of the file with given inode (eg. to reprocess it), returning resulting persistent offset.
+    ///
+    /// Will return NotFound io error if there is no file with given inode.
+    pub fn seek_to_inode_start(&mut self, inode: u64) -> io::Result<InodeAwareOffset> {
+        let item_index = self.require_item_index_by_inode(inode)?;
+        self.seek_to_item_start(item_index)?;
+        Ok(self.get_persistent_offset())
+    }
+
+    /// Seek to the end of the file with given inode, returning resulting persistent offset.
+    ///
+    /// End of any file except the newest one is the same position as the start of the next file, and returned offset
+    /// refers to the latter.
+    ///
+    /// Will return NotFound io error if there is no file with given inode.
+    pub fn seek_to_inode_end(&mut self, inode: u64) -> io::Result<InodeAwareOffset> {
+        let item_index = self.require_item_index_by_inode(inode)?;
+        self.seek_by_local_index(item_index, io::SeekFrom::End(0))?;
+        Ok(self.get_persistent_offset())
+    }
+
+    fn require_item_index_by_inode(&self, inode: u64) -> io::Result<usize> {
+        self.get_item_index_by_inode(inode).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("file with inode {inode} is not among read files"),
+            )
+        })
+    }
+
+    /// Set what `seek_persistent` does when offset points past the end of file, see `TruncationPolicy`.
+    pub fn set_truncation_policy(&mut self, policy: TruncationPolicy) {
+        self.truncation_policy = policy;
+    }
+
+    /// Get policy set by `set_truncation_policy`.
+    pub fn get_truncation_policy(&self) -> TruncationPolicy {
+        self.truncation_policy
+    }
+
+    /// Set what `seek_persistent` does when file of persistent offset cannot be found (fails by default).
+    pub fn set_missing_inode_policy(&mut self, policy: MissingInodePolicy) {
+        self.missing_inode_policy = policy;
+    }
+
+    /// Get policy set by `set_missing_inode_policy`.
+    pub fn get_missing_inode_policy(&self) -> MissingInodePolicy {
+        self.missing_inode_policy
+    }
+
+    /// Enable fingerprinting of files using up to `length` first bytes (1-4 KB is usually enough), or disable it with None.
+    ///
+    /// Fingerprints are computed right away and for files found on `refresh`, and are then included into persistent
+    /// offsets. This allows `seek_persistent` to find a file whose inode changed, eg. because it was copied. Files that are
+    /// empty at the moment of fingerprinting get no fingerprint.
+    pub fn set_fingerprint_length(&mut self, length: Option<usize>) -> io::Result<()> {
+        self.fingerprints = match length {
+            Some(length) => self
+                .compute_fingerprints(length)?
+                .into_iter()
+                .map(Some)
+                .collect(),
+            None => vec![None; self.inodes.len()],
+        };
+        // empty files cannot be told apart
+        for fingerprint in self.fingerprints.iter_mut() {
+            if fingerprint.is_some_and(|fingerprint| fingerprint.prefix_len == 0) {
+                *fingerprint = None;
+            }
+        }
+        self.fingerprint_length = length;
+        Ok(())
+    }
+
+    /// Get fingerprint length set by `set_fingerprint_length`.
+    pub fn get_fingerprint_length(&self) -> Option<usize> {
+        self.fingerprint_length
+    }
+
+    /// Compute fingerprints of all files using up to `length` first bytes. Current position is preserved.
+    pub fn compute_fingerprints(&mut self, length: usize) -> io::Result<Vec<Fingerprint>> {
+        let position = self.stream_position()?;
+        let fingerprints = (0..self.inodes.len())
+            .map(|item_index| self.fingerprint_item(item_index, length as u64))
+            .collect::<io::Result<Vec<_>>>();
+        self.seek(io::SeekFrom::Start(position))?;
+        fingerprints
+    }
+
+    /// Compute fingerprint of a single item, returning None for empty files. Position of item is not restored.
+    fn compute_fingerprint(
+        &mut self,
+        item_index: usize,
+        length: usize,
+    ) -> io::Result<Option<Fingerprint>> {
+        let fingerprint = self.fingerprint_item(item_index, length as u64)?;
+        Ok((fingerprint.prefix_len > 0).then_some(fingerprint))
+    }
+
+    fn fingerprint_item(&mut self, item_index: usize, length: u64) -> io::Result<Fingerprint> {
+        let item = self
+            .inner
+            .get_item_mut(item_index)
+            .expect("item index should be valid");
+        let size = item.seek(io::SeekFrom::End(0))?;
+        item.rewind()?;
+        let mut prefix = vec![];
+        item.take(length).read_to_end(&mut prefix)?;
+        Ok(Fingerprint {
+            hash: fnv1a(&prefix),
+            prefix_len: prefix.len() as u64,
+            size,
+        })
+    }
+
+    /// Search for item whose content starts the same way as fingerprinted file did. Current position is preserved.
+    fn get_item_index_by_fingerprint(
+        &mut self,
+        fingerprint: Fingerprint,
+    ) -> io::Result<Option<usize>> {
+        let position = self.stream_position()?;
+        let mut found = None;
+        for item_index in 0..self.inodes.len() {
+            let candidate = self.fingerprint_item(item_index, fingerprint.prefix_len)?;
+            if candidate.size >= fingerprint.size
+                && candidate.prefix_len == fingerprint.prefix_len
+                && candidate.hash == fingerprint.hash
+            {
+                found = Some(item_index);
+                break;
+            }
+        }
+        self.seek(io::SeekFrom::Start(position))?;
+        Ok(found)
+    }
+
+    /// Get slice of inodes for current execution.
+    pub fn get_inodes(&self) -> &[u64] {
+        &self.inodes
+    }
+
+    /// Get paths of underlying files in the same order as inodes. Paths are recorded when files are opened, so rotation
+    /// that happened later is not reflected. For compressed logs this is the path of compressed file.
+    pub fn get_paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Get paths that were considered while searching for logs on construction, along with whether they exist and why they
+    /// are not read. Logs added by `refresh` are not reflected.
+    pub fn resolution_report(&self) -> &ResolutionReport {
+        &self.resolution_report
+    }
+
+    /// Get paths of rotated logs that were not opened because they were too old, see `InodeAwareReaderBuilder::min_mtime`.
+    pub fn get_skipped_files(&self) -> &[PathBuf] {
+        &self.skipped_files
+    }
+
+    /// Get path of an item that is currently read. Reader without files (see `InodeAwareReaderBuilder::allow_missing`)
+    /// returns path of the newest log it waits for.
+    pub fn get_current_path(&self) -> &Path {
+        let item_index = self.get_current_item_index();
+        self.paths.get(item_index).unwrap_or(&self.path)
+    }
+
+    /// Query metadata (eg. size or modification time) of an item that is currently read, see `stat_item`.
+    pub fn stat_current(&self) -> io::Result<Metadata> {
+        self.stat_item(self.get_current_item_index())
+    }
+
+    /// Query metadata of an item. Metadata of opened file is available even if it was deleted, released file is queried
+    /// by its path. For compressed logs this is metadata of decompressed content, so size matches offsets inside it.
+    ///
+    /// Errors mention path of the file. Fails with InvalidInput io error if there is no such item.
+    pub fn stat_item(&self, item_index: usize) -> io::Result<Metadata> {
+        let item = self.inner.get_item(item_index).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "item {item_index} does not exist among rotated versions of {}",
+                    self.path.display()
+                ),
+            )
+        })?;
+        item.metadata().map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("cannot query metadata of {}: {e}", item.path().display()),
+            )
+        })
+    }
+
+    /// Check whether opened files still match the filesystem, without changing current position. Useful to decide whether
+    /// `refresh` is needed in long-running readers.
+    ///
+    /// Every recorded path is checked to still point to the recorded inode, and every file is checked to be at least as
+    /// large as known sizes. For the last file, which may still be written into, only the part that was already read is
+    /// checked.
+    pub fn verify_integrity(&self) -> io::Result<IntegrityReport> {
+        let current_index = self.get_current_item_index();
+        let last_index = self.len().saturating_sub(1);
+        let mut items = Vec::with_capacity(self.len());
+        for (item_index, (path, &inode)) in self.paths.iter().zip(&self.inodes).enumerate() {
+            let found_inode = match file_id(path) {
+                Ok(found_inode) => found_inode,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    items.push(ItemIntegrity::Missing);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            if found_inode != inode {
+                items.push(ItemIntegrity::Replaced {
+                    new_inode: found_inode,
+                });
+                continue;
+            }
+            let known_size = if item_index < last_index {
+                self.inner.get_item_size_cached(item_index)
+            } else if item_index == current_index {
+                Some(self.inner.get_local_offset())
+            } else {
+                None
+            };
+            let size = self.stat_item(item_index)?.len();
+            items.push(match known_size {
+                Some(known_size) if size < known_size => ItemIntegrity::Shrunk {
+                    by: known_size - size,
+                },
+                _ => ItemIntegrity::Ok,
+            });
+        }
+        Ok(IntegrityReport { items })
+    }
+
+    // Destroy struct and return underlying reader, inodes and paths.
+    pub fn into_inner(self) -> (Multireader<LogFile>, Vec<u64>, Vec<PathBuf>) {
+        (self.inner, self.inodes, self.paths)
+    }
+
+    /// Get inode of an item that is currently read. Reader without files (see `InodeAwareReaderBuilder::allow_missing`)
+    /// returns 0, same as inode of its persistent offset.
+    pub fn get_current_inode(&self) -> u64 {
+        let item_index = self.get_current_item_index();
+        self.inodes.get(item_index).copied().unwrap_or_default()
+    }
+
+    /// Search for item index by given inode.
+    pub fn get_item_index_by_inode(&self, inode: u64) -> Option<usize> {
+        self.item_indices.get(&inode).cloned()
+    }
+
+    /// Get inode of an item, None if there is no such item.
+    pub fn get_inode_for_item(&self, item_index: usize) -> Option<u64> {
+        self.inodes.get(item_index).cloned()
+    }
+
+    /// Compare two offsets as if they were pointing into one large buffer. Returns None if any of the offsets do not belong
+    /// to underlying files.
+    pub fn compare_offsets(
+        &self,
+        first: InodeAwareOffset,
+        second: InodeAwareOffset,
+    ) -> Option<Ordering> {
+        let first_index = self.get_item_index_by_inode(first.inode)?;
+        let second_index = self.get_item_index_by_inode(second.inode)?;
+        if first_index == second_index {
+            return Some(first.offset.cmp(&second.offset));
+        }
+        Some(first_index.cmp(&second_index))
+    }
+
+    /// Number of bytes from `from` to `to` as if they were pointing into one large buffer, negative if `to` comes first.
+    /// Returns None if any of the offsets do not belong to underlying files (see `to_global`) or the distance does not fit
+    /// into i64.
+    pub fn distance_between(&self, from: InodeAwareOffset, to: InodeAwareOffset) -> Option<i64> {
+        let from = i64::try_from(self.to_global(from)?).ok()?;
+        let to = i64::try_from(self.to_global(to)?).ok()?;
+        to.checked_sub(from)
+    }
+
+    /// Number of bytes between `committed` offset and the end of the newest file, eg. to monitor how far behind the log
+    /// its processing is. Newest file is measured, so data written since previous call is accounted for.
+    ///
+    /// Will return NotFound io error if there is no file with inode of `committed` and InvalidInput io error if it lies
+    /// past the end of a rotated file.
+    pub fn lag_bytes(&mut self, committed: InodeAwareOffset) -> io::Result<u64> {
+        self.require_item_index_by_inode(committed.inode)?;
+        let total_size = self.inner.get_total_size()?;
+        let committed = self.to_global(committed).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "committed offset lies past the end of its file",
+            )
+        })?;
+        Ok(total_size.saturating_sub(committed))
+    }
+
+    /// Number of rotations that happened since `offset` was in the newest file: 0 if it still is, 1 if its file is the
+    /// newest rotated one and so on. Returns None if there is no file with inode of `offset`, eg. because it was already
+    /// rotated away.
+    ///
+    /// `len() - 1 - rotations_behind` is the number of older files still kept, ie. how close the file is to being
+    /// removed.
+    pub fn rotations_behind(&self, offset: InodeAwareOffset) -> Option<usize> {
+        let item_index = self.get_item_index_by_inode(offset.inode)?;
+        Some(self.inner.len() - 1 - item_index)
+    }
+
+    /// Report how much of available data was read. Newest file is measured on each call, so total size grows along with
+    /// it.
+    pub fn progress(&mut self) -> io::Result<Progress> {
+        Ok(Progress {
+            bytes_read: self.inner.get_global_offset(),
+            bytes_total: self.inner.get_total_size()?,
+            current_item: self.inner.get_current_item_index(),
+            item_count: self.inner.len(),
+        })
+    }
+
+    /// Get up to `n` last lines across all files along with persistent offsets at which they start, similar to `tail -n`.
+    ///
+    /// Files are scanned backwards from the end of the newest one, so only their tail is read, and current position is not
+    /// changed. Line terminators are stripped like `Multireader::last_lines` does. Returns InvalidData io error if lines
+    /// are not valid UTF-8.
+    pub fn tail_lines(&mut self, n: usize) -> io::Result<Vec<(InodeAwareOffset, String)>> {
+        let (lines_start, content) = self.with_relocation(|inner| {
+            let total_size = inner.get_total_size()?;
+            let lines_start = inner.last_lines_start(n, total_size)?;
+            let mut content = vec![0; (total_size - lines_start) as usize];
+            inner.read_exact_at(lines_start, &mut content)?;
+            Ok((lines_start, content))
+        })?;
+        let content = String::from_utf8(content).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )
+        })?;
+
+        let mut line_start = lines_start;
+        let mut lines = vec![];
+        for line in content.split_inclusive('\n') {
+            let offset = self
+                .to_persistent(line_start)
+                .expect("offsets up to total size belong to items");
+            line_start += line.len() as u64;
+            let line = match line.strip_suffix('\n') {
+                Some(line) => line.strip_suffix('\r').unwrap_or(line),
+                None => line,
+            };
+            lines.push((offset, line.to_string()));
+        }
+        Ok(lines)
+    }
+
+    /// Find persistent offset at which 1-based line number `line` across all files starts, eg. to seek to it with
+    /// `seek_persistent`. Files are scanned from the beginning of the oldest one without changing current position.
+    ///
+    /// Returns None if there are fewer lines, InvalidInput io error if `line` is 0. See `offset_of_line_from` to avoid
+    /// rescanning files.
+    pub fn offset_of_line(&mut self, line: u64) -> io::Result<Option<InodeAwareOffset>> {
+        let line_start = self.find_line_start(0, 1, line)?;
+        Ok(line_start.and_then(|global| self.to_persistent(global)))
+    }
+
+    /// Like `offset_of_line`, but scanning starts at `checkpoint` known to be the start of given line, eg. returned by
+    /// previous call. Files are scanned from the beginning if `line` precedes the checkpoint.
+    ///
+    /// Returns NotFound io error if there is no file with inode of checkpoint and InvalidInput io error if checkpoint lies
+    /// past the end of a rotated file.
+    pub fn offset_of_line_from(
+        &mut self,
+        checkpoint: (InodeAwareOffset, u64),
+        line: u64,
+    ) -> io::Result<Option<InodeAwareOffset>> {
+        let (checkpoint, checkpoint_line) = checkpoint;
+        self.require_item_index_by_inode(checkpoint.inode)?;
+        let start = self.to_global(checkpoint).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "checkpoint lies past the end of its file",
+            )
+        })?;
+        if line < checkpoint_line {
+            return self.offset_of_line(line);
+        }
+        let line_start = self.find_line_start(start, checkpoint_line, line)?;
+        Ok(line_start.and_then(|global| self.to_persistent(global)))
+    }
+
+    /// Find global offset of line `line` counting newlines from global offset `start`, where line `start_line` starts.
+    /// Returns None if there are fewer lines.
+    fn find_line_start(
+        &mut self,
+        start: u64,
+        start_line: u64,
+        line: u64,
+    ) -> io::Result<Option<u64>> {
+        if line == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "line numbers start at 1",
+            ));
+        }
+        let total_size = self.with_relocation(Multireader::get_total_size)?;
+        let line_start = |global: u64| Some(global).filter(|&global| global < total_size);
+        let mut newlines_left = line - start_line;
+        if newlines_left == 0 {
+            return Ok(line_start(start));
+        }
+        let mut buf = vec![0; self.buffer_capacity.max(1)];
+        let mut position = start;
+        while position < total_size {
+            let len = (total_size - position).min(buf.len() as u64) as usize;
+            let size_read =
+                self.with_relocation(|inner| inner.read_at(position, &mut buf[..len]))?;
+            if size_read == 0 {
+                break;
+            }
+            for newline in memchr::memchr_iter(b'\n', &buf[..size_read]) {
+                newlines_left -= 1;
+                if newlines_left == 0 {
+                    return Ok(line_start(position + newline as u64 + 1));
+                }
+            }
+            position += size_read as u64;
+        }
+        Ok(None)
+    }
+
+    /// Describe every file from oldest to newest, eg. for monitoring. Newest file is measured on each call.
+    pub fn describe_items(&mut self) -> io::Result<Vec<ItemDescription>> {
+        let current_index = self.inner.get_current_item_index();
+        (0..self.inner.len())
+            .map(|index| {
+                Ok(ItemDescription {
+                    index,
+                    path: self.paths[index].clone(),
+                    inode: self.inodes[index],
+                    size: self.inner.get_item_size(index)?,
+                    current: index == current_index,
+                })
+            })
+            .collect()
+    }
+
+    /// Count lines read through `Read` and `BufRead` implementations, see `current_line`. Lines before current position are
+    /// counted right away, so enabling tracking in the middle of a large log reads everything before it. Returns current
+    /// line number.
+    pub fn enable_line_tracking(&mut self) -> io::Result<u64> {
+        self.line_tracking = true;
+        self.recount_lines()
+    }
+
+    pub fn disable_line_tracking(&mut self) {
+        self.line_tracking = false;
+        self.line_count = None;
+    }
+
+    pub fn get_line_tracking(&self) -> bool {
+        self.line_tracking
+    }
+
+    /// 1-based number of line at current position across all files, see `enable_line_tracking`.
+    ///
+    /// Returns None if tracking is disabled or cursor was moved other than by reading through `Read` or `BufRead` (eg. by
+    /// seeking or by methods of underlying `Multireader`), in which case the count stays unknown until `recount_lines`.
+    pub fn current_line(&self) -> Option<u64> {
+        self.valid_line_count().map(|count| count.lines + 1)
+    }
+
+    /// 1-based number of line at current position within current file, None under the same conditions as `current_line`.
+    pub fn current_line_in_item(&self) -> Option<u64> {
+        let count = self.valid_line_count()?;
+        match count.item_index == self.inner.get_current_item_index() {
+            true => Some(count.lines - count.item_start_lines + 1),
+            // cursor moved to the start of the next file without reading
+            false => Some(1),
+        }
+    }
+
+    /// Count lines from the start of the oldest file up to current position without moving the cursor, eg. after seeking.
+    /// Returns current line number. Does nothing but return 1 if line tracking is disabled.
+    pub fn recount_lines(&mut self) -> io::Result<u64> {
+        if !self.line_tracking {
+            return Ok(1);
+        }
+        let offset = self.inner.get_global_offset();
+        let item_index = self.inner.get_current_item_index();
+        let item_start = self.inner.item_start_offset(item_index).unwrap_or(0);
+        let mut count = LineCount {
+            offset,
+            lines: 0,
+            item_index,
+            item_start_lines: 0,
+        };
+        let mut buf = vec![0; self.buffer_capacity.max(1)];
+        let mut position = 0;
+        while position < offset {
+            let len = (offset - position).min(buf.len() as u64) as usize;
+            let size_read =
+                self.with_relocation(|inner| inner.read_at(position, &mut buf[..len]))?;
+            if size_read == 0 {
+                break;
+            }
+            let before_item = item_start.saturating_sub(position).min(size_read as u64) as usize;
+            count.item_start_lines += count_newlines(&buf[..before_item]);
+            count.lines += count_newlines(&buf[..size_read]);
+            position += size_read as u64;
+        }
+        self.line_count = Some(count);
+        Ok(count.lines + 1)
+    }
+
+    fn valid_line_count(&self) -> Option<&LineCount> {
+        self.line_count
+            .as_ref()
+            .filter(|count| count.offset == self.inner.get_global_offset())
+    }
+
+    /// Account for `newlines` that were read starting at global offset `start`. `newlines_before` counts newlines among
+    /// the first given number of bytes read, used when cursor moved to another file.
+    fn track_lines(&mut self, start: u64, newlines: u64, newlines_before: impl Fn(usize) -> u64) {
+        let Some(count) = self.line_count.as_mut() else {
+            return;
+        };
+        if count.offset != start {
+            self.line_count = None;
+            return;
+        }
+        let item_index = self.inner.get_current_item_index();
+        if item_index != count.item_index {
+            let item_start = self.inner.item_start_offset(item_index).unwrap_or(start);
+            let boundary = usize::try_from(item_start.saturating_sub(start)).unwrap_or(usize::MAX);
+            count.item_start_lines = count.lines + newlines_before(boundary);
+            count.item_index = item_index;
+        }
+        count.lines += newlines;
+        count.offset = self.inner.get_global_offset();
+    }
+
+    /// Convert persistent offset into global offset of underlying `Multireader` (eg. for `read_at`). Returns None if inode
+    /// is unknown or offset lies past the end of a rotated file. Offsets in the newest file are not limited since it may
+    /// grow.
+    pub fn to_global(&self, offset: InodeAwareOffset) -> Option<u64> {
+        let item_index = self.get_item_index_by_inode(offset.inode)?;
+        self.inner.local_to_global(item_index, offset.offset)
+    }
+
+    /// Convert global offset of underlying `Multireader` into persistent offset, same as `get_persistent_offset` would
+    /// return at that position except that file stamp is never recorded. Returns None for empty reader.
+    ///
+    /// Offset at the end of a file is converted into the start of the following one.
+    pub fn to_persistent(&self, global: u64) -> Option<InodeAwareOffset> {
+        let (item_index, offset) = self.inner.global_to_local(global)?;
+        Some(InodeAwareOffset {
+            inode: self.inodes[item_index],
+            offset,
+            fingerprint: self.fingerprints.get(item_index).cloned().flatten(),
+            file_stamp: None,
+        })
+    }
+}
+
+/// Search for the log and its rotated versions. If `allow_missing` is set and the log does not exist, only rotated versions
+/// are returned.
+fn find_logs(
+    path: &Path,
+    max_depth: usize,
+    scheme: &(dyn RotationScheme + Send),
+    codecs: &CodecRegistry,
+    allow_missing: bool,
+    symlink_policy: SymlinkPolicy,
+) -> io::Result<Vec<(PathBuf, u64)>> {
+    if symlink_policy == SymlinkPolicy::WatchLinkTarget {
+        return match fs::canonicalize(path) {
+            Ok(target) => {
+                let inode = file_id(&target)?;
+                Ok(vec![(target, inode)])
+            }
+            Err(e) if allow_missing && e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(e),
+        };
+    }
+    let suffixes = codecs.suffixes();
+    match file_id(path) {
+        Err(e) if allow_missing && e.kind() == io::ErrorKind::NotFound => scheme
+            .candidates(path, max_depth, &suffixes)?
+            .into_iter()
+            .map(|path| {
+                let inode = file_id(&path)?;
+                Ok((path, inode))
+            })
+            .collect(),
+        _ => glob_rotated_logs_with_scheme(path, max_depth, scheme, &suffixes),
+    }
+}
+
+/// Remove found files last modified before `min_mtime` and return their paths. Newest file is always kept.
+fn skip_older_files(
+    paths_and_inodes: &mut Vec<(PathBuf, u64)>,
+    min_mtime: SystemTime,
+) -> io::Result<Vec<PathBuf>> {
+    let newest = paths_and_inodes.pop();
+    let mut skipped = vec![];
+    for (path, inode) in std::mem::take(paths_and_inodes) {
+        if fs::metadata(&path)?.modified()? < min_mtime {
+            skipped.push(path);
+        } else {
+            paths_and_inodes.push((path, inode));
+        }
+    }
+    paths_and_inodes.extend(newest);
+    Ok(skipped)
+}
+
+/// Build lookup table of item indices by inode. Fails with InvalidInput io error if inodes are not unique, since the same
+/// file cannot be read twice.
+fn index_inodes(inodes: &[u64]) -> io::Result<HashMap<u64, usize>> {
+    let mut item_indices = HashMap::with_capacity(inodes.len());
+    for (item_index, &inode) in inodes.iter().enumerate() {
+        if item_indices.insert(inode, item_index).is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("inode {inode} is used more than once"),
+            ));
+        }
+    }
+    Ok(item_indices)
+}
+
+/// Create reader of files that are opened on first access, taking their sizes from metadata. Compressed logs are opened
+/// right away since size of their decompressed content is needed.
+fn open_lazily(
+    paths: &[PathBuf],
+    inodes: &[u64],
+    buffer_capacity: usize,
+    codecs: &CodecRegistry,
+) -> io::Result<Multireader<LogFile>> {
+    let mut files = Vec::with_capacity(paths.len());
+    let mut sizes = Vec::with_capacity(paths.len());
+    for (path, &inode) in paths.iter().zip(inodes) {
+        if codecs.is_compressed(path) {
+            let mut file = LogFile::open(path, inode, buffer_capacity, codecs)?;
+            sizes.push(file.seek(io::SeekFrom::End(0))?);
+            file.rewind()?;
+            files.push(file);
+        } else {
+            sizes.push(fs::metadata(path)?.len());
+            files.push(LogFile::open_lazily(path, inode, buffer_capacity, codecs));
+        }
+    }
+    Multireader::with_sizes(files, sizes)
+}
+
+/// Measure sizes of all files except the newest one, so that start of every file is known. Rotated files do not grow, so
+/// it is done only once.
+fn measure_rotated_files(multireader: &mut Multireader<LogFile>) -> io::Result<()> {
+    if multireader.len() >= 2 {
+        multireader.get_item_size(multireader.len() - 2)?;
+    }
+    Ok(())
+}
+
+/// 64-bit FNV-1a hash, which unlike `DefaultHasher` is stable across Rust versions and thus can be persisted.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Builder of `InodeAwareReader` for cases when other constructors are not enough. Defaults match
+/// `InodeAwareReader::from_rotated_logs`.
+///
+/// ```rust no_run
+/// # use filetrack::{InodeAwareReader, MissingInodePolicy};
+/// let reader = InodeAwareReader::builder()
+///     .path("/var/log/mail.log")
+///     .search_depth(5)
+///     .buffer_capacity(64 * 1024)
+///     .missing_inode_policy(MissingInodePolicy::StartOfOldest)
+///     .auto_refresh(true)
+///     .open()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct InodeAwareReaderBuilder {
+    path: Option<PathBuf>,
+    search_depth: usize,
+    scheme: Box<dyn RotationScheme + Send>,
+    codecs: CodecRegistry,
+    buffer_capacity: usize,
+    truncation_policy: TruncationPolicy,
+    missing_inode_policy: MissingInodePolicy,
+    fingerprint_length: Option<usize>,
+    record_file_stamps: bool,
+    auto_refresh: bool,
+    detect_truncation: bool,
+    lazy_open: bool,
+    auto_release: bool,
+    allow_missing: bool,
+    symlink_policy: SymlinkPolicy,
+    min_mtime: Option<SystemTime>,
+    #[cfg(feature = "mmap")]
+    mmap: bool,
+}
+
+impl Default for InodeAwareReaderBuilder {
+    fn default() -> Self {
+        Self {
+            path: None,
+            search_depth: 2,
+            scheme: Box::new(RotationNaming::default()),
+            codecs: CodecRegistry::default(),
+            buffer_capacity: DEFAULT_BUFFER_CAPACITY,
+            truncation_policy: TruncationPolicy::default(),
+            missing_inode_policy: MissingInodePolicy::default(),
+            fingerprint_length: None,
+            record_file_stamps: false,
+            auto_refresh: false,
+            detect_truncation: false,
+            lazy_open: false,
+            auto_release: false,
+            allow_missing: false,
+            symlink_policy: SymlinkPolicy::default(),
+            min_mtime: None,
+            #[cfg(feature = "mmap")]
+            mmap: false,
+        }
+    }
+}
+
+impl InodeAwareReaderBuilder {
+    /// Path to the newest log, required.
+    pub fn path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Maximum number of rotated logs to use, 2 by default.
+    pub fn search_depth(mut self, search_depth: usize) -> Self {
+        self.search_depth = search_depth;
+        self
+    }
+
+    /// How rotated logs are found, `RotationNaming::Numeric` by default.
+    pub fn scheme(mut self, scheme: impl RotationScheme + Send + 'static) -> Self {
+        self.scheme = Box::new(scheme);
+        self
+    }
+
+    /// Search for numbered rotated logs in `dir` as well, for logs rotated with `olddir` option of logrotate. Shortcut for
+    /// `scheme(OldDir::new(dir))`, use `scheme` with `OldDir` directly for other naming.
+    pub fn olddir(self, dir: impl AsRef<Path>) -> Self {
+        self.scheme(OldDir::new(dir))
+    }
+
+    /// Codecs of compressed rotated logs, ones enabled by features by default.
+    pub fn codecs(mut self, codecs: CodecRegistry) -> Self {
+        self.codecs = codecs;
+        self
+    }
+
+    /// Capacity of buffer of each opened file, 8 KiB by default.
+    pub fn buffer_capacity(mut self, bytes: usize) -> Self {
+        self.buffer_capacity = bytes;
+        self
+    }
+
+    /// See `InodeAwareReader::set_truncation_policy`.
+    pub fn truncation_policy(mut self, policy: TruncationPolicy) -> Self {
+        self.truncation_policy = policy;
+        self
+    }
+
+    /// See `InodeAwareReader::set_missing_inode_policy`.
+    pub fn missing_inode_policy(mut self, policy: MissingInodePolicy) -> Self {
+        self.missing_inode_policy = policy;
+        self
+    }
+
+    /// See `InodeAwareReader::set_fingerprint_length`, disabled by default.
+    pub fn fingerprint_length(mut self, length: usize) -> Self {
+        self.fingerprint_length = Some(length);
+        self
+    }
+
+    /// See `InodeAwareReader::set_record_file_stamps`, disabled by default.
+    pub fn record_file_stamps(mut self, enabled: bool) -> Self {
+        self.record_file_stamps = enabled;
+        self
+    }
+
+    /// See `InodeAwareReader::set_auto_refresh`, disabled by default.
+    pub fn auto_refresh(mut self, enabled: bool) -> Self {
+        self.auto_refresh = enabled;
+        self
+    }
+
+    /// See `InodeAwareReader::set_detect_truncation`, disabled by default.
+    pub fn detect_truncation(mut self, enabled: bool) -> Self {
+        self.detect_truncation = enabled;
+        self
+    }
+
+    /// Do not open files until cursor enters them (eg. when many logs with deep search are watched, to stay within limit
+    /// of file descriptors). Disabled by default, so all files are opened on construction.
+    ///
+    /// Sizes of files are taken from their metadata instead. Offsets behave the same: if a file is rotated further before
+    /// it is opened, it is looked up among rotated logs by its inode. Accessing it fails with NotFound io error only if it
+    /// was removed.
+    /// Compressed logs and files used for fingerprinting (see `fingerprint_length`) are opened anyway.
+    pub fn lazy_open(mut self, enabled: bool) -> Self {
+        self.lazy_open = enabled;
+        self
+    }
+
+    /// See `InodeAwareReader::set_auto_release`, disabled by default.
+    pub fn auto_release(mut self, enabled: bool) -> Self {
+        self.auto_release = enabled;
+        self
+    }
+
+    /// Open reader even if the newest log does not exist yet (eg. because service did not write anything so far), disabled
+    /// by default so that NotFound io error is returned.
+    ///
+    /// Rotated logs that exist are read as usual. Until the newest log appears and is added with
+    /// `InodeAwareReader::try_attach` or `refresh`, reader may have no files at all: reads return 0 and persistent offset
+    /// is the default one.
+    pub fn allow_missing(mut self, enabled: bool) -> Self {
+        self.allow_missing = enabled;
+        self
+    }
+
+    /// Do not open rotated logs last modified before `min_mtime`, eg. to avoid ingesting old history on first run. Newest
+    /// file is always opened. Skipped files are reported by `InodeAwareReader::get_skipped_files`.
+    pub fn min_mtime(mut self, min_mtime: SystemTime) -> Self {
+        self.min_mtime = Some(min_mtime);
+        self
+    }
+
+    /// Do not open rotated logs last modified more than `max_age` ago, see `min_mtime`.
+    pub fn max_age(self, max_age: Duration) -> Self {
+        let min_mtime = SystemTime::now().checked_sub(max_age).unwrap_or(UNIX_EPOCH);
+        self.min_mtime(min_mtime)
+    }
+
+    /// How path that is a symbolic link is treated, `SymlinkPolicy::Follow` by default.
+    pub fn symlink_policy(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlink_policy = policy;
+        self
+    }
+
+    /// Read rotated logs from memory maps, see `InodeAwareReader::from_rotated_logs_mmap`. Disabled by default.
+    #[cfg(feature = "mmap")]
+    pub fn mmap(mut self, enabled: bool) -> Self {
+        self.mmap = enabled;
+        self
+    }
+
+    /// Search for rotated logs and open them. Fails with InvalidInput io error if path was not set or the same file was
+    /// found more than once (eg. as a hard link).
+    pub fn open(self) -> io::Result<InodeAwareReader> {
+        let Some(path) = self.path else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "path to the log is required",
+            ));
+        };
+        let path = match self.symlink_policy {
+            SymlinkPolicy::FollowAndTrackTarget => match fs::canonicalize(&path) {
+                Ok(target) => target,
+                // missing log is looked for by the link path then
+                Err(e) if self.allow_missing && e.kind() == io::ErrorKind::NotFound => path,
+                Err(e) => return Err(e),
+            },
+            SymlinkPolicy::Follow | SymlinkPolicy::WatchLinkTarget => path,
+        };
+        let mut paths_and_inodes = find_logs(
+            &path,
+            self.search_depth,
+            self.scheme.as_ref(),
+            &self.codecs,
+            self.allow_missing,
+            self.symlink_policy,
+        )?;
+        let rejected = match self.symlink_policy {
+            SymlinkPolicy::WatchLinkTarget => vec![],
+            SymlinkPolicy::Follow | SymlinkPolicy::FollowAndTrackTarget => {
+                self.scheme
+                    .rejected(&path, self.search_depth, &self.codecs.suffixes())?
+            }
+        };
+        let mut resolution_report = ResolutionReport::collect(&path, &paths_and_inodes, rejected)?;
+        let skipped_files = match self.min_mtime {
+            Some(min_mtime) => skip_older_files(&mut paths_and_inodes, min_mtime)?,
+            None => vec![],
+        };
+        for entry in resolution_report.entries.iter_mut() {
+            if skipped_files.contains(&entry.path) {
+                entry.skipped = Some(SkipReason::OlderThanCutoff);
+            }
+        }
+        let mut reader = InodeAwareReader::open_files(
+            paths_and_inodes,
+            path,
+            self.search_depth,
+            self.scheme,
+            self.codecs,
+            self.buffer_capacity,
+            self.lazy_open,
+        )?;
+        reader.set_truncation_policy(self.truncation_policy);
+        reader.set_missing_inode_policy(self.missing_inode_policy);
+        reader.set_record_file_stamps(self.record_file_stamps);
+        reader.set_auto_refresh(self.auto_refresh);
+        reader.set_detect_truncation(self.detect_truncation);
+        reader.set_auto_release(self.auto_release);
+        reader.allow_missing = self.allow_missing;
+        reader.symlink_policy = self.symlink_policy;
+        reader.skipped_files = skipped_files;
+        reader.resolution_report = resolution_report;
+        #[cfg(feature = "mmap")]
+        {
+            reader.map_rotated = self.mmap;
+            reader.map_rotated_files()?;
+        }
+        if self.fingerprint_length.is_some() {
+            reader.set_fingerprint_length(self.fingerprint_length)?;
+        }
+        Ok(reader)
+    }
+}
+
+/// Iterator over lines of followed log, see `InodeAwareReader::follow`.
+pub struct Follow<'a> {
+    reader: &'a mut InodeAwareReader,
+    poll: Duration,
+    deadline: Option<Instant>,
+    stop: Option<Arc<AtomicBool>>,
+}
+
+impl<'a> Follow<'a> {
+    /// Stop iteration once `deadline` passes.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stop iteration once `stop` is set, allowing to shut down from another thread.
+    pub fn with_stop_flag(mut self, stop: Arc<AtomicBool>) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    fn should_stop(&self) -> bool {
+        self.stop
+            .as_ref()
+            .is_some_and(|stop| stop.load(AtomicOrdering::Relaxed))
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Sleep for poll interval, waking up earlier if deadline comes sooner.
+    fn wait(&self) {
+        let timeout = match self.deadline {
+            Some(deadline) => self
+                .poll
+                .min(deadline.saturating_duration_since(Instant::now())),
+            None => self.poll,
+        };
+        thread::sleep(timeout);
+    }
+
+    /// Read complete line or return None on EOF, leaving reader at the start of incomplete line.
+    fn read_complete_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        let size_read = self.reader.read_line(&mut line)?;
+        if !line.ends_with('\n') {
+            self.reader.seek_relative(-(size_read as i64))?;
+            return Ok(None);
+        }
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+        Ok(Some(line))
+    }
+}
+
+impl<'a> Iterator for Follow<'a> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.should_stop() {
+            match self.read_complete_line() {
+                Ok(Some(line)) => return Some(Ok(line)),
+                Ok(None) => {}
+                Err(e) => return Some(Err(e)),
+            }
+            match self.reader.refresh() {
+                // new files may already contain data
+                Ok(outcome) if outcome.new_files > 0 => continue,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+            self.wait();
+        }
+        None
+    }
+}
+
+impl Read for InodeAwareReader {
+    /// Read from underlying files, refreshing them on EOF if auto refresh is enabled. Truncation is checked on EOF if its
+    /// detection is enabled. Files left behind are released if auto release is enabled. Lines are counted if line tracking
+    /// is enabled.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let start = self.inner.get_global_offset();
+        let mut size_read = self.with_relocation(|inner| inner.read(buf))?;
+        if size_read == 0 && !buf.is_empty() {
+            if self.refresh_on_eof()? {
+                size_read = self.with_relocation(|inner| inner.read(buf))?;
+            } else {
+                self.check_truncation()?;
+            }
+        }
+        if self.line_tracking {
+            let read = &buf[..size_read];
+            self.track_lines(start, count_newlines(read), |boundary| {
+                count_newlines(&read[..boundary.min(read.len())])
+            });
+        }
+        self.release_left_behind()?;
+        Ok(size_read)
+    }
+}
+
+impl BufRead for InodeAwareReader {
+    /// Fill buffer of underlying files, refreshing them on EOF if auto refresh is enabled. Truncation is checked on EOF if
+    /// its detection is enabled. Files left behind are released if auto release is enabled.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.with_relocation(|inner| inner.fill_buf().map(drop))?;
+        if (self.auto_refresh || self.detect_truncation)
+            && self.inner.fill_buf()?.is_empty()
+            && !self.refresh_on_eof()?
+        {
+            self.check_truncation()?;
+        }
+        if self.auto_release {
+            self.inner.fill_buf()?;
+            self.release_left_behind()?;
+        }
+        self.inner.fill_buf()
+    }
+
+    /// Lines in consumed part of the buffer are counted if line tracking is enabled.
+    fn consume(&mut self, amt: usize) {
+        if !self.line_tracking {
+            return self.inner.consume(amt);
+        }
+        let start = self.inner.get_global_offset();
+        // buffer was filled before consuming, so this does not read anything
+        let newlines = match self.inner.fill_buf() {
+            Ok(buf) => count_newlines(&buf[..amt.min(buf.len())]),
+            Err(_) => {
+                self.line_count = None;
+                return self.inner.consume(amt);
+            }
+        };
+        self.inner.consume(amt);
+        // consumed bytes come from a single file, so cursor moved either before or after all of them
+        self.track_lines(start, newlines, |boundary| match boundary {
+            0 => 0,
+            _ => newlines,
+        });
+    }
+
+    /// Stops after `InodeAwareReader::set_max_line_length` bytes if the limit is set.
+    fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let max = self.max_line_length.unwrap_or(usize::MAX);
+        self.read_until_limited(byte, buf, max)
+    }
+
+    /// Stops after `InodeAwareReader::set_max_line_length` bytes if the limit is set.
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        let mut line = vec![];
+        let size_read = self.read_until(b'\n', &mut line)?;
+        match String::from_utf8(line) {
+            Ok(line) => {
+                buf.push_str(&line);
+                Ok(size_read)
+            }
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )),
+        }
+    }
+}
+
+impl Deref for InodeAwareReader {
+    type Target = Multireader<LogFile>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for InodeAwareReader {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, Read, Seek, SeekFrom, Write},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use tempfile::TempDir;
+
+    use rstest::rstest;
+
+    use super::{
+        FileStamp, InodeAwareOffset, InodeAwareReader, InodeAwareReaderBuilder, ItemDescription,
+        ItemIntegrity, LineTooLong, MissingInodePolicy, OffsetValidity, Progress, RefreshOutcome,
+        ReopenOutcome, ResolutionEntry, SeekOutcome, Truncation, TruncationDetected,
+        TruncationPolicy,
+    };
+    use crate::{
+        compression::FileOpener,
+        path_utils::{file_id, RotationNaming, SkipReason},
+        CodecRegistry, RotatingWriter,
+    };
+
+    fn rotating_log() -> (TempDir, RotatingWriter) {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = RotatingWriter::with_max_depth(dir.path().join("log"), 2).unwrap();
+        (dir, writer)
+    }
+
+    fn read_line(reader: &mut InodeAwareReader) -> String {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        line
+    }
+
+    #[test]
+    fn refresh_picks_up_rotated_log() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        let position = reader.get_persistent_offset();
+
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+        let outcome = reader.refresh().unwrap();
+        assert_eq!(
+            outcome,
+            RefreshOutcome {
+                new_files: 1,
+                active_file_changed: true
+            }
+        );
+        assert_eq!(reader.get_persistent_offset(), position);
+        assert_eq!(
+            reader.get_inodes().last().cloned(),
+            Some(file_id(writer.path()).unwrap())
+        );
+
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(read_line(&mut reader), "third\n");
+        assert_eq!(read_line(&mut reader), "");
+    }
+
+    #[test]
+    fn refresh_without_rotation_changes_nothing() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        writer.write_all(b"second\n").unwrap();
+        assert_eq!(reader.refresh().unwrap(), RefreshOutcome::default());
+        assert_eq!(reader.len(), 1);
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+    }
+
+    #[test]
+    fn auto_refresh_continues_into_rotated_log() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        reader.set_auto_refresh(true);
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "");
+
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(reader.len(), 2);
+
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+        let mut buf = vec![];
+        reader.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"third\n");
+    }
+
+    #[test]
+    fn auto_refresh_is_attempted_once_per_eof() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        writer.rotate().unwrap();
+        // disabled by default
+        assert_eq!(read_line(&mut reader), "");
+        assert_eq!(reader.len(), 1);
+
+        reader.set_auto_refresh(true);
+        // new file is empty, so there is still nothing to read after refresh
+        assert_eq!(read_line(&mut reader), "");
+        assert_eq!(reader.len(), 2);
+        assert_eq!(read_line(&mut reader), "");
+        assert_eq!(reader.len(), 2);
+    }
+
+    #[test]
+    fn refresh_after_multiple_rotations() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        for content in ["second\n", "third\n"] {
+            writer.rotate().unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        assert_eq!(reader.refresh().unwrap().new_files, 2);
+        assert_eq!(reader.len(), 3);
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(read_line(&mut reader), "third\n");
+    }
+
+    const POLL: Duration = Duration::from_millis(5);
+
+    fn deadline() -> Instant {
+        Instant::now() + Duration::from_secs(10)
+    }
+
+    #[test]
+    fn follow_delivers_lines_written_from_another_thread() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+
+        let writer_thread = thread::spawn(move || {
+            for i in 0..5 {
+                thread::sleep(POLL * 2);
+                writer.write_all(format!("line {i}\n").as_bytes()).unwrap();
+                if i == 2 {
+                    writer.rotate().unwrap();
+                }
+            }
+            // incomplete line is delivered once it is terminated
+            writer.write_all(b"incomp").unwrap();
+            thread::sleep(POLL * 2);
+            writer.write_all(b"lete\r\n").unwrap();
+        });
+        let lines = reader
+            .follow(POLL)
+            .with_deadline(deadline())
+            .take(7)
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        writer_thread.join().unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                "first",
+                "line 0",
+                "line 1",
+                "line 2",
+                "line 3",
+                "line 4",
+                "incomplete"
+            ]
+        );
+        assert_eq!(reader.len(), 2);
+    }
+
+    #[test]
+    fn follow_stops_on_flag_or_deadline() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_setter = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                thread::sleep(POLL * 4);
+                stop.store(true, std::sync::atomic::Ordering::Relaxed);
+            })
+        };
+        let lines = reader
+            .follow(POLL)
+            .with_stop_flag(stop)
+            .with_deadline(deadline())
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        stop_setter.join().unwrap();
+        assert_eq!(lines, vec!["first"]);
+        // reader is left at the start of incomplete line
+        assert_eq!(reader.get_global_offset(), 6);
+
+        let mut follow = reader.follow(POLL).with_deadline(Instant::now() + POLL);
+        assert!(follow.next().is_none());
+    }
+
+    const COMPRESSED_CONTENT: &[u8] = b"first\nsecond\n";
+
+    /// Check that rotated log compressed into `compressed` and stored with `suffix` is read before uncompressed ones and
+    /// reading can be resumed from inside of it.
+    fn check_compressed_rotation(codecs: CodecRegistry, suffix: &str, compressed: &[u8]) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        let compressed_path = dir.path().join(format!("log.2{suffix}"));
+        fs::write(&compressed_path, compressed).unwrap();
+        // delaycompress leaves the most recent rotated log uncompressed
+        fs::write(dir.path().join("log.1"), b"third\n").unwrap();
+        fs::write(&path, b"fourth\n").unwrap();
+
+        let mut reader =
+            InodeAwareReader::from_rotated_logs_with_codecs(&path, 2, codecs.clone()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        let position = reader.get_persistent_offset();
+        assert_eq!(position.inode, file_id(compressed_path).unwrap());
+        assert_eq!(position.offset, 6);
+
+        let mut resumed =
+            InodeAwareReader::from_rotated_logs_with_codecs(&path, 2, codecs).unwrap();
+        resumed.seek_persistent(position).unwrap();
+        let lines = (&mut *resumed)
+            .lines()
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(lines, vec!["second", "third", "fourth"]);
+    }
+
+    #[test]
+    fn custom_codec_is_used_for_rotated_logs() {
+        let mut codecs = CodecRegistry::new();
+        codecs.register(".rev", |mut input, output| {
+            let mut content = vec![];
+            input.read_to_end(&mut content)?;
+            content.reverse();
+            output.write_all(&content)
+        });
+        let mut compressed = COMPRESSED_CONTENT.to_vec();
+        compressed.reverse();
+        check_compressed_rotation(codecs, ".rev", &compressed);
+    }
+
+    #[test]
+    fn unknown_suffix_ends_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::write(dir.path().join("log.1.rev"), b"first\n").unwrap();
+        fs::write(&path, b"second\n").unwrap();
+        let reader = InodeAwareReader::from_rotated_logs(&path).unwrap();
+        assert_eq!(reader.len(), 1);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_rotated_logs_are_read() {
+        use flate2::{write::GzEncoder, Compression};
+
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(COMPRESSED_CONTENT).unwrap();
+        let compressed = encoder.finish().unwrap();
+        check_compressed_rotation(CodecRegistry::default(), ".gz", &compressed);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd_rotated_logs_are_read() {
+        let compressed = zstd::encode_all(COMPRESSED_CONTENT, 0).unwrap();
+        check_compressed_rotation(CodecRegistry::default(), ".zst", &compressed);
+    }
+
+    #[cfg(feature = "xz")]
+    #[test]
+    fn xz_rotated_logs_are_read() {
+        let mut encoder = xz2::write::XzEncoder::new(vec![], 6);
+        encoder.write_all(COMPRESSED_CONTENT).unwrap();
+        let compressed = encoder.finish().unwrap();
+        check_compressed_rotation(CodecRegistry::default(), ".xz", &compressed);
+    }
+
+    #[test]
+    fn dateext_rotated_logs_are_read_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::write(dir.path().join("log-20240115"), b"third\n").unwrap();
+        fs::write(dir.path().join("log-20231231"), b"first\n").unwrap();
+        fs::write(dir.path().join("log-20240101"), b"second\n").unwrap();
+        fs::write(&path, b"fourth\n").unwrap();
+
+        let mut reader =
+            InodeAwareReader::from_rotated_logs_with_naming(&path, 2, RotationNaming::Auto)
+                .unwrap();
+        let lines = (&mut *reader)
+            .lines()
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(lines, vec!["second", "third", "fourth"]);
+
+        fs::rename(&path, dir.path().join("log-20240116")).unwrap();
+        fs::write(&path, b"fifth\n").unwrap();
+        assert_eq!(reader.refresh().unwrap().new_files, 1);
+        assert_eq!(read_line(&mut reader), "fifth\n");
+    }
+
+    #[test]
+    fn custom_scheme_finds_archived_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("service.log");
+        fs::create_dir(dir.path().join("archive")).unwrap();
+        for (stamp, content) in [
+            ("2024-01-15T10-30-00", "second\n"),
+            ("2024-01-14T23-00-00", "first\n"),
+            ("2024-01-15T11-00-00", "third\n"),
+        ] {
+            fs::write(
+                dir.path().join(format!("archive/service.log.{stamp}")),
+                content,
+            )
+            .unwrap();
+        }
+        fs::write(&path, b"fourth\n").unwrap();
+
+        let archive = |live_path: &Path, max_depth: usize| -> std::io::Result<Vec<PathBuf>> {
+            let mut paths = fs::read_dir(live_path.with_file_name("archive"))?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<std::io::Result<Vec<_>>>()?;
+            paths.sort();
+            Ok(paths.split_off(paths.len().saturating_sub(max_depth)))
+        };
+        let mut reader =
+            InodeAwareReader::from_rotated_logs_with_scheme(&path, 2, archive).unwrap();
+        let lines = (&mut *reader)
+            .lines()
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(lines, vec!["second", "third", "fourth"]);
+    }
+
+    /// Replace file at `path` with its copy, changing inode but not content.
+    fn copy_with_new_inode(path: &Path) {
+        let copy = path.with_extension("copy");
+        fs::copy(path, &copy).unwrap();
+        fs::rename(copy, path).unwrap();
+    }
+
+    #[test]
+    fn fingerprint_finds_copied_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer
+            .write_all(
+                b"first
+second
+",
+            )
+            .unwrap();
+        writer.rotate().unwrap();
+        writer
+            .write_all(
+                b"third
+fourth
+",
+            )
+            .unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        reader.set_fingerprint_length(Some(1024)).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(read_line(&mut reader), "third\n");
+        let position = reader.get_persistent_offset();
+        assert!(position.fingerprint.is_some());
+        assert_eq!(reader.compute_fingerprints(1024).unwrap().len(), 2);
+        // computing fingerprints does not move reader
+        assert_eq!(reader.get_persistent_offset(), position);
+        drop(reader);
+
+        copy_with_new_inode(writer.path());
+        assert_ne!(file_id(writer.path()).unwrap(), position.inode);
+        fs::OpenOptions::new()
+            .append(true)
+            .open(writer.path())
+            .unwrap()
+            .write_all(b"fifth\n")
+            .unwrap();
+
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        reader.seek_persistent(position).unwrap();
+        assert_eq!(reader.get_current_item_index(), 1);
+        assert_eq!(read_line(&mut reader), "fourth\n");
+        assert_eq!(read_line(&mut reader), "fifth\n");
+
+        let without_fingerprint = super::InodeAwareOffset {
+            fingerprint: None,
+            ..position
+        };
+        let error = reader.seek_persistent(without_fingerprint).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn fingerprint_does_not_match_other_content() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        reader.set_fingerprint_length(Some(4)).unwrap();
+        let position = reader.get_persistent_offset();
+        drop(reader);
+
+        fs::write(writer.path(), b"other\n").unwrap();
+        copy_with_new_inode(writer.path());
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        let error = reader.seek_persistent(position).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn empty_files_get_no_fingerprint() {
+        let (_dir, mut writer) = rotating_log();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        reader.set_fingerprint_length(Some(1024)).unwrap();
+        assert_eq!(reader.get_persistent_offset().fingerprint, None);
+
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        reader.refresh().unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert!(reader.get_persistent_offset().fingerprint.is_some());
+    }
+
+    #[rstest]
+    #[case(TruncationPolicy::RestartFromBeginning, "new\n")]
+    #[case(TruncationPolicy::SeekToEnd, "")]
+    fn truncated_file_is_detected(#[case] policy: TruncationPolicy, #[case] expected: &str) {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+        let position = reader.get_persistent_offset();
+        drop(reader);
+
+        fs::File::options()
+            .write(true)
+            .open(writer.path())
+            .unwrap()
+            .set_len(0)
+            .unwrap();
+        writer.write_all(b"new\n").unwrap();
+
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        reader.set_truncation_policy(policy);
+        assert_eq!(
+            reader.seek_persistent(position).unwrap(),
+            Some(Truncation {
+                inode: position.inode,
+                path: writer.path().to_path_buf(),
+                offset: 13,
+                size: 4
+            })
+        );
+        assert_eq!(read_line(&mut reader), expected);
+    }
+
+    #[test]
+    fn truncation_can_be_an_error() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        let position = reader.get_persistent_offset();
+        // offset at the very end is not a truncation
+        assert_eq!(reader.seek_persistent(position).unwrap(), None);
+
+        writer.rotate_copytruncate().unwrap();
+        reader.set_truncation_policy(TruncationPolicy::Error);
+        let error = reader.seek_persistent(position).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        let truncation = error.get_ref().unwrap().downcast_ref::<Truncation>();
+        assert_eq!(truncation.map(|truncation| truncation.size), Some(0));
+    }
+
+    #[test]
+    fn paths_follow_inode_order() {
+        let (dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        let expected_paths = ["log.2", "log.1", "log"].map(|name| dir.path().join(name));
+        assert_eq!(reader.get_paths(), expected_paths);
+        let inodes = expected_paths
+            .iter()
+            .map(|path| file_id(path).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(reader.get_inodes(), inodes);
+
+        assert_eq!(reader.get_current_path(), expected_paths[0]);
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(reader.get_current_path(), expected_paths[1]);
+        assert_eq!(read_line(&mut reader), "second\n");
+
+        writer.rotate().unwrap();
+        reader.refresh().unwrap();
+        assert_eq!(reader.get_paths().last(), Some(&dir.path().join("log")));
+
+        let (_, inodes, paths) = reader.into_inner();
+        assert_eq!(inodes.len(), paths.len());
+    }
+
+    #[test]
+    fn missing_inode_error_mentions_path() {
+        let (_dir, writer) = rotating_log();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        let mut position = reader.get_persistent_offset();
+        position.inode += 1;
+        let error = reader.seek_persistent(position).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains(&writer.path().display().to_string()));
+    }
+
+    #[test]
+    fn seek_to_latest_and_oldest() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+
+        let latest = reader.seek_to_latest().unwrap();
+        assert_eq!(latest.inode, file_id(writer.path()).unwrap());
+        assert_eq!(latest.offset, 7);
+        assert_eq!(reader.get_persistent_offset(), latest);
+        assert_eq!(read_line(&mut reader), "");
+        writer.write_all(b"third\n").unwrap();
+        assert_eq!(read_line(&mut reader), "third\n");
+
+        let oldest = reader.seek_to_oldest().unwrap();
+        assert_eq!(oldest.inode, reader.get_inodes()[0]);
+        assert_eq!(oldest.offset, 0);
+        assert_eq!(read_line(&mut reader), "first\n");
+    }
+
+    #[test]
+    fn seek_to_latest_lands_in_empty_newest_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+
+        let latest = reader.seek_to_latest().unwrap();
+        assert_eq!(latest.inode, file_id(writer.path()).unwrap());
+        assert_eq!(latest.offset, 0);
+        writer.write_all(b"second\n").unwrap();
+        assert_eq!(read_line(&mut reader), "second\n");
+    }
+
+    #[test]
+    fn seek_to_inode_boundaries() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        let rotated_inode = reader.get_inodes()[0];
+        let newest_inode = reader.get_inodes()[1];
+
+        reader.seek_to_latest().unwrap();
+        let start = reader.seek_to_inode_start(rotated_inode).unwrap();
+        assert_eq!((start.inode, start.offset), (rotated_inode, 0));
+        assert_eq!(read_line(&mut reader), "first\n");
+
+        let end = reader.seek_to_inode_end(rotated_inode).unwrap();
+        assert_eq!((end.inode, end.offset), (newest_inode, 0));
+        assert_eq!(read_line(&mut reader), "third\n");
+
+        let start = reader.seek_to_inode_start(newest_inode).unwrap();
+        assert_eq!((start.inode, start.offset), (newest_inode, 0));
+        assert_eq!(read_line(&mut reader), "third\n");
+
+        let missing = newest_inode.max(rotated_inode) + 1;
+        let error = reader.seek_to_inode_start(missing).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+        assert!(error.to_string().contains(&missing.to_string()));
+        assert!(reader.seek_to_inode_end(missing).is_err());
+    }
+
+    #[test]
+    fn seek_persistent_or_restores_known_positions() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        let position = reader.get_persistent_offset();
+
+        reader.seek_to_oldest().unwrap();
+        let outcome = reader
+            .seek_persistent_or(position, MissingInodePolicy::EndOfNewest)
+            .unwrap();
+        assert_eq!(outcome, SeekOutcome::Restored);
+        assert_eq!(read_line(&mut reader), "second\n");
+
+        let past_end = super::InodeAwareOffset {
+            offset: 100,
+            ..position
+        };
+        let outcome = reader
+            .seek_persistent_or(past_end, MissingInodePolicy::Error)
+            .unwrap();
+        assert!(matches!(outcome, SeekOutcome::Truncated(truncation) if truncation.size == 13));
+
+        let missing = super::InodeAwareOffset {
+            inode: position.inode + 1,
+            ..position
+        };
+        let outcome = reader
+            .seek_persistent_or(missing, MissingInodePolicy::StartOfOldest)
+            .unwrap();
+        assert_eq!(
+            outcome,
+            SeekOutcome::FellBack(MissingInodePolicy::StartOfOldest)
+        );
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert!(reader
+            .seek_persistent_or(missing, MissingInodePolicy::Error)
+            .is_err());
+    }
+
+    #[test]
+    fn from_files_keeps_given_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = ["b", "c", "a"].map(|name| dir.path().join(name));
+        for (path, content) in paths.iter().zip(["first\n", "second\n", "third\n"]) {
+            fs::write(path, content).unwrap();
+        }
+
+        let mut reader = InodeAwareReader::from_files(&paths).unwrap();
+        assert_eq!(reader.get_paths(), paths);
+        let inodes = paths
+            .iter()
+            .map(|path| file_id(path).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(reader.get_inodes(), inodes);
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+        // end of a rotated file is reported as the start of the next one
+        let position = reader.get_persistent_offset();
+        assert_eq!((position.inode, position.offset), (inodes[2], 0));
+
+        let mut resumed = InodeAwareReader::from_files(&paths).unwrap();
+        resumed.seek_persistent(position).unwrap();
+        assert_eq!(read_line(&mut resumed), "third\n");
+        assert_eq!(resumed.refresh().unwrap(), RefreshOutcome::default());
+    }
+
+    #[test]
+    fn from_files_rejects_duplicates_and_empty_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let (path, link) = (dir.path().join("log"), dir.path().join("link"));
+        fs::write(&path, "content").unwrap();
+        fs::hard_link(&path, &link).unwrap();
+
+        let error = InodeAwareReader::from_files([&path, &link]).err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(error.to_string().contains("same file"));
+
+        let error = InodeAwareReader::from_files(Vec::<PathBuf>::new())
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    fn fake_reader(contents: &[&str], inodes: Vec<u64>) -> std::io::Result<InodeAwareReader> {
+        let items = contents
+            .iter()
+            .map(|content| {
+                let mut file = tempfile::tempfile().unwrap();
+                file.write_all(content.as_bytes()).unwrap();
+                std::io::BufReader::new(file)
+            })
+            .collect();
+        InodeAwareReader::from_readers_with_inodes(items, inodes)
+    }
+
+    #[test]
+    fn fake_inodes_support_persistent_offsets() {
+        let mut reader = fake_reader(&["first\n", "second\n"], vec![20, 10]).unwrap();
+        assert_eq!(reader.get_inodes(), [20, 10]);
+        assert_eq!(read_line(&mut reader), "first\n");
+        reader.read_exact(&mut [0; 1]).unwrap();
+        let position = reader.get_persistent_offset();
+        assert_eq!((position.inode, position.offset), (10, 1));
+
+        let mut resumed = fake_reader(&["first\n", "second\n"], vec![20, 10]).unwrap();
+        resumed.seek_persistent(position).unwrap();
+        assert_eq!(read_line(&mut resumed), "econd\n");
+
+        let start = super::InodeAwareOffset {
+            inode: 20,
+            ..Default::default()
+        };
+        assert_eq!(
+            resumed.compare_offsets(start, position),
+            Some(std::cmp::Ordering::Less)
+        );
+        let unknown = super::InodeAwareOffset {
+            inode: 30,
+            ..Default::default()
+        };
+        assert_eq!(resumed.compare_offsets(start, unknown), None);
+        assert!(resumed.seek_persistent(unknown).is_err());
+    }
+
+    #[test]
+    fn fake_inodes_are_validated() {
+        let error = fake_reader(&["a", "b"], vec![1]).err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        let error = fake_reader(&["a", "b"], vec![1, 1]).err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn builder_defaults_match_from_rotated_logs() {
+        let builder = InodeAwareReaderBuilder::default();
+        assert_eq!(builder.path, None);
+        assert_eq!(builder.search_depth, 2);
+        assert_eq!(builder.buffer_capacity, 8 * 1024);
+        assert_eq!(
+            builder.truncation_policy,
+            TruncationPolicy::RestartFromBeginning
+        );
+        assert_eq!(builder.missing_inode_policy, MissingInodePolicy::Error);
+        assert_eq!(builder.fingerprint_length, None);
+        assert!(!builder.auto_refresh);
+        assert_eq!(
+            builder.codecs.suffixes(),
+            CodecRegistry::default().suffixes()
+        );
+
+        let (dir, mut writer) = rotating_log();
+        for content in ["first\n", "second\n", "third\n"] {
+            writer.write_all(content.as_bytes()).unwrap();
+            writer.rotate().unwrap();
+        }
+        let built = InodeAwareReader::builder()
+            .path(writer.path())
+            .open()
+            .unwrap();
+        let constructed = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(built.get_paths(), constructed.get_paths());
+        assert_eq!(built.get_paths()[0], dir.path().join("log.2"));
+        assert_eq!(
+            built.get_item(0).unwrap().get_ref().unwrap().capacity(),
+            8 * 1024
+        );
+        assert!(!built.get_auto_refresh());
+        assert_eq!(built.get_fingerprint_length(), None);
+        assert_eq!(built.get_truncation_policy(), TruncationPolicy::default());
+        assert_eq!(
+            built.get_missing_inode_policy(),
+            MissingInodePolicy::default()
+        );
+
+        let error = InodeAwareReader::builder().open().err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn builder_applies_options() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::builder()
+            .path(writer.path())
+            .search_depth(1)
+            .buffer_capacity(16)
+            .fingerprint_length(4)
+            .truncation_policy(TruncationPolicy::SeekToEnd)
+            .missing_inode_policy(MissingInodePolicy::EndOfNewest)
+            .auto_refresh(true)
+            .open()
+            .unwrap();
+        assert_eq!(
+            reader.get_item(0).unwrap().get_ref().unwrap().capacity(),
+            16
+        );
+        assert!(reader.get_auto_refresh());
+        assert!(reader.get_persistent_offset().fingerprint.is_some());
+        assert_eq!(reader.get_truncation_policy(), TruncationPolicy::SeekToEnd);
+
+        let missing = super::InodeAwareOffset {
+            inode: reader.get_current_inode() + 1,
+            ..Default::default()
+        };
+        assert_eq!(reader.seek_persistent(missing).unwrap(), None);
+        assert_eq!(read_line(&mut reader), "");
+    }
+
+    #[test]
+    fn release_consumed_reopens_on_seek_back() {
+        let (_dir, mut writer) = rotating_log();
+        for content in ["first\n", "second\n"] {
+            writer.write_all(content.as_bytes()).unwrap();
+            writer.rotate().unwrap();
+        }
+        writer.write_all(b"third\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(reader.release_consumed().unwrap(), 0);
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+        reader.read_exact(&mut [0; 1]).unwrap();
+        let position = reader.get_persistent_offset();
+        let global_position = reader.stream_position().unwrap();
+
+        assert_eq!(reader.release_consumed().unwrap(), 2);
+        assert!(reader.get_item(0).unwrap().is_released());
+        assert!(reader.get_item(1).unwrap().is_released());
+        assert!(!reader.get_item(2).unwrap().is_released());
+        assert_eq!(reader.release_consumed().unwrap(), 0);
+        assert_eq!(reader.get_persistent_offset(), position);
+        assert_eq!(reader.stream_position().unwrap(), global_position);
+        assert_eq!(read_line(&mut reader), "hird\n");
+
+        reader.seek_to_oldest().unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert!(!reader.get_item(0).unwrap().is_released());
+        assert_eq!(read_line(&mut reader), "second\n");
+    }
+
+    #[test]
+    fn released_file_is_found_after_rotation() {
+        let (dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(reader.release_consumed().unwrap(), 1);
+
+        // log.1 becomes log.2, so the path of released file now points to another one
+        writer.rotate().unwrap();
+        reader.seek_to_oldest().unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(reader.get_paths()[0], dir.path().join("log.2"));
+
+        // removed file cannot be found anymore
+        assert_eq!(reader.release_consumed().unwrap(), 1);
+        fs::remove_file(dir.path().join("log.2")).unwrap();
+        reader.seek_to_oldest().unwrap();
+        let error = reader.read(&mut [0; 4]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+        assert!(error.to_string().contains("log.2"));
+    }
+
+    #[test]
+    fn distance_between_offsets_in_different_files() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        let reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        let [rotated, newest] = [0, 1].map(|index| reader.get_inodes()[index]);
+        let at = |inode, offset| super::InodeAwareOffset {
+            inode,
+            offset,
+            fingerprint: None,
+            file_stamp: None,
+        };
+
+        assert_eq!(
+            reader.distance_between(at(rotated, 2), at(newest, 3)),
+            Some(7)
+        );
+        assert_eq!(
+            reader.distance_between(at(newest, 3), at(rotated, 2)),
+            Some(-7)
+        );
+        assert_eq!(
+            reader.distance_between(at(rotated, 6), at(newest, 0)),
+            Some(0)
+        );
+        // offsets past current end of the growing newest file are fine
+        assert_eq!(
+            reader.distance_between(at(newest, 7), at(newest, 9)),
+            Some(2)
+        );
+        // rotated file holds only 6 bytes
+        assert_eq!(
+            reader.distance_between(at(rotated, 100), at(newest, 0)),
+            None
+        );
+        assert_eq!(
+            reader.distance_between(at(newest, 0), at(newest, u64::MAX)),
+            None
+        );
+        let unknown = rotated.max(newest) + 1;
+        assert_eq!(reader.distance_between(at(unknown, 0), at(newest, 0)), None);
+    }
+
+    #[test]
+    fn lag_bytes_follows_growing_log() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        reader.read_exact(&mut [0; 1]).unwrap();
+        let committed = reader.get_persistent_offset();
+        assert_eq!(reader.lag_bytes(committed).unwrap(), 6);
+
+        writer.write_all(b"third\n").unwrap();
+        assert_eq!(reader.lag_bytes(committed).unwrap(), 12);
+        let oldest = reader.seek_to_oldest().unwrap();
+        assert_eq!(reader.lag_bytes(oldest).unwrap(), 19);
+        let latest = reader.seek_to_latest().unwrap();
+        assert_eq!(reader.lag_bytes(latest).unwrap(), 0);
+
+        let unknown = super::InodeAwareOffset {
+            inode: latest.inode + 1,
+            ..latest
+        };
+        assert!(reader.lag_bytes(unknown).is_err());
+        let past_end = super::InodeAwareOffset {
+            offset: 100,
+            ..oldest
+        };
+        assert_eq!(
+            reader.lag_bytes(past_end).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn validate_offset_does_not_move_reader() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        let position = reader.get_persistent_offset();
+        let at = |inode, offset| super::InodeAwareOffset {
+            inode,
+            offset,
+            fingerprint: None,
+            file_stamp: None,
+        };
+        let [rotated, newest] = [0, 1].map(|index| reader.get_inodes()[index]);
+
+        assert_eq!(
+            reader.validate_offset(at(rotated, 6)).unwrap(),
+            OffsetValidity::Valid
+        );
+        assert_eq!(
+            reader.validate_offset(at(newest, 7)).unwrap(),
+            OffsetValidity::Valid
+        );
+        assert_eq!(
+            reader.validate_offset(at(rotated, 7)).unwrap(),
+            OffsetValidity::PastEndOfFile { file_size: 6 }
+        );
+        assert_eq!(
+            reader.validate_offset(at(newest, 8)).unwrap(),
+            OffsetValidity::PastEndOfFile { file_size: 7 }
+        );
+        // newest file is measured again
+        writer.write_all(b"x").unwrap();
+        assert_eq!(
+            reader.validate_offset(at(newest, 8)).unwrap(),
+            OffsetValidity::Valid
+        );
+        assert_eq!(
+            reader
+                .validate_offset(at(rotated.max(newest) + 1, 0))
+                .unwrap(),
+            OffsetValidity::UnknownInode
+        );
+
+        assert_eq!(reader.get_persistent_offset(), position);
+        assert_eq!(read_line(&mut reader), "second\n");
+    }
+
+    #[test]
+    fn lines_persistent_report_offsets_of_first_bytes() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsec").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"ond\r\nthird\nlast").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        let [rotated, newest] = [0, 1].map(|index| reader.get_inodes()[index]);
+
+        let lines = reader
+            .lines_persistent()
+            .map(|line| {
+                let (offset, line) = line.unwrap();
+                (offset.inode, offset.offset, line)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                (rotated, 0, "first".to_string()),
+                (rotated, 6, "second".to_string()),
+                (newest, 5, "third".to_string()),
+                (newest, 11, "last".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn file_stamp_accepts_grown_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::builder()
+            .path(writer.path())
+            .record_file_stamps(true)
+            .open()
+            .unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        let offset = reader.get_persistent_offset();
+        assert!(offset.file_stamp.is_some());
+
+        writer.write_all(b"second\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(reader.seek_persistent(offset).unwrap(), None);
+        assert_eq!(read_line(&mut reader), "second\n");
+    }
+
+    #[rstest]
+    #[case::file_shrunk(|_: &mut FileStamp| {})]
+    #[case::modified_earlier(|stamp: &mut FileStamp| {
+        stamp.size = 0;
+        stamp.modified = u64::MAX;
+    })]
+    fn reused_inode_is_treated_as_missing(#[case] adjust_stamp: fn(&mut FileStamp)) {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = InodeAwareReader::builder()
+            .path(writer.path())
+            .record_file_stamps(true)
+            .open()
+            .unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        let mut offset = reader.get_persistent_offset();
+        drop(reader);
+
+        // replace the file keeping its name and pretend that the filesystem reused the inode
+        fs::remove_file(writer.path()).unwrap();
+        fs::write(writer.path(), b"new\n").unwrap();
+        offset.inode = file_id(writer.path()).unwrap();
+        adjust_stamp(offset.file_stamp.as_mut().unwrap());
+
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(
+            reader.validate_offset(offset).unwrap(),
+            OffsetValidity::UnknownInode
+        );
+        let error = reader.seek_persistent(offset).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+        assert_eq!(
+            reader
+                .seek_persistent_or(offset, MissingInodePolicy::StartOfOldest)
+                .unwrap(),
+            SeekOutcome::FellBack(MissingInodePolicy::StartOfOldest)
+        );
+        assert_eq!(read_line(&mut reader), "new\n");
+
+        // without the stamp replaced file can only be seen as truncated
+        offset.file_stamp = None;
+        assert!(reader.seek_persistent(offset).unwrap().is_some());
+    }
+
+    #[test]
+    fn progress_counts_bytes_of_all_files() {
+        let mut reader = fake_reader(&["first\n", "second\n"], vec![20, 10]).unwrap();
+        let progress = reader.progress().unwrap();
+        assert_eq!(
+            progress,
+            Progress {
+                bytes_read: 0,
+                bytes_total: 13,
+                current_item: 0,
+                item_count: 2,
+            }
+        );
+        assert_eq!(progress.to_string(), "1/2 files, 0.0%");
+
+        assert_eq!(read_line(&mut reader), "first\n");
+        reader.read_exact(&mut [0; 3]).unwrap();
+        let progress = reader.progress().unwrap();
+        assert_eq!((progress.bytes_read, progress.current_item), (9, 1));
+        assert_eq!(progress.to_string(), "2/2 files, 69.2%");
+
+        assert_eq!(read_line(&mut reader), "ond\n");
+        assert_eq!(reader.progress().unwrap().to_string(), "2/2 files, 100.0%");
+    }
+
+    #[test]
+    fn progress_follows_growing_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(reader.progress().unwrap().fraction(), 1.0);
+
+        writer.write_all(b"second\n").unwrap();
+        let progress = reader.progress().unwrap();
+        assert_eq!((progress.bytes_read, progress.bytes_total), (6, 13));
+        assert_eq!(progress.to_string(), "1/1 files, 46.2%");
+    }
+
+    #[test]
+    fn progress_of_empty_file_is_complete() {
+        let mut reader = fake_reader(&[""], vec![1]).unwrap();
+        assert_eq!(reader.progress().unwrap().to_string(), "1/1 files, 100.0%");
+    }
+
+    #[test]
+    fn reopen_current_follows_rotated_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+
+        writer.rotate().unwrap();
+        assert_eq!(reader.reopen_current().unwrap(), ReopenOutcome::Reopened);
+        assert_eq!(reader.get_paths(), [writer.path().with_extension("1")]);
+        assert_eq!(read_line(&mut reader), "second\n");
+    }
+
+    #[test]
+    fn reopen_current_reports_gone_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        reader.read_exact(&mut [0; 2]).unwrap();
+
+        fs::remove_file(writer.path().with_extension("1")).unwrap();
+        assert_eq!(reader.reopen_current().unwrap(), ReopenOutcome::Gone);
+        assert_eq!(reader.get_global_offset(), 2);
+    }
+
+    #[test]
+    fn reopen_current_reports_shrunk_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+
+        fs::OpenOptions::new()
+            .write(true)
+            .open(writer.path())
+            .unwrap()
+            .set_len(3)
+            .unwrap();
+        assert_eq!(
+            reader.reopen_current().unwrap(),
+            ReopenOutcome::Shrunk { size: 3 }
+        );
+        assert_eq!(read_line(&mut reader), "");
+    }
+
+    /// Release current file so that reading it fails once it is moved or removed.
+    fn release_current(reader: &mut InodeAwareReader) {
+        let item_index = reader.get_current_item_index();
+        assert!(reader.get_item_mut(item_index).unwrap().release().unwrap());
+    }
+
+    #[test]
+    fn read_resilient_finds_moved_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+
+        release_current(&mut reader);
+        writer.rotate().unwrap();
+        let mut buf = [0; 7];
+        assert_eq!(reader.read_resilient(&mut buf).unwrap(), 7);
+        assert_eq!(&buf, b"second\n");
+    }
+
+    #[test]
+    fn read_resilient_skips_removed_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+
+        release_current(&mut reader);
+        fs::remove_file(writer.path().with_extension("1")).unwrap();
+        let mut buf = [0; 6];
+        assert_eq!(reader.read_resilient(&mut buf).unwrap(), 6);
+        assert_eq!(&buf, b"third\n");
+
+        release_current(&mut reader);
+        fs::remove_file(writer.path()).unwrap();
+        let error = reader.read_resilient(&mut buf).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn mmap_and_buffered_readers_produce_same_output() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"third\nfourth\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"fifth\n").unwrap();
+        let collect = |mut reader: InodeAwareReader| {
+            reader
+                .lines_persistent()
+                .collect::<std::io::Result<Vec<_>>>()
+                .unwrap()
+        };
+
+        let buffered = collect(InodeAwareReader::from_rotated_logs(writer.path()).unwrap());
+        let mapped_reader = InodeAwareReader::from_rotated_logs_mmap(writer.path()).unwrap();
+        let mapped_items: Vec<_> = mapped_reader.iter().map(|file| file.is_mapped()).collect();
+        assert_eq!(mapped_items, [true, true, false]);
+        let mapped = collect(mapped_reader);
+        assert_eq!(buffered.len(), 5);
+        assert_eq!(mapped, buffered);
+
+        let mut reader = InodeAwareReader::from_rotated_logs_mmap(writer.path()).unwrap();
+        reader.seek_persistent(buffered[3].0).unwrap();
+        assert_eq!(read_line(&mut reader), "fourth\n");
+        assert_eq!(reader.get_persistent_offset(), buffered[4].0);
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn refresh_maps_rotated_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs_mmap(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert!(!reader.get_item(0).unwrap().is_mapped());
+
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+        reader.refresh().unwrap();
+        assert!(reader.get_item(0).unwrap().is_mapped());
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(read_line(&mut reader), "third\n");
+    }
+
+    #[test]
+    fn inode_lookups_follow_refresh() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        let first_inode = file_id(writer.path()).unwrap();
+        assert_eq!(reader.get_inode_for_item(0), Some(first_inode));
+        assert_eq!(reader.get_inode_for_item(1), None);
+
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        reader.refresh().unwrap();
+        let second_inode = file_id(writer.path()).unwrap();
+        assert_eq!(reader.get_inode_for_item(1), Some(second_inode));
+        assert_eq!(reader.get_item_index_by_inode(first_inode), Some(0));
+        assert_eq!(reader.get_item_index_by_inode(second_inode), Some(1));
+        assert_eq!(reader.get_item_index_by_inode(second_inode + 1), None);
+
+        let offset = super::InodeAwareOffset {
+            inode: second_inode,
+            offset: 1,
+            ..Default::default()
+        };
+        reader.seek_persistent(offset).unwrap();
+        assert_eq!(read_line(&mut reader), "econd\n");
+    }
+
+    #[test]
+    fn hard_linked_rotated_log_is_rejected() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        fs::hard_link(writer.path(), writer.path().with_extension("1")).unwrap();
+        let error = InodeAwareReader::from_rotated_logs(writer.path())
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn missing_log_is_attached_once_created() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        assert_eq!(
+            InodeAwareReader::from_rotated_logs(&path)
+                .err()
+                .unwrap()
+                .kind(),
+            std::io::ErrorKind::NotFound
+        );
+        let mut reader = InodeAwareReader::builder()
+            .path(&path)
+            .allow_missing(true)
+            .open()
+            .unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(read_line(&mut reader), "");
+        assert_eq!(reader.get_persistent_offset(), Default::default());
+        assert_eq!(reader.get_current_inode(), 0);
+        assert_eq!(reader.get_current_path(), path);
+        assert!(!reader.try_attach().unwrap());
+
+        fs::write(&path, "first\n").unwrap();
+        assert!(reader.try_attach().unwrap());
+        assert_eq!(reader.get_inodes(), [file_id(&path).unwrap()]);
+        assert_eq!(reader.get_current_inode(), file_id(&path).unwrap());
+        assert_eq!(read_line(&mut reader), "first\n");
+    }
+
+    #[test]
+    fn missing_log_keeps_rotated_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::write(path.with_extension("1"), "old\n").unwrap();
+        let mut reader = InodeAwareReader::builder()
+            .path(&path)
+            .allow_missing(true)
+            .auto_refresh(true)
+            .open()
+            .unwrap();
+        assert_eq!(read_line(&mut reader), "old\n");
+        assert_eq!(read_line(&mut reader), "");
+
+        fs::write(&path, "new\n").unwrap();
+        assert_eq!(read_line(&mut reader), "new\n");
+        assert_eq!(reader.len(), 2);
+    }
+
+    /// Point `link` to `target`, replacing previous link.
+    #[cfg(unix)]
+    fn repoint(link: &Path, target: &Path) {
+        if fs::symlink_metadata(link).is_ok() {
+            fs::remove_file(link).unwrap();
+        }
+        std::os::unix::fs::symlink(target, link).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn watched_link_target_is_switched_on_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let (first, second) = (dir.path().join("first.log"), dir.path().join("second.log"));
+        fs::write(&first, "first\n").unwrap();
+        fs::write(&second, "second\n").unwrap();
+        let link = dir.path().join("current");
+        fs::write(link.with_extension("1"), "unrelated\n").unwrap();
+        repoint(&link, &first);
+
+        let mut reader = InodeAwareReader::builder()
+            .path(&link)
+            .symlink_policy(super::SymlinkPolicy::WatchLinkTarget)
+            .open()
+            .unwrap();
+        assert_eq!(reader.get_paths(), [first.canonicalize().unwrap()]);
+        assert_eq!(read_line(&mut reader), "first\n");
+
+        repoint(&link, &second);
+        assert_eq!(read_line(&mut reader), "");
+        let outcome = reader.refresh().unwrap();
+        assert_eq!(
+            outcome,
+            RefreshOutcome {
+                new_files: 1,
+                active_file_changed: true
+            }
+        );
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(
+            reader.get_paths(),
+            [
+                first.canonicalize().unwrap(),
+                second.canonicalize().unwrap()
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn link_target_rotations_are_tracked() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("app.log");
+        fs::write(target.with_extension("log.1"), "old\n").unwrap();
+        fs::write(&target, "new\n").unwrap();
+        let link = dir.path().join("current");
+        repoint(&link, &target);
+
+        let mut reader = InodeAwareReader::builder()
+            .path(&link)
+            .symlink_policy(super::SymlinkPolicy::FollowAndTrackTarget)
+            .open()
+            .unwrap();
+        assert_eq!(read_line(&mut reader), "old\n");
+        assert_eq!(read_line(&mut reader), "new\n");
+
+        let other = dir.path().join("other.log");
+        fs::write(&other, "other\n").unwrap();
+        repoint(&link, &other);
+        assert_eq!(reader.refresh().unwrap(), RefreshOutcome::default());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn followed_link_searches_rotations_next_to_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("app.log");
+        fs::write(target.with_extension("log.1"), "old\n").unwrap();
+        fs::write(&target, "new\n").unwrap();
+        let link = dir.path().join("current");
+        repoint(&link, &target);
+
+        let mut reader = InodeAwareReader::from_rotated_logs(&link).unwrap();
+        assert_eq!(reader.len(), 1);
+        assert_eq!(read_line(&mut reader), "new\n");
+    }
+
+    #[test]
+    fn from_rotated_logs_at_restores_offset() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+        let offset = super::InodeAwareOffset {
+            inode: file_id(writer.path().with_extension("1")).unwrap(),
+            offset: 6,
+            ..Default::default()
+        };
+
+        let (mut reader, outcome) = InodeAwareReader::from_rotated_logs_at(
+            writer.path(),
+            1,
+            Some(offset),
+            MissingInodePolicy::Error,
+        )
+        .unwrap();
+        assert_eq!(outcome, SeekOutcome::Restored);
+        assert_eq!(read_line(&mut reader), "second\n");
+
+        let (mut reader, outcome) = InodeAwareReader::from_rotated_logs_at(
+            writer.path(),
+            1,
+            None,
+            MissingInodePolicy::Error,
+        )
+        .unwrap();
+        assert_eq!(outcome, SeekOutcome::Restored);
+        assert_eq!(read_line(&mut reader), "first\n");
+    }
+
+    #[test]
+    fn from_rotated_logs_at_applies_missing_inode_policy() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        let vanished = super::InodeAwareOffset {
+            inode: file_id(writer.path()).unwrap() + 1,
+            offset: 3,
+            ..Default::default()
+        };
+
+        let (mut reader, outcome) = InodeAwareReader::from_rotated_logs_at(
+            writer.path(),
+            1,
+            Some(vanished),
+            MissingInodePolicy::StartOfOldest,
+        )
+        .unwrap();
+        assert_eq!(
+            outcome,
+            SeekOutcome::FellBack(MissingInodePolicy::StartOfOldest)
+        );
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(
+            reader.get_missing_inode_policy(),
+            MissingInodePolicy::StartOfOldest
+        );
+
+        let error = InodeAwareReader::from_rotated_logs_at(
+            writer.path(),
+            1,
+            Some(vanished),
+            MissingInodePolicy::Error,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[rstest]
+    #[case::single_file(&["first\n"])]
+    #[case::rotated_files(&["first\n", "second\n", "third\n"])]
+    #[case::empty_rotated_file(&["first\n", "", "second\n"])]
+    fn offset_conversions_match_position(#[case] contents: &[&str]) {
+        let inodes = (1..=contents.len() as u64).collect();
+        let mut reader = fake_reader(contents, inodes).unwrap();
+        let total_size = reader.get_total_size().unwrap();
+        for global in 0..=total_size {
+            reader.seek(std::io::SeekFrom::Start(global)).unwrap();
+            let persistent = reader.get_persistent_offset();
+            assert_eq!(reader.to_persistent(global), Some(persistent));
+            assert_eq!(reader.to_global(persistent), Some(global));
+        }
+    }
+
+    #[test]
+    fn offset_conversions_reject_unknown_offsets() {
+        let reader = fake_reader(&["first\n", "second\n"], vec![20, 10]).unwrap();
+        let offset = |inode, offset| super::InodeAwareOffset {
+            inode,
+            offset,
+            ..Default::default()
+        };
+        assert_eq!(reader.to_global(offset(20, 6)), Some(6));
+        assert_eq!(reader.to_global(offset(20, 7)), None);
+        assert_eq!(reader.to_global(offset(30, 0)), None);
+        assert_eq!(reader.to_global(offset(10, 100)), Some(106));
+        assert_eq!(reader.to_persistent(6), Some(offset(10, 0)));
+
+        let empty = fake_reader(&[], vec![]).unwrap();
+        assert_eq!(empty.to_persistent(0), None);
+    }
+
+    fn set_mtime(path: &Path, modified: std::time::SystemTime) {
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_times(fs::FileTimes::new().set_modified(modified))
+            .unwrap();
+    }
+
+    #[test]
+    fn old_rotated_logs_are_skipped() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"oldest\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"recent\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"live\n").unwrap();
+        let day_ago = std::time::SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+        let oldest = writer.path().with_extension("2");
+        set_mtime(&oldest, day_ago - Duration::from_secs(1));
+        set_mtime(writer.path(), day_ago - Duration::from_secs(1));
+
+        let mut reader = InodeAwareReader::builder()
+            .path(writer.path())
+            .min_mtime(day_ago)
+            .open()
+            .unwrap();
+        assert_eq!(reader.get_skipped_files(), [oldest]);
+        assert_eq!(reader.len(), 2);
+        assert_eq!(read_line(&mut reader), "recent\n");
+        assert_eq!(read_line(&mut reader), "live\n");
+
+        let reader = InodeAwareReader::builder()
+            .path(writer.path())
+            .max_age(Duration::from_secs(2 * 24 * 60 * 60))
+            .open()
+            .unwrap();
+        assert!(reader.get_skipped_files().is_empty());
+        assert_eq!(reader.len(), 3);
+    }
+
+    #[test]
+    fn tiny_buffer_capacity_reads_everything() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsec").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"ond\nthird\n").unwrap();
+
+        let mut reader =
+            InodeAwareReader::from_rotated_logs_with_capacity(writer.path(), 2, 1).unwrap();
+        assert_eq!(reader.get_buffer_capacity(), 1);
+        assert_eq!(reader.get_item(0).unwrap().get_ref().unwrap().capacity(), 1);
+        assert_eq!(read_line(&mut reader), "first\n");
+        let offset = reader.get_persistent_offset();
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "second\nthird\n");
+
+        reader.seek_persistent(offset).unwrap();
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(read_line(&mut reader), "third\n");
+
+        let default = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(default.get_buffer_capacity(), 8 * 1024);
+    }
+
+    #[test]
+    fn stat_reports_sizes_of_items() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        for item_index in 0..reader.len() {
+            assert_eq!(
+                reader.stat_item(item_index).unwrap().len(),
+                reader.get_item_size(item_index).unwrap()
+            );
+        }
+        assert_eq!(reader.stat_current().unwrap().len(), 6);
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(reader.stat_current().unwrap().len(), 7);
+
+        let error = reader.stat_item(2).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn stat_of_deleted_released_item_mentions_path() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        let rotated = reader.get_paths()[0].clone();
+        assert!(reader.get_item_mut(0).unwrap().release().unwrap());
+        fs::remove_file(&rotated).unwrap();
+
+        let error = reader.stat_item(0).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+        assert!(error.to_string().contains(&rotated.display().to_string()));
+        assert!(reader.stat_item(1).is_ok());
+    }
+
+    #[test]
+    fn verify_integrity_reports_drift() {
+        let (dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        let report = reader.verify_integrity().unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.items, [ItemIntegrity::Ok; 3]);
+
+        let offset = reader.get_persistent_offset();
+        let paths = reader.get_paths().to_vec();
+        reader.get_item_mut(1).unwrap().release().unwrap();
+        fs::remove_file(&paths[1]).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&paths[0])
+            .unwrap()
+            .set_len(2)
+            .unwrap();
+        fs::rename(writer.path(), dir.path().join("moved")).unwrap();
+        fs::write(writer.path(), b"fourth\n").unwrap();
+        let report = reader.verify_integrity().unwrap();
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.items,
+            [
+                ItemIntegrity::Shrunk { by: 4 },
+                ItemIntegrity::Missing,
+                ItemIntegrity::Replaced {
+                    new_inode: file_id(writer.path()).unwrap()
+                },
+            ]
+        );
+        assert_eq!(reader.get_persistent_offset(), offset);
+    }
+
+    #[test]
+    fn verify_integrity_checks_read_part_of_last_file() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        fs::File::options()
+            .write(true)
+            .open(writer.path())
+            .unwrap()
+            .set_len(4)
+            .unwrap();
+        let report = reader.verify_integrity().unwrap();
+        assert_eq!(report.items, [ItemIntegrity::Shrunk { by: 2 }]);
+    }
+
+    #[test]
+    fn current_item_is_read_to_its_end() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"third\nfourth").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        reader.read_exact(&mut [0; 3]).unwrap();
+
+        let mut buf = b"fir".to_vec();
+        assert_eq!(reader.read_current_item_to_end(&mut buf).unwrap(), 10);
+        assert_eq!(buf, b"first\nsecond\n");
+        assert_eq!(reader.get_global_offset(), 13);
+
+        assert_eq!(read_line(&mut reader), "third\n");
+        buf.clear();
+        assert_eq!(reader.read_current_item_to_end(&mut buf).unwrap(), 6);
+        assert_eq!(buf, b"fourth");
+        writer.write_all(b"\nfifth\n").unwrap();
+        assert_eq!(reader.read_current_item_to_end(&mut buf).unwrap(), 7);
+        assert_eq!(reader.read_current_item_to_end(&mut buf).unwrap(), 0);
+        assert_eq!(buf, b"fourth\nfifth\n");
+    }
+
+    #[test]
+    fn lines_of_current_item_are_visited() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\nthird\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"fourth\nfifth\nsixth").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+
+        let mut lines = vec![];
+        let line_count = reader
+            .for_each_line_in_current_item(|line| lines.push(line.to_string()))
+            .unwrap();
+        assert_eq!(line_count, 2);
+        assert_eq!(lines, ["second\n", "third\n"]);
+
+        assert_eq!(read_line(&mut reader), "fourth\n");
+        lines.clear();
+        reader
+            .for_each_line_in_current_item(|line| lines.push(line.to_string()))
+            .unwrap();
+        assert_eq!(lines, ["fifth\n", "sixth"]);
+        assert_eq!(read_line(&mut reader), "");
+    }
+
+    #[test]
+    fn truncation_is_detected_on_eof() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = InodeAwareReader::builder()
+            .path(writer.path())
+            .detect_truncation(true)
+            .open()
+            .unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(read_line(&mut reader), "");
+
+        fs::write(writer.path(), b"third\n").unwrap();
+        let error = reader.read_line(&mut String::new()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        let detected = error
+            .get_ref()
+            .unwrap()
+            .downcast_ref::<TruncationDetected>();
+        assert_eq!(
+            detected,
+            Some(&TruncationDetected {
+                inode: file_id(writer.path()).unwrap(),
+                path: writer.path().to_path_buf(),
+                previous_offset: 13,
+                new_size: 6,
+            })
+        );
+        assert!(reader.read(&mut [0; 4]).is_err());
+
+        assert_eq!(reader.rewind_after_truncation().unwrap(), 0);
+        assert_eq!(read_line(&mut reader), "third\n");
+        assert_eq!(read_line(&mut reader), "");
+    }
+
+    #[test]
+    fn truncation_is_not_detected_when_disabled() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert!(!reader.get_detect_truncation());
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+
+        fs::write(writer.path(), b"third\n").unwrap();
+        assert_eq!(read_line(&mut reader), "");
+        reader.set_detect_truncation(true);
+        assert!(reader.read(&mut [0; 4]).is_err());
+    }
+
+    fn open_item_count(reader: &InodeAwareReader) -> usize {
+        reader.iter().filter(|item| !item.is_released()).count()
+    }
+
+    /// Opener counting calls of `File::open`.
+    #[derive(Default)]
+    struct CountingOpener {
+        opened: AtomicUsize,
+    }
+
+    impl CountingOpener {
+        fn opened(&self) -> usize {
+            self.opened.load(AtomicOrdering::SeqCst)
+        }
+    }
+
+    impl FileOpener for CountingOpener {
+        fn open(&self, path: &Path) -> std::io::Result<fs::File> {
+            self.opened.fetch_add(1, AtomicOrdering::SeqCst);
+            fs::File::open(path)
+        }
+    }
+
+    fn counting_codecs() -> (CodecRegistry, Arc<CountingOpener>) {
+        let opener = Arc::new(CountingOpener::default());
+        let mut codecs = CodecRegistry::new();
+        codecs.set_opener(opener.clone());
+        (codecs, opener)
+    }
+
+    #[test]
+    fn lazily_opened_files_are_opened_when_entered() {
+        let (_dir, mut writer) = rotating_log();
+        for content in ["first\n", "second\n"] {
+            writer.write_all(content.as_bytes()).unwrap();
+            writer.rotate().unwrap();
+        }
+        writer.write_all(b"third\nfourth\n").unwrap();
+        let (eager_codecs, eager_opener) = counting_codecs();
+        let mut eager = InodeAwareReader::builder()
+            .path(writer.path())
+            .codecs(eager_codecs)
+            .open()
+            .unwrap();
+        let (codecs, opener) = counting_codecs();
+        let mut reader = InodeAwareReader::builder()
+            .path(writer.path())
+            .codecs(codecs)
+            .lazy_open(true)
+            .open()
+            .unwrap();
+        assert_eq!(eager_opener.opened(), 3);
+        assert_eq!(opener.opened(), 0);
+        assert_eq!(reader.item_sizes(), eager.item_sizes());
+
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(opener.opened(), 1);
+        assert_eq!(read_line(&mut eager), "first\n");
+        assert_eq!(read_line(&mut eager), "second\n");
+        assert_eq!(read_line(&mut eager), "third\n");
+        let offset = eager.get_persistent_offset();
+
+        reader.seek_persistent(offset).unwrap();
+        assert_eq!(read_line(&mut reader), "fourth\n");
+        assert_eq!(read_line(&mut eager), "fourth\n");
+        assert_eq!(
+            reader.get_persistent_offset(),
+            eager.get_persistent_offset()
+        );
+        // file in between was skipped without being opened
+        assert!(reader.get_item(1).unwrap().is_released());
+        assert_eq!(opener.opened(), 2);
+        assert_eq!(reader.get_total_size().unwrap(), 26);
+        assert_eq!(opener.opened(), 2);
+        assert_eq!(eager_opener.opened(), 3);
+    }
+
+    #[test]
+    fn auto_release_keeps_one_file_open() {
+        let (_dir, mut writer) = rotating_log();
+        for content in ["first\n", "second\n"] {
+            writer.write_all(content.as_bytes()).unwrap();
+            writer.rotate().unwrap();
+        }
+        writer.write_all(b"third\n").unwrap();
+        let (codecs, opener) = counting_codecs();
+        let mut reader = InodeAwareReader::builder()
+            .path(writer.path())
+            .codecs(codecs)
+            .lazy_open(true)
+            .auto_release(true)
+            .open()
+            .unwrap();
+        assert!(reader.get_auto_release());
+        for (opened, expected) in (1..).zip(["first\n", "second\n", "third\n"]) {
+            assert_eq!(read_line(&mut reader), expected);
+            assert_eq!(open_item_count(&reader), 1);
+            assert_eq!(opener.opened(), opened);
+        }
+        // reading the newest file again does not reopen it
+        assert_eq!(read_line(&mut reader), "");
+        assert_eq!(opener.opened(), 3);
+
+        let mut content = String::new();
+        reader.seek_to_oldest().unwrap();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "first\nsecond\nthird\n");
+        assert_eq!(open_item_count(&reader), 1);
+        // released files are reopened once each
+        assert_eq!(opener.opened(), 5);
+    }
+
+    #[test]
+    fn resolution_report_shows_gap_in_numbering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::write(&path, b"live\n").unwrap();
+        fs::write(dir.path().join("log.1"), b"first\n").unwrap();
+        fs::write(dir.path().join("log.3"), b"third\n").unwrap();
+
+        let mut reader = InodeAwareReader::from_rotated_logs_with_depth(&path, 3).unwrap();
+        let entry = |name: &str, size: Option<u64>, skipped: Option<SkipReason>| {
+            let path = dir.path().join(name);
+            ResolutionEntry {
+                existed: size.is_some(),
+                inode: size.map(|_| file_id(&path).unwrap()),
+                path,
+                size,
+                skipped,
+            }
+        };
+        assert_eq!(
+            reader.resolution_report().entries,
+            [
+                entry("log", Some(5), None),
+                entry("log.1", Some(6), None),
+                entry("log.2", None, None),
+                entry("log.3", Some(6), Some(SkipReason::AfterGap)),
+            ]
+        );
+        let summary = reader.resolution_report().to_string();
+        assert!(summary.contains("log.2 (missing)"));
+        assert!(summary.contains("skipped: after a gap in numbering"));
+
+        let missing = super::InodeAwareOffset {
+            inode: file_id(dir.path().join("log.3")).unwrap(),
+            ..Default::default()
+        };
+        let error = reader.seek_persistent(missing).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+        assert!(error.to_string().contains(&summary));
+    }
+
+    #[test]
+    fn resolution_report_shows_logs_beyond_depth() {
+        let (dir, mut writer) = rotating_log();
+        for content in ["first\n", "second\n"] {
+            writer.write_all(content.as_bytes()).unwrap();
+            writer.rotate().unwrap();
+        }
+        let reader = InodeAwareReader::from_rotated_logs_with_depth(writer.path(), 1).unwrap();
+        let entries = &reader.resolution_report().entries;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].path, dir.path().join("log.2"));
+        assert_eq!(entries[2].skipped, Some(SkipReason::BeyondDepth));
+        assert!(entries.iter().all(|entry| entry.existed));
+    }
+
+    #[test]
+    fn rotated_logs_are_found_in_olddir() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::create_dir(dir.path().join("archive")).unwrap();
+        fs::write(dir.path().join("archive/log.2"), b"first\n").unwrap();
+        fs::write(dir.path().join("archive/log.1"), b"second\n").unwrap();
+        fs::write(&path, b"third\n").unwrap();
+
+        let mut reader = InodeAwareReader::builder()
+            .path(&path)
+            .olddir("archive")
+            .open()
+            .unwrap();
+        assert_eq!(
+            reader.get_paths(),
+            [
+                dir.path().join("archive/log.2"),
+                dir.path().join("archive/log.1"),
+                path.clone(),
+            ]
+        );
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "first\nsecond\nthird\n");
+    }
+
+    #[test]
+    fn items_are_described() {
+        let (dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "first\n");
+        writer.write_all(b"third\n").unwrap();
+
+        let rotated = dir.path().join("log.1");
+        assert_eq!(
+            reader.describe_items().unwrap(),
+            [
+                ItemDescription {
+                    index: 0,
+                    inode: file_id(&rotated).unwrap(),
+                    path: rotated,
+                    size: 6,
+                    current: false,
+                },
+                ItemDescription {
+                    index: 1,
+                    path: writer.path().to_path_buf(),
+                    inode: file_id(writer.path()).unwrap(),
+                    size: 13,
+                    current: true,
+                },
+            ]
+        );
+        assert_eq!(read_line(&mut reader), "second\n");
+    }
+
+    #[test]
+    fn lines_are_tracked_across_files() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"a\nb\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"c\nd\ne").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(reader.current_line(), None);
+        assert_eq!(reader.enable_line_tracking().unwrap(), 1);
+
+        let mut positions = vec![];
+        for _ in 0..3 {
+            read_line(&mut reader);
+            positions.push((reader.current_line(), reader.current_line_in_item()));
+        }
+        assert_eq!(
+            positions,
+            [(Some(2), Some(2)), (Some(3), Some(1)), (Some(4), Some(2))]
+        );
+
+        // seeking backwards makes count unknown until it is recounted
+        reader.seek(SeekFrom::Start(2)).unwrap();
+        assert_eq!(reader.current_line(), None);
+        assert_eq!(reader.current_line_in_item(), None);
+        assert_eq!(reader.recount_lines().unwrap(), 2);
+        assert_eq!(reader.current_line_in_item(), Some(2));
+
+        // reading through `Read` counts lines in each file it crosses
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "b\nc\nd\ne");
+        assert_eq!(reader.current_line(), Some(5));
+        assert_eq!(reader.current_line_in_item(), Some(3));
+    }
+
+    #[test]
+    fn line_tracking_enabled_midway_counts_previous_lines() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"a\nb\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"c\nd\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        reader.seek(SeekFrom::Start(6)).unwrap();
+
+        assert_eq!(reader.enable_line_tracking().unwrap(), 4);
+        assert_eq!(reader.current_line_in_item(), Some(2));
+        assert_eq!(read_line(&mut reader), "d\n");
+        assert_eq!(reader.current_line(), Some(5));
+
+        reader.disable_line_tracking();
+        assert_eq!(reader.current_line(), None);
+    }
+
+    #[test]
+    fn line_too_long_is_skipped_across_files() {
+        let (dir, mut writer) = rotating_log();
+        writer.write_all(b"ok\nlong line").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b" continues\nnext\n").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        // limit includes the delimiter
+        reader.set_max_line_length(Some(3));
+        assert_eq!(read_line(&mut reader), "ok\n");
+
+        reader.set_max_line_length(Some(8));
+        let mut line = String::new();
+        let error = reader.read_line(&mut line).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.get_ref().unwrap().downcast_ref::<LineTooLong>(),
+            Some(&LineTooLong {
+                start: InodeAwareOffset {
+                    inode: file_id(dir.path().join("log.1")).unwrap(),
+                    offset: 3,
+                    fingerprint: None,
+                    file_stamp: None,
+                },
+                length: 20,
+                max: 8,
+            })
+        );
+        assert_eq!(line, "");
+        assert_eq!(reader.get_persistent_offset().offset, 11);
+        assert_eq!(read_line(&mut reader), "next\n");
+    }
+
+    #[test]
+    fn tail_lines_span_rotated_files() {
+        let (dir, mut writer) = rotating_log();
+        writer.write_all(b"one\ntwo\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"three\r\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"four").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(read_line(&mut reader), "one\n");
+
+        let at = |path: &Path, offset| InodeAwareOffset {
+            inode: file_id(path).unwrap(),
+            offset,
+            fingerprint: None,
+            file_stamp: None,
+        };
+        let (oldest, rotated) = (dir.path().join("log.2"), dir.path().join("log.1"));
+        assert_eq!(
+            reader.tail_lines(3).unwrap(),
+            [
+                (at(&oldest, 4), "two".to_string()),
+                (at(&rotated, 0), "three".to_string()),
+                (at(writer.path(), 0), "four".to_string()),
+            ]
+        );
+        assert_eq!(reader.tail_lines(10).unwrap().len(), 4);
+        assert_eq!(reader.tail_lines(0).unwrap(), []);
+        // position is not changed
+        assert_eq!(read_line(&mut reader), "two\n");
+    }
+
+    #[test]
+    fn tail_lines_skip_empty_newest_file() {
+        let (dir, mut writer) = rotating_log();
+        writer.write_all(b"one\ntwo\n").unwrap();
+        writer.rotate().unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+
+        let rotated = dir.path().join("log.1");
+        let lines = reader.tail_lines(1).unwrap();
+        assert_eq!(
+            lines,
+            [(
+                InodeAwareOffset {
+                    inode: file_id(&rotated).unwrap(),
+                    offset: 4,
+                    fingerprint: None,
+                    file_stamp: None,
+                },
+                "two".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn rotations_behind_count_generations() {
+        let (dir, mut writer) = rotating_log();
+        for _ in 0..2 {
+            writer.write_all(b"line\n").unwrap();
+            writer.rotate().unwrap();
+        }
+        let reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        let behind = [
+            dir.path().join("log.2"),
+            dir.path().join("log.1"),
+            writer.path().to_path_buf(),
+        ]
+        .iter()
+        .map(|path| {
+            reader.rotations_behind(InodeAwareOffset {
+                inode: file_id(path).unwrap(),
+                ..Default::default()
+            })
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(behind, [Some(2), Some(1), Some(0)]);
+
+        let unknown = InodeAwareOffset {
+            inode: u64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(reader.rotations_behind(unknown), None);
+    }
+
+    #[rstest]
+    #[case::line_straddles_rotation(b"one\ntw", b"o\nthree\n", &[Some(("log.1", 0)), Some(("log.1", 4)), Some(("log", 2)), None])]
+    #[case::rotated_file_ends_with_newline(b"one\n", b"two", &[Some(("log.1", 0)), Some(("log", 0)), None])]
+    #[case::empty_newest_file(b"one\n", b"", &[Some(("log.1", 0)), None])]
+    fn offsets_of_lines_are_found(
+        #[case] rotated: &[u8],
+        #[case] newest: &[u8],
+        #[case] expected: &[Option<(&str, u64)>],
+    ) {
+        let (dir, mut writer) = rotating_log();
+        writer.write_all(rotated).unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(newest).unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        let first_line = read_line(&mut reader);
+
+        let offsets = (1..=expected.len() as u64)
+            .map(|line| reader.offset_of_line(line).unwrap())
+            .collect::<Vec<_>>();
+        let expected = expected
+            .iter()
+            .map(|line_start| {
+                line_start.map(|(name, offset)| InodeAwareOffset {
+                    inode: file_id(dir.path().join(name)).unwrap(),
+                    offset,
+                    fingerprint: None,
+                    file_stamp: None,
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, expected);
+        assert_eq!(
+            reader.offset_of_line(0).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+
+        // scanning from checkpoint gives the same results
+        let last_line = expected.iter().flatten().count();
+        let checkpoint = (expected[last_line - 1].unwrap(), last_line as u64);
+        for (line, offset) in (1..).zip(&expected) {
+            assert_eq!(
+                reader.offset_of_line_from(checkpoint, line).unwrap(),
+                *offset
+            );
+        }
+        // position is not changed
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        let content = [rotated, newest].concat();
+        assert_eq!(first_line + &rest, String::from_utf8(content).unwrap());
+    }
+
+    #[test]
+    fn lazily_opened_compressed_logs_are_opened_right_away() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::write(dir.path().join("log.2.raw"), b"first\n").unwrap();
+        fs::write(dir.path().join("log.1"), b"second\n").unwrap();
+        fs::write(&path, b"third\n").unwrap();
+        let (mut codecs, opener) = counting_codecs();
+        codecs.register(".raw", |mut input, output| {
+            std::io::copy(&mut input, output).map(drop)
+        });
+        let mut reader = InodeAwareReader::builder()
+            .path(&path)
+            .search_depth(2)
+            .codecs(codecs)
+            .lazy_open(true)
+            .open()
+            .unwrap();
+        // size of decompressed content is only known after decompressing it
+        assert_eq!(opener.opened(), 1);
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "first\nsecond\nthird\n");
+        assert_eq!(opener.opened(), 3);
+    }
+
+    #[test]
+    fn lazily_opened_files_are_found_after_rotation() {
+        let (dir, mut writer) = rotating_log();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        let mut reader = InodeAwareReader::builder()
+            .path(writer.path())
+            .lazy_open(true)
+            .open()
+            .unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+
+        assert_eq!(read_line(&mut reader), "first\n");
+        assert_eq!(read_line(&mut reader), "second\n");
+        assert_eq!(
+            reader.get_paths(),
+            [dir.path().join("log.2"), dir.path().join("log.1")]
+        );
+    }
+
+    #[test]
+    fn rest_of_long_line_is_skipped_once_written() {
+        let (_dir, mut writer) = rotating_log();
+        writer.write_all(b"ok\nlong li").unwrap();
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        reader.set_max_line_length(Some(4));
+        assert_eq!(read_line(&mut reader), "ok\n");
+        let error = reader.read_line(&mut String::new()).unwrap_err();
+        assert!(error.get_ref().unwrap().is::<LineTooLong>());
+        assert_eq!(read_line(&mut reader), "");
+
+        writer.write_all(b"ne\nok\n").unwrap();
+        assert_eq!(read_line(&mut reader), "ok\n");
+    }
+
+    #[test]
+    fn long_line_of_attached_log_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        let mut reader = InodeAwareReader::builder()
+            .path(&path)
+            .allow_missing(true)
+            .auto_refresh(true)
+            .open()
+            .unwrap();
+        reader.set_max_line_length(Some(4));
+        assert_eq!(read_line(&mut reader), "");
+
+        // log is attached by the same read that finds the line too long
+        fs::write(&path, "long line\n").unwrap();
+        let error = reader.read_line(&mut String::new()).unwrap_err();
+        let too_long = error.get_ref().unwrap().downcast_ref::<LineTooLong>();
+        assert_eq!(too_long.unwrap().start.inode, file_id(&path).unwrap());
+        assert_eq!(too_long.unwrap().start.offset, 0);
     }
 }
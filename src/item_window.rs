@@ -0,0 +1,153 @@
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+
+/// Reader that exposes part of underlying reader starting at `start` and optionally ending at `end` (exclusive) as if it
+/// was a standalone reader. Produced by `Multireader::split_at`.
+///
+/// Without `end`, window extends to the end of underlying reader (which may still grow).
+pub struct ItemWindow<R> {
+    inner: R,
+    start: u64,
+    end: Option<u64>,
+    /// position relative to `start`
+    position: u64,
+}
+
+impl<R: Seek> ItemWindow<R> {
+    /// Create a window into `inner`, seeking it to `start`.
+    pub fn new(mut inner: R, start: u64, end: Option<u64>) -> io::Result<Self> {
+        inner.seek(SeekFrom::Start(start))?;
+        Ok(Self {
+            inner,
+            start,
+            end,
+            position: 0,
+        })
+    }
+
+    /// Destroy the window and return underlying reader. Its position is left wherever window put it.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Get reference to underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Offset in underlying reader at which window starts.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Offset in underlying reader at which window ends, if it is limited.
+    pub fn end(&self) -> Option<u64> {
+        self.end
+    }
+
+    /// Number of bytes that can be read before reaching the end of window, None if window is not limited.
+    fn remaining(&self) -> Option<u64> {
+        self.end
+            .map(|end| end.saturating_sub(self.start + self.position))
+    }
+}
+
+impl<R: Read + Seek> Read for ItemWindow<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let max_len = match self.remaining() {
+            Some(remaining) => buf.len().min(remaining as usize),
+            None => buf.len(),
+        };
+        let size_read = self.inner.read(&mut buf[..max_len])?;
+        self.position += size_read as u64;
+        Ok(size_read)
+    }
+}
+
+impl<R: BufRead + Seek> BufRead for ItemWindow<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let remaining = self.remaining();
+        let buf = self.inner.fill_buf()?;
+        Ok(match remaining {
+            Some(remaining) => &buf[..buf.len().min(remaining as usize)],
+            None => buf,
+        })
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.position += amt as u64;
+    }
+}
+
+impl<R: Seek> Seek for ItemWindow<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::Current(offset) => self.position as i64 + offset,
+            SeekFrom::End(offset) => {
+                let end = match self.end {
+                    Some(end) => end,
+                    None => self.inner.seek(SeekFrom::End(0))?,
+                };
+                // window starting past the end of underlying reader is empty
+                end.saturating_sub(self.start) as i64 + offset
+            }
+        };
+        if target < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "negative real offset after seek",
+            ));
+        }
+        self.inner
+            .seek(SeekFrom::Start(self.start + target as u64))?;
+        self.position = target as u64;
+        Ok(self.position)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor, Read, Seek, SeekFrom};
+
+    use super::ItemWindow;
+
+    fn window(start: u64, end: Option<u64>) -> ItemWindow<Cursor<Vec<u8>>> {
+        ItemWindow::new(Cursor::new(b"hello\nworld".to_vec()), start, end).unwrap()
+    }
+
+    #[test]
+    fn window_limits_reads() {
+        let mut content = String::new();
+        window(2, Some(8)).read_to_string(&mut content).unwrap();
+        assert_eq!(content, "llo\nwo");
+
+        content.clear();
+        window(6, None).read_to_string(&mut content).unwrap();
+        assert_eq!(content, "world");
+
+        let mut lines = window(3, Some(9)).lines();
+        assert_eq!(lines.next().unwrap().unwrap(), "lo");
+        assert_eq!(lines.next().unwrap().unwrap(), "wor");
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn window_seeks_relative_to_start() {
+        let mut window = window(2, Some(8));
+        assert_eq!(window.seek(SeekFrom::End(-2)).unwrap(), 4);
+        let mut buf = [0; 4];
+        assert_eq!(window.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], b"wo");
+        assert_eq!(window.seek(SeekFrom::Current(-6)).unwrap(), 0);
+        assert!(window.seek(SeekFrom::Current(-1)).is_err());
+        assert_eq!(window.stream_position().unwrap(), 0);
+
+        let mut unlimited = ItemWindow::new(Cursor::new(b"abc".to_vec()), 1, None).unwrap();
+        assert_eq!(unlimited.seek(SeekFrom::End(0)).unwrap(), 2);
+    }
+}
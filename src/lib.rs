@@ -19,12 +19,12 @@
 //! ```
 //!
 //! * `InodeAwareReader` that allows working with rotated logs and maintating persistent offset inside them. Scheme of persistence is
-//! to be implemented by user.
+//!   to be implemented by user.
 //!
 //! ```rust no_run
 //! # use std::io::{Read, BufRead, self};
 //! # use filetrack::{InodeAwareOffset, InodeAwareReader};
-//! # fn load_state() -> io::Result<InodeAwareOffset> {Ok(InodeAwareOffset{inode: 0, offset: 0})}
+//! # fn load_state() -> io::Result<InodeAwareOffset> {Ok(InodeAwareOffset{inode: 0, offset: 0, fingerprint: None, file_stamp: None})}
 //! # fn save_state(state: InodeAwareOffset) -> io::Result<()> {Ok(())}
 //! let mut reader = InodeAwareReader::from_rotated_logs("/var/log/mail.log")?;
 //! reader.seek_persistent(load_state()?)?;
@@ -35,7 +35,7 @@
 //! ```
 //!
 //! * `TrackedReader` that allows to read logs or any other content from rotated files with offset persisted across restarts inside a file
-//! in case you want a ready-to-use structure.
+//!   in case you want a ready-to-use structure.
 //!
 //! ```rust no_run
 //! # use filetrack::{TrackedReader, TrackedReaderError};
@@ -54,12 +54,33 @@
 //! ```
 //!
 
+mod compression;
 mod inode_aware;
+mod item_window;
+mod log_file;
+#[cfg(feature = "mmap")]
+mod mapped_file;
 mod multireader;
 /// A collection of utility functions for working with paths and filesystem.
 pub mod path_utils;
+mod rotating_writer;
+mod sequential_multireader;
 mod tracked_reader;
 
-pub use inode_aware::{InodeAwareOffset, InodeAwareReader};
-pub use multireader::Multireader;
+pub use compression::{CodecRegistry, CompressedCodec, Decoder};
+pub use inode_aware::{
+    FileStamp, Fingerprint, Follow, InodeAwareOffset, InodeAwareReader, InodeAwareReaderBuilder,
+    IntegrityReport, ItemDescription, ItemIntegrity, LineTooLong, MissingInodePolicy,
+    OffsetValidity, Progress, RefreshOutcome, ReopenOutcome, ResolutionEntry, ResolutionReport,
+    SeekOutcome, SymlinkPolicy, Truncation, TruncationDetected, TruncationPolicy,
+};
+pub use item_window::ItemWindow;
+pub use log_file::LogFile;
+pub use multireader::{
+    BoxedMultireader, Chunk, Chunks, CloneReader, ItemStats, LinesWithOffsets, Multireader,
+    MultireaderSnapshot, MultireaderSummary, ReadLimitedError, ReadSeek, ReaderStats, Record,
+    Records, RestoreError, ScopedTake, SizeCachePolicy, SizesChanged, WindowedMultireader,
+};
+pub use rotating_writer::RotatingWriter;
+pub use sequential_multireader::SequentialMultireader;
 pub use tracked_reader::{State, StateSerdeError, TrackedReader, TrackedReaderError};
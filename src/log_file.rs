@@ -0,0 +1,363 @@
+use std::{
+    fs::{self, File, Metadata},
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "mmap")]
+use crate::mapped_file::MappedFile;
+use crate::{compression::CodecRegistry, path_utils::file_id, CloneReader};
+
+/// File read by `InodeAwareReader`.
+///
+/// Behaves like `BufReader<File>` of a (possibly decompressed) log, but can be released to free its file descriptor. Released
+/// file is reopened by its path on next access, which fails with NotFound io error if the path no longer points to the
+/// same file (inode).
+///
+/// With `mmap` feature, file may be memory-mapped instead of being read through a buffer, see
+/// `InodeAwareReader::from_rotated_logs_mmap`.
+pub struct LogFile {
+    reader: Option<Source>,
+    path: PathBuf,
+    inode: u64,
+    capacity: usize,
+    codecs: CodecRegistry,
+    /// position of released file, restored after reopening
+    position: u64,
+    /// whether file is mapped into memory, also after being reopened
+    #[cfg(feature = "mmap")]
+    mapped: bool,
+}
+
+/// Opened file, read either through a buffer or from a memory map.
+enum Source {
+    Buffered(BufReader<File>),
+    #[cfg(feature = "mmap")]
+    Mapped(MappedFile),
+}
+
+impl LogFile {
+    /// Open log at `path` with buffer of `capacity`, decompressing it if needed.
+    pub(crate) fn open(
+        path: &Path,
+        inode: u64,
+        capacity: usize,
+        codecs: &CodecRegistry,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            reader: Some(Source::Buffered(codecs.open_log(path, capacity)?)),
+            path: path.to_path_buf(),
+            inode,
+            capacity,
+            codecs: codecs.clone(),
+            position: 0,
+            #[cfg(feature = "mmap")]
+            mapped: false,
+        })
+    }
+
+    /// Remember log at `path` without opening it, so that it is opened on first access like a released file.
+    pub(crate) fn open_lazily(
+        path: &Path,
+        inode: u64,
+        capacity: usize,
+        codecs: &CodecRegistry,
+    ) -> Self {
+        Self {
+            reader: None,
+            path: path.to_path_buf(),
+            inode,
+            capacity,
+            codecs: codecs.clone(),
+            position: 0,
+            #[cfg(feature = "mmap")]
+            mapped: false,
+        }
+    }
+
+    /// Wrap already opened file that is not associated with any path, so it cannot be released.
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn from_reader(reader: BufReader<File>, inode: u64) -> Self {
+        Self {
+            capacity: reader.capacity(),
+            reader: Some(Source::Buffered(reader)),
+            path: PathBuf::new(),
+            inode,
+            codecs: CodecRegistry::new(),
+            position: 0,
+            #[cfg(feature = "mmap")]
+            mapped: false,
+        }
+    }
+
+    /// Path the file had when it was opened.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reopen released file from `path` it was moved to, eg. by rotation.
+    pub(crate) fn relocate(&mut self, path: &Path) {
+        self.path = path.to_path_buf();
+    }
+
+    /// Get reference to underlying reader, None if file is released or memory-mapped.
+    pub fn get_ref(&self) -> Option<&BufReader<File>> {
+        match self.reader.as_ref() {
+            Some(Source::Buffered(reader)) => Some(reader),
+            _ => None,
+        }
+    }
+
+    /// Query metadata of the file, from its path if it is released.
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        match self.reader.as_ref() {
+            Some(source) => source.file().metadata(),
+            None => fs::metadata(&self.path),
+        }
+    }
+
+    /// Check whether file is memory-mapped (or will be once reopened if it is released), see `map`.
+    #[cfg(feature = "mmap")]
+    pub fn is_mapped(&self) -> bool {
+        self.mapped
+    }
+
+    /// Read file from read-only memory map instead of a buffer, keeping current position. Released file is mapped once it
+    /// is reopened.
+    ///
+    /// Map does not see data appended to the file after mapping, and file must not be truncated while it is mapped (see
+    /// `InodeAwareReader::from_rotated_logs_mmap`).
+    #[cfg(feature = "mmap")]
+    pub(crate) fn map(&mut self) -> io::Result<()> {
+        self.mapped = true;
+        let Some(Source::Buffered(reader)) = self.reader.as_mut() else {
+            return Ok(());
+        };
+        let position = reader.stream_position()?;
+        let mut mapped = MappedFile::new(reader.get_ref().try_clone()?)?;
+        mapped.seek(SeekFrom::Start(position))?;
+        self.reader = Some(Source::Mapped(mapped));
+        Ok(())
+    }
+
+    /// Check whether file descriptor was released, see `release`.
+    pub fn is_released(&self) -> bool {
+        self.reader.is_none()
+    }
+
+    /// Close underlying file remembering current position. Returns false if file was already released or cannot be
+    /// reopened later because it is not associated with a path.
+    pub fn release(&mut self) -> io::Result<bool> {
+        if self.path.as_os_str().is_empty() {
+            return Ok(false);
+        }
+        let Some(reader) = self.reader.as_mut() else {
+            return Ok(false);
+        };
+        self.position = reader.stream_position()?;
+        self.reader = None;
+        Ok(true)
+    }
+
+    /// Get underlying reader, reopening released file.
+    fn reader(&mut self) -> io::Result<&mut Source> {
+        if self.reader.is_none() {
+            let reopened = self.reopen()?;
+            self.reader = Some(reopened);
+        }
+        Ok(self.reader.as_mut().expect("reader was just opened"))
+    }
+
+    fn reopen(&self) -> io::Result<Source> {
+        let moved_error = || {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "released file with inode {} is no longer at {}",
+                    self.inode,
+                    self.path.display()
+                ),
+            )
+        };
+        match file_id(&self.path) {
+            Ok(inode) if inode == self.inode => {}
+            Ok(_) => return Err(moved_error()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(moved_error()),
+            Err(e) => return Err(e),
+        }
+        let mut reader = Source::Buffered(self.codecs.open_log(&self.path, self.capacity)?);
+        #[cfg(feature = "mmap")]
+        if self.mapped {
+            reader = Source::Mapped(MappedFile::new(reader.file().try_clone()?)?);
+        }
+        reader.seek(SeekFrom::Start(self.position))?;
+        Ok(reader)
+    }
+}
+
+impl Source {
+    fn file(&self) -> &File {
+        match self {
+            Source::Buffered(reader) => reader.get_ref(),
+            #[cfg(feature = "mmap")]
+            Source::Mapped(mapped) => mapped.get_ref(),
+        }
+    }
+}
+
+impl Read for Source {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Source::Buffered(reader) => reader.read(buf),
+            #[cfg(feature = "mmap")]
+            Source::Mapped(mapped) => mapped.read(buf),
+        }
+    }
+}
+
+impl BufRead for Source {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match self {
+            Source::Buffered(reader) => reader.fill_buf(),
+            #[cfg(feature = "mmap")]
+            Source::Mapped(mapped) => mapped.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match self {
+            Source::Buffered(reader) => reader.consume(amt),
+            #[cfg(feature = "mmap")]
+            Source::Mapped(mapped) => mapped.consume(amt),
+        }
+    }
+}
+
+impl Seek for Source {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Source::Buffered(reader) => reader.seek(pos),
+            #[cfg(feature = "mmap")]
+            Source::Mapped(mapped) => mapped.seek(pos),
+        }
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        match self {
+            Source::Buffered(reader) => reader.stream_position(),
+            #[cfg(feature = "mmap")]
+            Source::Mapped(mapped) => mapped.stream_position(),
+        }
+    }
+}
+
+impl CloneReader for Source {
+    fn try_clone_reader(&self) -> io::Result<Self> {
+        match self {
+            Source::Buffered(reader) => reader.try_clone_reader().map(Source::Buffered),
+            #[cfg(feature = "mmap")]
+            Source::Mapped(mapped) => mapped.try_clone_reader().map(Source::Mapped),
+        }
+    }
+}
+
+impl Read for LogFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reader()?.read(buf)
+    }
+}
+
+impl BufRead for LogFile {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.reader()?.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Some(reader) = self.reader.as_mut() {
+            reader.consume(amt)
+        }
+    }
+}
+
+impl Seek for LogFile {
+    /// Seeking released file to a known position does not reopen it.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        if let Some(reader) = self.reader.as_mut() {
+            return reader.seek(pos);
+        }
+        let target = match pos {
+            SeekFrom::Start(offset) => offset,
+            SeekFrom::Current(offset) => {
+                self.position.checked_add_signed(offset).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "invalid seek to a negative offset",
+                    )
+                })?
+            }
+            SeekFrom::End(_) => return self.reader()?.seek(pos),
+        };
+        self.position = target;
+        Ok(target)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        match self.reader.as_mut() {
+            Some(reader) => reader.stream_position(),
+            None => Ok(self.position),
+        }
+    }
+}
+
+/// Released files stay released in the clone.
+impl CloneReader for LogFile {
+    fn try_clone_reader(&self) -> io::Result<Self> {
+        Ok(Self {
+            reader: self
+                .reader
+                .as_ref()
+                .map(CloneReader::try_clone_reader)
+                .transpose()?,
+            path: self.path.clone(),
+            inode: self.inode,
+            capacity: self.capacity,
+            codecs: self.codecs.clone(),
+            position: self.position,
+            #[cfg(feature = "mmap")]
+            mapped: self.mapped,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{Read, Seek, SeekFrom},
+    };
+
+    use super::LogFile;
+    use crate::{path_utils::file_id, CodecRegistry};
+
+    #[test]
+    fn released_file_is_reopened_at_its_position() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::write(&path, b"hello world").unwrap();
+        let mut file =
+            LogFile::open(&path, file_id(&path).unwrap(), 16, &CodecRegistry::new()).unwrap();
+        file.read_exact(&mut [0; 6]).unwrap();
+
+        assert!(file.release().unwrap());
+        assert!(!file.release().unwrap());
+        assert_eq!(file.stream_position().unwrap(), 6);
+        // seeking to known position does not need the file
+        assert_eq!(file.seek(SeekFrom::Current(-2)).unwrap(), 4);
+        assert!(file.is_released());
+
+        let mut content = String::new();
+        file.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "o world");
+        assert!(!file.is_released());
+    }
+}
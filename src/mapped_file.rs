@@ -0,0 +1,138 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, Read, Seek, SeekFrom},
+};
+
+use memmap2::Mmap;
+
+use crate::CloneReader;
+
+/// Read-only memory map of a file that is read and seeked like a `Cursor`.
+///
+/// Length of the map is fixed when it is created, so data appended to the file later is not visible. Reads never go past
+/// that length, but if the file is truncated while mapped, accessing its former content is undefined behaviour (usually
+/// the process is killed with SIGBUS). It is only used for rotated logs, which are not supposed to change.
+pub(crate) struct MappedFile {
+    file: File,
+    map: Mmap,
+    position: u64,
+}
+
+impl MappedFile {
+    pub(crate) fn new(file: File) -> io::Result<Self> {
+        // SAFETY: map is read-only and never handed out beyond borrows of self, the remaining hazard of file being
+        // truncated by another process is documented on the type
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Self {
+            file,
+            map,
+            position: 0,
+        })
+    }
+
+    pub(crate) fn get_ref(&self) -> &File {
+        &self.file
+    }
+
+    /// Part of the map after current position, empty if position is past its end.
+    fn remaining(&self) -> &[u8] {
+        let start = usize::try_from(self.position)
+            .unwrap_or(usize::MAX)
+            .min(self.map.len());
+        &self.map[start..]
+    }
+}
+
+impl Read for MappedFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size_read = self.remaining().read(buf)?;
+        self.position += size_read as u64;
+        Ok(size_read)
+    }
+}
+
+impl BufRead for MappedFile {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.remaining())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let amt = amt.min(self.remaining().len());
+        self.position += amt as u64;
+    }
+}
+
+impl Seek for MappedFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(offset) => {
+                self.position = offset;
+                return Ok(offset);
+            }
+            SeekFrom::End(offset) => (self.map.len() as u64, offset),
+            SeekFrom::Current(offset) => (self.position, offset),
+        };
+        self.position = base.checked_add_signed(offset).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.position)
+    }
+}
+
+/// File is mapped once more, position is kept.
+impl CloneReader for MappedFile {
+    fn try_clone_reader(&self) -> io::Result<Self> {
+        let mut clone = Self::new(self.file.try_clone()?)?;
+        clone.position = self.position;
+        Ok(clone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Read, Seek, SeekFrom, Write};
+
+    use super::MappedFile;
+
+    fn mapped(content: &[u8]) -> MappedFile {
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(content).unwrap();
+        MappedFile::new(file).unwrap()
+    }
+
+    #[test]
+    fn mapped_file_behaves_like_cursor() {
+        let mut file = mapped(b"hello\nworld");
+        let mut line = String::new();
+        file.read_line(&mut line).unwrap();
+        assert_eq!(line, "hello\n");
+        assert_eq!(file.seek(SeekFrom::End(-2)).unwrap(), 9);
+        let mut rest = String::new();
+        file.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "ld");
+        assert!(file.seek(SeekFrom::Current(-12)).is_err());
+    }
+
+    #[test]
+    fn reads_past_the_end_are_empty() {
+        let mut file = mapped(b"abc");
+        file.seek(SeekFrom::Start(10)).unwrap();
+        assert_eq!(file.read(&mut [0; 4]).unwrap(), 0);
+        assert!(file.fill_buf().unwrap().is_empty());
+        file.consume(5);
+        assert_eq!(file.stream_position().unwrap(), 10);
+    }
+
+    #[test]
+    fn empty_file_can_be_mapped() {
+        let mut file = mapped(b"");
+        assert_eq!(file.read(&mut [0; 4]).unwrap(), 0);
+    }
+}
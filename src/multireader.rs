@@ -1,9 +1,29 @@
-use std::io::{self, BufRead, Read, Seek, SeekFrom};
+use crate::item_window::ItemWindow;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Debug},
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor, IoSliceMut, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+use thiserror::Error;
 
 /// Structure that provides seeking and reading in a sequence of underlying readables.
 ///
 /// **Note**: all readers except for the last one **MUST** have constant size so that we can rely on offsets for indexing across them.
-/// This will be true for the case of reading logrotated files.
+/// This will be true for the case of reading logrotated files. If sizes changed anyway, `refresh_sizes` can be used to rebuild
+/// offsets.
+///
+/// Multireader may be constructed without any items. Such reader behaves like an empty buffer: reads return 0, total size is 0
+/// and only seeking to zero offset is allowed.
+///
+/// Sizes of items are discovered lazily: size of an item is only measured when cursor needs to move past it (or when total
+/// size is requested), so items that are never reached are never touched. Likewise, underlying items are only positioned when
+/// they become current.
+///
+/// Empty items are allowed anywhere and are transparently skipped while reading. Current item index never points at an item
+/// that is known to be empty unless it is the last one, so at a boundary followed by empty items it points at the first
+/// non-empty item after them. Item with size that was not discovered yet may still turn out to be empty.
 ///
 /// ## Usage
 ///
@@ -14,8 +34,8 @@ use std::io::{self, BufRead, Read, Seek, SeekFrom};
 /// # use std::io::{Cursor, Read};
 /// # use filetrack::Multireader;
 /// let inner_items = vec![Cursor::new(vec![1, 2, 3]), Cursor::new(vec![4, 5])];
-/// // we get result here because Multireader performs seek
-/// // (fallible operation) under the hood to determine sizes
+/// // we get result here for compatibility with constructors that perform
+/// // fallible operations under the hood
 /// let mut reader = Multireader::new(inner_items)?;
 /// # let mut buf = vec![];
 /// reader.read_to_end(&mut buf)?;
@@ -37,34 +57,425 @@ use std::io::{self, BufRead, Read, Seek, SeekFrom};
 /// # Ok::<(), std::io::Error>(())
 /// ```
 pub struct Multireader<R: Seek> {
-    /// nonempty
+    /// may be empty, in which case reader behaves like an empty buffer
     items: Vec<R>,
-    /// global offsets for all files except for first (which is zero)
+    /// global offsets for all files except for first (which is zero). Only contains offsets discovered so far, all items
+    /// before current one are guaranteed to be discovered.
     offsets: Vec<u64>,
     global_offset: u64,
+    /// cached index of an item that contains global offset. May also point at an item that was drained by one of
+    /// `*_within_item` methods, in which case global offset is equal to its end
+    current_item: usize,
+    /// index of an item whose underlying position is known to match local offset
+    positioned_item: Option<usize>,
+    /// global offset up to which positioned item returned data from its last `fill_buf`, used to clamp `consume`
+    buffered_end: u64,
+    size_cache_policy: SizeCachePolicy,
+    /// size of the last item measured previously, only used with `SizeCachePolicy::Manual`
+    cached_last_size: Option<u64>,
+    item_change_hook: Option<ItemChangeHook>,
+    stats_enabled: bool,
+    stats: ReaderStats,
+    /// too long record whose rest is skipped once it is written, see `read_until_limited`
+    unfinished_skip: Option<UnfinishedSkip>,
+}
+
+type ItemChangeHook = Box<dyn FnMut(usize, usize) + Send>;
+
+/// Defines how `Multireader` obtains size of the last item, which may still grow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SizeCachePolicy {
+    /// Measure last item every time its size is needed (eg. for `SeekFrom::End` or `get_total_size`).
+    #[default]
+    AlwaysRefresh,
+    /// Measure last item once and reuse its size until `refresh_last_size` is called. Note that in this mode size of growing
+    /// item may be under-reported, so seeking relative to the end and computing total size work with stale data. Reading
+    /// is not affected and returns newly written data.
+    Manual,
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl<T: std::io::BufRead + std::io::Seek> Sealed for T {}
+}
+
+/// Object-safe combination of `BufRead` and `Seek` that allows mixing different kinds of readers in one `Multireader`.
+///
+/// This trait is sealed and implemented for every type that implements `BufRead` and `Seek`.
+pub trait ReadSeek: BufRead + Seek + private::Sealed {}
+
+impl<T: BufRead + Seek> ReadSeek for T {}
+
+/// `Multireader` over parts of underlying readers, see `Multireader::split_at`.
+pub type WindowedMultireader<R> = Multireader<ItemWindow<R>>;
+
+/// `Multireader` over boxed readers of possibly different types, see `Multireader::boxed`.
+pub type BoxedMultireader = Multireader<Box<dyn ReadSeek>>;
+
+/// Readers that can produce another handle to the same data with independent position, used by `Multireader::try_clone`.
+pub trait CloneReader: Sized {
+    fn try_clone_reader(&self) -> io::Result<Self>;
+}
+
+impl<T: Clone> CloneReader for Cursor<T> {
+    fn try_clone_reader(&self) -> io::Result<Self> {
+        Ok(self.clone())
+    }
+}
+
+/// Underlying file is opened anew since handle produced by `File::try_clone` shares position with original one. This works
+/// for files that were renamed or deleted after being opened.
+///
+/// Only Linux (through `/proc/self/fd`) and Windows (through `ReOpenFile`) are supported, Unsupported io error is returned
+/// on other platforms.
+impl CloneReader for BufReader<File> {
+    fn try_clone_reader(&self) -> io::Result<Self> {
+        let file = reopen_file(self.get_ref())?;
+        Ok(BufReader::with_capacity(self.capacity(), file))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn reopen_file(file: &File) -> io::Result<File> {
+    use std::os::fd::AsRawFd;
+    File::open(format!("/proc/self/fd/{}", file.as_raw_fd()))
+}
+
+#[cfg(windows)]
+fn reopen_file(file: &File) -> io::Result<File> {
+    use std::os::windows::io::{AsRawHandle, FromRawHandle};
+    use windows_sys::Win32::{
+        Foundation::INVALID_HANDLE_VALUE,
+        Storage::FileSystem::{
+            ReOpenFile, FILE_GENERIC_READ, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE,
+        },
+    };
+
+    // same sharing as `File::open` uses, so that the log may still be written, renamed and deleted
+    let share_mode = FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE;
+    // SAFETY: handle is valid while `file` is alive, returned handle is checked before being owned by the new `File`
+    let handle = unsafe { ReOpenFile(file.as_raw_handle(), FILE_GENERIC_READ, share_mode, 0) };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: handle was just opened and is not owned by anything else
+    Ok(unsafe { File::from_raw_handle(handle) })
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn reopen_file(_file: &File) -> io::Result<File> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reopening file with independent position is not supported on this platform",
+    ))
+}
+
+/// Report produced by `Multireader::refresh_sizes` describing items whose sizes differ from ones previously used for indexing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizesChanged {
+    /// pairs of item index and difference between new and previous size
+    pub items: Vec<(usize, i64)>,
+}
+
+impl SizesChanged {
+    /// Returns true if no sizes changed.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// Counters of data read from each item, collected after `Multireader::enable_stats` is called.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReaderStats {
+    /// per-item counters by item index, items that were never read from may be missing at the end
+    pub items: Vec<ItemStats>,
+    pub total_bytes: u64,
+    /// number of times cursor moved into another item, be it a result of reading or seeking
+    pub item_transitions: u64,
+}
+
+/// Data read from a single item, see `ReaderStats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ItemStats {
+    pub bytes: u64,
+    /// number of calls that returned (or consumed) at least one byte of this item
+    pub reads: u64,
+}
+
+impl ReaderStats {
+    fn record_read(&mut self, item_index: usize, size: u64) {
+        if size == 0 {
+            return;
+        }
+        if self.items.len() <= item_index {
+            self.items.resize(item_index + 1, ItemStats::default());
+        }
+        self.items[item_index].bytes += size;
+        self.items[item_index].reads += 1;
+        self.total_bytes += size;
+    }
+}
+
+/// Snapshot of `Multireader` position and indexing state, suitable for structured logging.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultireaderSummary {
+    /// number of underlying items
+    pub items: usize,
+    /// global end offsets of items whose sizes were discovered so far (last item is never included)
+    pub offsets: Vec<u64>,
+    pub global_offset: u64,
+    pub current_item_index: usize,
+    pub local_offset: u64,
+}
+
+/// Serializable position of `Multireader` that can be restored into a reader built over the same items later, see
+/// `Multireader::snapshot`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultireaderSnapshot {
+    /// number of underlying items
+    pub items: usize,
+    /// global end offsets of items whose sizes were discovered at the moment of snapshot
+    pub offsets: Vec<u64>,
+    pub global_offset: u64,
+}
+
+/// Possible errors of `Multireader::restore`.
+#[derive(Error, Debug)]
+pub enum RestoreError {
+    #[error("while working with underlying items")]
+    IO(#[from] io::Error),
+    #[error("snapshot was taken from reader with {expected} items, but this one has {actual}")]
+    ItemCountMismatch { expected: usize, actual: usize },
+    #[error("item {item_index} had size {expected} in snapshot, but now has size {actual}")]
+    SizeMismatch {
+        item_index: usize,
+        expected: u64,
+        actual: u64,
+    },
 }
 
 impl<R: Seek> Multireader<R> {
-    /// Create a Multireader from a nonempty collection of readers.
+    /// Create a Multireader from a collection of readers.
     ///
-    /// This function returns io::Result because it will use seek to determine sizes which can fail.
-    pub fn new(mut items: Vec<R>) -> io::Result<Self> {
-        assert_ne!(
-            items.len(),
-            0,
-            "you should provide at least one item to be used"
-        );
-        let sizes = get_sizes_fallible(&mut items)?;
+    /// No seeks are performed during construction since sizes are discovered lazily, items are rewound when they are first
+    /// read from. Empty collection produces a reader that always yields EOF.
+    pub fn new(items: Vec<R>) -> io::Result<Self> {
+        Ok(Self {
+            items,
+            offsets: vec![],
+            global_offset: 0,
+            current_item: 0,
+            positioned_item: None,
+            buffered_end: 0,
+            size_cache_policy: SizeCachePolicy::default(),
+            cached_last_size: None,
+            item_change_hook: None,
+            stats_enabled: false,
+            stats: ReaderStats::default(),
+            unfinished_skip: None,
+        })
+    }
+
+    /// Create a Multireader wrapping a single reader.
+    ///
+    /// ```rust
+    /// # use std::io::{Cursor, Read};
+    /// # use filetrack::Multireader;
+    /// let mut reader = Multireader::single(Cursor::new(vec![1, 2, 3]))?;
+    /// # let mut buf = vec![];
+    /// reader.read_to_end(&mut buf)?;
+    /// assert_eq!(buf, vec![1, 2, 3]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn single(item: R) -> io::Result<Self> {
+        Self::new(vec![item])
+    }
+
+    /// Create a Multireader from any iterable of readers, in iteration order.
+    ///
+    /// ```rust
+    /// # use std::io::{Cursor, Read};
+    /// # use filetrack::Multireader;
+    /// let items = (1..=3).map(|i| Cursor::new(vec![i; 2]));
+    /// let mut reader = Multireader::from_iter_fallible(items)?;
+    /// # let mut buf = vec![];
+    /// reader.read_to_end(&mut buf)?;
+    /// assert_eq!(buf, vec![1, 1, 2, 2, 3, 3]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_iter_fallible(items: impl IntoIterator<Item = R>) -> io::Result<Self> {
+        Self::new(items.into_iter().collect())
+    }
+
+    /// Create a Multireader from a collection of readers with already known sizes, skipping size discovery.
+    ///
+    /// `sizes` must contain size of every item (size of the last item is ignored since it may still grow), otherwise
+    /// InvalidInput io error is returned. Supplied sizes are trusted; `validate_sizes` can be used to check them.
+    pub fn with_sizes(items: Vec<R>, mut sizes: Vec<u64>) -> io::Result<Self> {
+        if items.len() != sizes.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "got {} sizes for {} items, they must match",
+                    sizes.len(),
+                    items.len()
+                ),
+            ));
+        }
+        sizes.pop();
         let offsets = produce_total_offsets(sizes);
-        let global_offset = 0;
 
         Ok(Self {
             items,
             offsets,
-            global_offset,
+            global_offset: 0,
+            current_item: 0,
+            positioned_item: None,
+            buffered_end: 0,
+            size_cache_policy: SizeCachePolicy::default(),
+            cached_last_size: None,
+            item_change_hook: None,
+            stats_enabled: false,
+            stats: ReaderStats::default(),
+            unfinished_skip: None,
         })
     }
 
+    /// Check that sizes used for indexing match real sizes of all items that were discovered so far.
+    ///
+    /// Returns InvalidData io error describing first mismatch found. Positions of items are restored after measuring.
+    pub fn validate_sizes(&mut self) -> io::Result<()> {
+        let mut item_start = 0;
+        for (item_index, &item_end) in self.offsets.iter().enumerate() {
+            let item = &mut self.items[item_index];
+            let original_offset = item.stream_position()?;
+            let real_size = item.seek(SeekFrom::End(0))?;
+            item.seek(SeekFrom::Start(original_offset))?;
+            let known_size = item_end - item_start;
+            if real_size != known_size {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "item {item_index} has size {real_size} while {known_size} is used for indexing"
+                    ),
+                ));
+            }
+            item_start = item_end;
+        }
+        Ok(())
+    }
+
+    /// Get snapshot of indexing state and current position.
+    pub fn summary(&self) -> MultireaderSummary {
+        MultireaderSummary {
+            items: self.len(),
+            offsets: self.offsets.clone(),
+            global_offset: self.global_offset,
+            current_item_index: self.get_current_item_index(),
+            local_offset: self.get_local_offset(),
+        }
+    }
+
+    /// Get serializable snapshot of position that can be restored later with `restore`, possibly in another reader built
+    /// over the same items.
+    ///
+    /// ```rust
+    /// # use std::io::{Cursor, Read};
+    /// # use filetrack::Multireader;
+    /// let items = || vec![Cursor::new(vec![1, 2, 3]), Cursor::new(vec![4, 5])];
+    /// let mut reader = Multireader::new(items())?;
+    /// reader.read_exact(&mut [0; 4])?;
+    /// let snapshot = reader.snapshot();
+    ///
+    /// let mut restored = Multireader::new(items())?;
+    /// restored.restore(&snapshot)?;
+    /// # let mut buf = vec![];
+    /// restored.read_to_end(&mut buf)?;
+    /// assert_eq!(buf, vec![5]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn snapshot(&self) -> MultireaderSnapshot {
+        MultireaderSnapshot {
+            items: self.len(),
+            offsets: self.offsets.clone(),
+            global_offset: self.global_offset,
+        }
+    }
+
+    /// Seek to position stored in snapshot after checking that this reader has the same shape as the one snapshot was
+    /// taken from: number of items must match, as well as sizes of all items whose sizes are recorded in snapshot.
+    ///
+    /// Position is left untouched if validation fails.
+    pub fn restore(&mut self, snapshot: &MultireaderSnapshot) -> Result<(), RestoreError> {
+        if snapshot.items != self.len() {
+            return Err(RestoreError::ItemCountMismatch {
+                expected: snapshot.items,
+                actual: self.len(),
+            });
+        }
+        let mut item_start = 0;
+        for (item_index, &item_end) in snapshot.offsets.iter().enumerate() {
+            let Some(expected) = item_end.checked_sub(item_start) else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "offsets in snapshot are not sorted",
+                )
+                .into());
+            };
+            let actual = self.get_item_size(item_index)?;
+            if expected != actual {
+                return Err(RestoreError::SizeMismatch {
+                    item_index,
+                    expected,
+                    actual,
+                });
+            }
+            item_start = item_end;
+        }
+        self.seek(SeekFrom::Start(snapshot.global_offset))?;
+        Ok(())
+    }
+
+    /// Measure all items except for the last one again and rebuild offsets table, preserving position inside current item.
+    ///
+    /// This allows recovering from non-last items changing their size (which violates requirements of this struct, but may
+    /// happen with `copytruncate` rotation or if rotation happened between listing and opening files). Global offset is
+    /// adjusted so that current item index and local offset stay the same (local offset is clamped to size of current item
+    /// if it shrank). Items whose sizes were not discovered before are measured too but are not reported as changed.
+    pub fn refresh_sizes(&mut self) -> io::Result<SizesChanged> {
+        let mut changed = SizesChanged::default();
+        let mut offsets = Vec::with_capacity(self.len().saturating_sub(1));
+        let mut item_start = 0;
+        for item_index in 0..self.len().saturating_sub(1) {
+            let size = self.items[item_index].seek(SeekFrom::End(0))?;
+            if let Some(known_size) = self.get_item_size_cached(item_index) {
+                if known_size != size {
+                    changed
+                        .items
+                        .push((item_index, size as i64 - known_size as i64));
+                }
+            }
+            item_start += size;
+            offsets.push(item_start);
+        }
+        self.positioned_item = self
+            .positioned_item
+            .filter(|&item_index| item_index + 1 == self.len());
+        self.buffered_end = 0;
+
+        let item_index = self.get_current_item_index();
+        let local_offset = self.get_local_offset();
+        self.offsets = offsets;
+        let local_offset = match self.get_item_size_cached(item_index) {
+            Some(size) => local_offset.min(size),
+            None => local_offset,
+        };
+        // index is kept even if position ends up exactly at the end of current item
+        self.global_offset = self.get_bytes_before_current_item() + local_offset;
+        Ok(changed)
+    }
+
     /// Offset amoung all underlying items.
     pub fn get_global_offset(&self) -> u64 {
         self.global_offset
@@ -72,31 +483,147 @@ impl<R: Seek> Multireader<R> {
 
     /// Offset inside current item.
     pub fn get_local_offset(&self) -> u64 {
-        let item_index = self.get_current_item_index();
-        if item_index == 0 {
-            return self.global_offset;
+        self.global_offset - self.get_bytes_before_current_item()
+    }
+
+    /// Convert global offset into index of an item containing it and offset inside that item.
+    ///
+    /// Offset exactly at the end of an item is attributed to the start of the following one. Returns None for empty reader
+    /// and for offsets that lie past the known part of a non-last item whose size was not discovered yet.
+    pub fn global_to_local(&self, global_offset: u64) -> Option<(usize, u64)> {
+        let item_index = self.find_item_index(global_offset);
+        let item_start = self.item_start_offset(item_index)?;
+        self.is_within_known_part(item_index, global_offset)
+            .then_some((item_index, global_offset - item_start))
+    }
+
+    /// Convert offset inside item identified by `item_index` into global offset.
+    ///
+    /// Local offset may be equal to item size (pointing at the end of item). Returns None if item does not exist, sizes of
+    /// preceding items are not known yet, local offset lies past the known part of a non-last item or global offset would
+    /// overflow.
+    pub fn local_to_global(&self, item_index: usize, local_offset: u64) -> Option<u64> {
+        let global_offset = self
+            .item_start_offset(item_index)?
+            .checked_add(local_offset)?;
+        self.is_within_known_part(item_index, global_offset)
+            .then_some(global_offset)
+    }
+
+    /// Check that global offset does not lie past the known part of an item. Item must have known start.
+    ///
+    /// Size of the last item is not limited as it may grow. For item with unknown size we only know that it spans at least
+    /// up to current global offset if it is the current item.
+    fn is_within_known_part(&self, item_index: usize, global_offset: u64) -> bool {
+        if item_index + 1 == self.len() {
+            return true;
+        }
+        match self.offsets.get(item_index) {
+            Some(&item_end) => global_offset <= item_end,
+            None => {
+                let item_start = self.item_start_offset(item_index).unwrap_or_default();
+                global_offset <= item_start.max(self.global_offset)
+            }
         }
-        self.global_offset - self.offsets[item_index - 1]
     }
 
-    //we do not have is_empty because, well, this reader cannot be empty.
-    #[allow(clippy::len_without_is_empty)]
     /// Number of underlying items.
     pub fn len(&self) -> usize {
         self.items.len()
     }
 
+    /// Returns true if reader was constructed without any items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
     /// index of an item that is currently read.
+    ///
+    /// For empty reader this is always 0 even though there is no item to be read.
     pub fn get_current_item_index(&self) -> usize {
-        let mut rightmost_index = 0;
-        for &item in &self.offsets {
-            if self.global_offset >= item {
-                rightmost_index += 1;
-            } else {
-                break;
+        self.current_item
+    }
+
+    /// Find index of an item containing global offset from scratch.
+    fn compute_current_item_index(&self) -> usize {
+        self.find_item_index(self.global_offset)
+    }
+
+    /// Find index of an item containing arbitrary global offset among items with known sizes.
+    fn find_item_index(&self, global_offset: u64) -> usize {
+        self.offsets
+            .partition_point(|&item_end| item_end <= global_offset)
+    }
+
+    /// Update cached item index after global offset moved forward.
+    fn advance_current_item_index(&mut self) {
+        let mut item_index = self.current_item;
+        while self
+            .offsets
+            .get(item_index)
+            .is_some_and(|&item_end| item_end <= self.global_offset)
+        {
+            item_index += 1;
+        }
+        self.set_current_item_index(item_index);
+    }
+
+    /// Set global offset to arbitrary value updating cached item index.
+    fn set_global_offset(&mut self, global_offset: u64) {
+        self.global_offset = global_offset;
+        self.set_current_item_index(self.compute_current_item_index());
+    }
+
+    /// Update cached item index, notifying item change hook if index changed.
+    fn set_current_item_index(&mut self, item_index: usize) {
+        let previous_index = self.current_item;
+        self.current_item = item_index;
+        if previous_index != item_index {
+            if self.stats_enabled {
+                self.stats.item_transitions += 1;
+            }
+            if let Some(hook) = &mut self.item_change_hook {
+                hook(previous_index, item_index);
             }
         }
-        rightmost_index
+    }
+
+    /// Mark item as positioned at local offset, forgetting data buffered by previously positioned item.
+    fn set_positioned_item(&mut self, item_index: usize) {
+        self.positioned_item = Some(item_index);
+        self.buffered_end = 0;
+    }
+
+    /// Set function that is called with previous and new index whenever cursor moves into another item, be it a result of
+    /// reading or seeking. Jumps over multiple items are reported once.
+    ///
+    /// Hook is not called when items are renumbered by removing leading ones (eg. with `retain_from`).
+    pub fn set_item_change_hook(&mut self, hook: impl FnMut(usize, usize) + Send + 'static) {
+        self.item_change_hook = Some(Box::new(hook));
+    }
+
+    /// Remove function set by `set_item_change_hook`.
+    pub fn remove_item_change_hook(&mut self) {
+        self.item_change_hook = None;
+    }
+
+    /// Start counting bytes and read calls served by each item as well as transitions between items, see `stats`.
+    ///
+    /// Counters are kept for the lifetime of the reader. When items are removed from the front (eg. with `retain_from`),
+    /// their counters are removed as well.
+    pub fn enable_stats(&mut self) {
+        self.stats_enabled = true;
+    }
+
+    /// Counters collected since `enable_stats` was called, all zero if it was not.
+    pub fn stats(&self) -> &ReaderStats {
+        &self.stats
+    }
+
+    fn record_read(&mut self, item_index: usize, size: u64) {
+        if self.stats_enabled {
+            self.stats.record_read(item_index, size);
+        }
     }
 
     /// Destroy the struct and return underlying readers.
@@ -104,37 +631,238 @@ impl<R: Seek> Multireader<R> {
         self.items
     }
 
+    /// Wrap every underlying item using `f`, keeping offsets table and current position.
+    ///
+    /// Wrapper must not change observable size of items, since sizes discovered so far are reused. Current item is positioned
+    /// at local offset after wrapping, while other items are positioned once they become current, as usual.
+    pub fn map_items<T: Seek>(self, f: impl FnMut(R) -> T) -> io::Result<Multireader<T>> {
+        let mut mapped = Multireader {
+            items: self.items.into_iter().map(f).collect(),
+            offsets: self.offsets,
+            global_offset: self.global_offset,
+            current_item: self.current_item,
+            positioned_item: None,
+            buffered_end: 0,
+            size_cache_policy: self.size_cache_policy,
+            cached_last_size: self.cached_last_size,
+            item_change_hook: self.item_change_hook,
+            stats_enabled: self.stats_enabled,
+            stats: self.stats,
+            unfinished_skip: self.unfinished_skip,
+        };
+        mapped.current_item()?;
+        Ok(mapped)
+    }
+
+    /// Borrow reader as `Read`/`BufRead` that yields at most `limit` bytes, like `Read::take` does.
+    ///
+    /// Reads go through this reader, so after returned value is dropped it is positioned right after consumed bytes.
+    pub fn take_scoped(&mut self, limit: u64) -> ScopedTake<'_, R> {
+        ScopedTake {
+            reader: self,
+            limit,
+        }
+    }
+
+    /// Iterate over underlying items in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, R> {
+        self.items.iter()
+    }
+
+    /// Iterate over mutable references to underlying items in order.
+    ///
+    /// Like with `get_item_mut`, changing positions of items desynchronizes them from global offset. Current item is
+    /// repositioned on next read unless `resync_current_item` is called to adopt its new position.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, R> {
+        self.positioned_item = None;
+        self.items.iter_mut()
+    }
+
     /// Get total size of underlying items.
     ///
     /// Computes total size of underlying items. This method requires mut ref and returns io::Result
-    /// because we need to seek inside last item to determine its size at the moment of call.
+    /// because we need to seek inside last item to determine its size at the moment of call (as well as
+    /// inside any items whose sizes were not yet discovered).
     pub fn get_total_size(&mut self) -> io::Result<u64> {
-        let pre_last_total = self.offsets.last().cloned().unwrap_or_default();
+        self.discover_all_sizes()?;
+        let pre_last_total = self.get_known_end();
         let last = self.get_last_item_size()?;
         Ok(pre_last_total + last)
     }
 
-    fn get_current_item(&mut self) -> &mut R {
+    /// Number of bytes between current global offset and the end of the last item.
+    ///
+    /// Like `get_total_size`, this measures last item (and any items with sizes not yet discovered) at the moment of call.
+    pub fn remaining_bytes(&mut self) -> io::Result<u64> {
+        let total_size = self.get_total_size()?;
+        Ok(total_size.saturating_sub(self.global_offset))
+    }
+
+    /// Global offset at which last item with known size ends.
+    fn get_known_end(&self) -> u64 {
+        self.offsets.last().cloned().unwrap_or_default()
+    }
+
+    /// Measure size of first item with unknown size. Must not be called if only last item remains undiscovered.
+    fn discover_next_size(&mut self) -> io::Result<()> {
+        let item_index = self.offsets.len();
+        let size = self.items[item_index].seek(SeekFrom::End(0))?;
+        if self.positioned_item == Some(item_index) {
+            self.positioned_item = None;
+        }
+        self.offsets.push(self.get_known_end() + size);
+        self.advance_current_item_index();
+        Ok(())
+    }
+
+    /// Discover sizes of items until we know which item contains `global_offset`.
+    fn discover_sizes_until(&mut self, global_offset: u64) -> io::Result<()> {
+        while self.offsets.len() + 1 < self.len() && self.get_known_end() < global_offset {
+            self.discover_next_size()?;
+        }
+        Ok(())
+    }
+
+    fn discover_all_sizes(&mut self) -> io::Result<()> {
+        while self.offsets.len() + 1 < self.len() {
+            self.discover_next_size()?;
+        }
+        Ok(())
+    }
+
+    /// Get current item making sure that its position matches local offset. Returns None for empty reader.
+    ///
+    /// Changing position of returned item desynchronizes it from global offset, use `resync_current_item` afterwards.
+    pub fn current_item(&mut self) -> io::Result<Option<&mut R>> {
         let index = self.get_current_item_index();
-        &mut self.items[index]
+        if index >= self.len() {
+            return Ok(None);
+        }
+        if self.positioned_item != Some(index) {
+            let local_offset = self.get_local_offset();
+            self.items[index].seek(SeekFrom::Start(local_offset))?;
+            self.set_positioned_item(index);
+        }
+        Ok(Some(&mut self.items[index]))
+    }
+
+    /// Get reference to underlying item by its index.
+    pub fn get_item(&self, item_index: usize) -> Option<&R> {
+        self.items.get(item_index)
+    }
+
+    /// Get mutable reference to underlying item by its index.
+    ///
+    /// Changing position of an item out-of-band desynchronizes it from global offset. If current item was moved, either call
+    /// `resync_current_item` to adopt its new position, or position will be restored on next read.
+    pub fn get_item_mut(&mut self, item_index: usize) -> Option<&mut R> {
+        if self.positioned_item == Some(item_index) {
+            self.positioned_item = None;
+        }
+        self.items.get_mut(item_index)
+    }
+
+    /// Update global offset from stream position of current item after it was moved out-of-band.
+    ///
+    /// Position of current item is trusted, so it must have been positioned before being moved (eg. obtained via
+    /// `current_item`). Does nothing for empty reader.
+    pub fn resync_current_item(&mut self) -> io::Result<()> {
+        let item_index = self.get_current_item_index();
+        let Some(item) = self.items.get_mut(item_index) else {
+            return Ok(());
+        };
+        let local_offset = item.stream_position()?;
+        self.set_global_offset(self.get_bytes_before_current_item() + local_offset);
+        self.set_positioned_item(item_index);
+        Ok(())
+    }
+
+    /// Move cursor to the start of the next item after current one was drained.
+    ///
+    /// If size of current item was unknown, it is derived from current position.
+    fn move_past_drained_item(&mut self) {
+        let item_index = self.get_current_item_index();
+        if item_index == self.offsets.len() {
+            self.offsets.push(self.global_offset);
+        } else {
+            self.global_offset = self.offsets[item_index];
+        }
+        self.advance_current_item_index();
     }
 
     /// Seek current underlying reader properly updating any internal state.
     ///
-    /// Returns current local offset after seek.
+    /// Returns current local offset after seek. Fails with InvalidInput io error for empty reader.
     pub fn seek_current_item(&mut self, pos: SeekFrom) -> io::Result<u64> {
-        let local_offset = self.get_current_item().seek(pos)?;
-        self.global_offset = self.get_bytes_before_current_item() + local_offset;
+        let Some(current_item) = self.current_item()? else {
+            return Err(empty_reader_error());
+        };
+        let local_offset = current_item.seek(pos)?;
+        // buffer of an item is discarded by seek
+        self.buffered_end = 0;
+        self.set_global_offset(self.get_bytes_before_current_item() + local_offset);
         Ok(local_offset)
     }
 
+    /// Number of bytes between current position and the end of current item.
+    ///
+    /// Current item is measured if its size is not known yet (which is always the case for the last item).
+    pub fn remaining_in_current_item(&mut self) -> io::Result<u64> {
+        let item_index = self.get_current_item_index();
+        let item_end = match self.item_end_offset(item_index) {
+            Some(item_end) => item_end,
+            None => {
+                let item_start = self.get_bytes_before_current_item();
+                let Some(item) = self.items.get_mut(item_index) else {
+                    return Ok(0);
+                };
+                let size = item.seek(SeekFrom::End(0))?;
+                if self.positioned_item == Some(item_index) {
+                    self.positioned_item = None;
+                }
+                if item_index + 1 < self.len() {
+                    // cursor is kept inside current item even if it is already exhausted
+                    self.offsets.push(item_start + size);
+                }
+                item_start + size
+            }
+        };
+        Ok(item_end.saturating_sub(self.global_offset))
+    }
+
+    /// Seek to the start of current item, returning new global offset.
+    pub fn rewind_current_item(&mut self) -> io::Result<u64> {
+        self.seek_to_item_start(self.get_current_item_index())
+    }
+
+    /// Seek to the start of the item following current one, returning new global offset.
+    ///
+    /// Returns None without moving the cursor if current item is the last one.
+    pub fn skip_to_next_item(&mut self) -> io::Result<Option<u64>> {
+        let next_item_index = self.get_current_item_index() + 1;
+        if next_item_index >= self.len() {
+            return Ok(None);
+        }
+        self.seek_to_item_start(next_item_index).map(Some)
+    }
+
     /// Perform seek to 0 offset in item identified by `item_index`.
+    ///
+    /// If item is empty, cursor is placed at the same global offset, but current item index points at the first non-empty
+    /// item after it (or at the last item). Fails with InvalidInput io error if there is no such item.
     pub fn seek_to_item_start(&mut self, item_index: usize) -> io::Result<u64> {
-        if item_index == 0 {
-            self.seek(SeekFrom::Start(0))
-        } else {
-            self.seek(SeekFrom::Start(self.offsets[item_index - 1]))
+        if item_index > 0 && item_index >= self.len() {
+            return Err(item_index_error(item_index, self.len()));
+        }
+        while self.offsets.len() < item_index {
+            self.discover_next_size()?;
+        }
+        let item_start = self.item_start_offset(item_index).unwrap_or_default();
+        // measure items starting at target offset so that empty ones are skipped by indexing
+        while self.offsets.len() + 1 < self.len() && self.get_known_end() == item_start {
+            self.discover_next_size()?;
         }
+        self.seek(SeekFrom::Start(item_start))
     }
 
     /// Seek globally by providing local `pos` inside item at index `item_index`.
@@ -145,240 +873,3831 @@ impl<R: Seek> Multireader<R> {
         self.seek_current_item(pos)
     }
 
-    /// Returns item size of item. If it is last, returns None instead.
+    /// Returns item size of item. If it is last or its size was not discovered yet, returns None instead.
     ///
     /// To determine size of last item, use get_last_item_size.
     pub fn get_current_item_size(&self) -> Option<u64> {
         let current_index = self.get_current_item_index();
-        if current_index == self.len() - 1 {
-            return None;
-        }
-        //we know that current item is not last
-        let next_item_start = self.offsets[current_index + 1];
-        Some(next_item_start - self.get_bytes_before_current_item())
+        let item_end = self.item_end_offset(current_index)?;
+        Some(item_end - self.get_bytes_before_current_item())
     }
 
     /// Computes global offset from which current item starts.
     pub fn get_bytes_before_current_item(&self) -> u64 {
-        if self.get_current_item_index() == 0 {
-            return 0;
-        }
-        self.offsets[self.get_current_item_index() - 1]
+        // start of current item is always known
+        self.item_start_offset(self.get_current_item_index())
+            .unwrap_or_default()
     }
 
-    /// Computes last item size.
+    /// Sizes of all items. Size is None for the last item (which may still grow) and for items whose size was not
+    /// discovered yet.
+    pub fn item_sizes(&self) -> Vec<Option<u64>> {
+        (0..self.len())
+            .map(|item_index| self.get_item_size_cached(item_index))
+            .collect()
+    }
+
+    /// Size of an item, measuring it if needed. For the last item this is the same as `get_last_item_size`.
     ///
-    /// Last file in this reader may still be written into, so this number may soon become invalid.
-    pub fn get_last_item_size(&mut self) -> io::Result<u64> {
-        let last_item = self.items.last_mut().unwrap();
-        let original_offset = last_item.stream_position()?;
-        let size = last_item.seek(io::SeekFrom::End(0))?;
-        last_item.seek(io::SeekFrom::Start(original_offset))?;
-        Ok(size)
+    /// Fails with InvalidInput io error if there is no such item.
+    pub fn get_item_size(&mut self, item_index: usize) -> io::Result<u64> {
+        if item_index >= self.len() {
+            return Err(item_index_error(item_index, self.len()));
+        }
+        if item_index + 1 == self.len() {
+            return self.get_last_item_size();
+        }
+        while self.offsets.len() <= item_index {
+            self.discover_next_size()?;
+        }
+        Ok(self.get_item_size_cached(item_index).unwrap_or_default())
     }
-}
 
-fn produce_total_offsets(mut items: Vec<u64>) -> Vec<u64> {
-    let mut total = 0;
-    for item in &mut items {
-        total += *item;
-        *item = total;
+    /// Size of an item if it is already known. Returns None for the last item, items that were not discovered yet and
+    /// items that do not exist.
+    pub fn get_item_size_cached(&self, item_index: usize) -> Option<u64> {
+        let item_end = self.item_end_offset(item_index)?;
+        Some(item_end - self.item_start_offset(item_index)?)
     }
-    items
-}
 
-fn get_sizes_fallible(items: &mut [impl Seek]) -> io::Result<Vec<u64>> {
-    let mut offsets = items
-        .iter_mut()
-        .map(|seekable| -> io::Result<u64> {
-            let item_size = seekable.seek(io::SeekFrom::End(0))?;
-            seekable.seek(io::SeekFrom::Start(0))?;
-            Ok(item_size)
-        })
-        .collect::<io::Result<Vec<u64>>>()?;
-    // last item is ignored
-    offsets.pop();
+    /// Global offset at which item starts. Returns None if item does not exist or sizes of preceding items were not
+    /// discovered yet.
+    pub fn item_start_offset(&self, item_index: usize) -> Option<u64> {
+        if item_index >= self.len() {
+            return None;
+        }
+        match item_index {
+            0 => Some(0),
+            _ => self.offsets.get(item_index - 1).cloned(),
+        }
+    }
 
-    Ok(offsets)
-}
+    /// Global offset at which item ends (exclusive). Returns None for the last item as well as for items that do not
+    /// exist or were not yet discovered.
+    pub fn item_end_offset(&self, item_index: usize) -> Option<u64> {
+        if item_index + 1 >= self.len() {
+            return None;
+        }
+        self.offsets.get(item_index).cloned()
+    }
 
-impl<R: Read + Seek> Read for Multireader<R> {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let size_read = self.get_current_item().read(buf)?;
-        self.global_offset += size_read as u64;
-        Ok(size_read)
+    /// Append new item to the end of the reader.
+    ///
+    /// Previously last item is considered complete after this call: its current size is measured and used for indexing from
+    /// now on. Pushed item becomes the new last (possibly growing) item and will be rewound once it is reached. Global offset
+    /// is not affected, although if reader is positioned exactly at the end of previously last item, it is now considered to
+    /// be at the start of pushed one.
+    pub fn push(&mut self, item: R) -> io::Result<()> {
+        if !self.is_empty() {
+            self.discover_all_sizes()?;
+            let pre_last_total = self.get_known_end();
+            let last_size = self.measure_last_item_size()?;
+            self.offsets.push(pre_last_total + last_size);
+            self.advance_current_item_index();
+        }
+        self.items.push(item);
+        self.cached_last_size = None;
+        Ok(())
     }
-}
 
-impl<R: BufRead + Seek> BufRead for Multireader<R> {
-    fn fill_buf(&mut self) -> io::Result<&[u8]> {
-        self.get_current_item().fill_buf()
+    /// Join two readers into one that reads items of `first` followed by items of `second`.
+    ///
+    /// Like with `push`, last item of `first` is considered complete: its size is measured and fixed at the moment of call.
+    /// Position of `first` is preserved, while position of `second` is discarded.
+    pub fn concat(mut first: Self, second: Self) -> io::Result<Self> {
+        first.discover_all_sizes()?;
+        let second_start = first.get_known_end() + first.measure_last_item_size()?;
+        if !first.is_empty() && !second.is_empty() {
+            first.offsets.push(second_start);
+            first.cached_last_size = None;
+        }
+        first.offsets.extend(
+            second
+                .offsets
+                .iter()
+                .map(|item_end| item_end + second_start),
+        );
+        first.items.extend(second.items);
+        first.advance_current_item_index();
+        Ok(first)
     }
 
-    fn consume(&mut self, amt: usize) {
-        self.get_current_item().consume(amt);
-        self.global_offset += amt as u64;
+    /// Remove first item if it was entirely consumed, i.e. current position lies in one of the following items.
+    ///
+    /// Offsets are rebased so that next item now starts at 0. Local offset and subsequent reads are not affected, while current
+    /// item index and global offset decrease by one item and its size respectively. Returns None if first item is still
+    /// being read.
+    pub fn pop_front(&mut self) -> Option<R> {
+        if self.get_current_item_index() == 0 {
+            return None;
+        }
+        self.retain_from(1).pop()
     }
-}
 
-impl<R: Seek> Seek for Multireader<R> {
-    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
-        match pos {
-            io::SeekFrom::Start(offset) => {
-                self.global_offset = offset;
-                let item_index = self.get_current_item_index();
-                for item_idx in 0..item_index {
-                    self.items[item_idx].seek(io::SeekFrom::End(0))?;
-                }
-                let local_offset = self.get_local_offset();
-                self.get_current_item()
-                    .seek(io::SeekFrom::Start(local_offset))?;
-                for item_idx in item_index + 1..self.items.len() {
-                    self.items[item_idx].seek(io::SeekFrom::Start(0))?;
+    /// Remove all leading items that were entirely consumed. See `pop_front` for details.
+    ///
+    /// Returns removed items in their original order.
+    pub fn drop_consumed(&mut self) -> Vec<R> {
+        self.retain_from(self.get_current_item_index())
+    }
+
+    /// Remove all items before `first_kept_index`, returning them in their original order.
+    ///
+    /// Offsets are rebased so that item at `first_kept_index` now starts at 0. If current position was inside one of removed
+    /// items, it is moved to the new start. Index larger than number of items removes all of them.
+    pub fn retain_from(&mut self, first_kept_index: usize) -> Vec<R> {
+        let first_kept_index = first_kept_index.min(self.len());
+        if first_kept_index == 0 {
+            return vec![];
+        }
+        match self.offsets.get(first_kept_index - 1).cloned() {
+            Some(new_start) => {
+                self.offsets.drain(..first_kept_index);
+                for offset in &mut self.offsets {
+                    *offset -= new_start;
+                }
+                if self.current_item >= first_kept_index {
+                    self.global_offset -= new_start;
+                    self.current_item -= first_kept_index;
+                } else {
+                    self.global_offset = 0;
+                    self.current_item = self.compute_current_item_index();
+                }
+            }
+            None => {
+                // sizes of kept items are not known since sizes are discovered in order
+                self.offsets.clear();
+                self.global_offset = 0;
+                self.current_item = 0;
+            }
+        }
+        self.positioned_item = self
+            .positioned_item
+            .and_then(|item_index| item_index.checked_sub(first_kept_index));
+        let removed_stats = first_kept_index.min(self.stats.items.len());
+        self.stats.items.drain(..removed_stats);
+        self.buffered_end = 0;
+        if first_kept_index == self.len() {
+            self.cached_last_size = None;
+        }
+        self.items.drain(..first_kept_index).collect()
+    }
+
+    /// Computes last item size.
+    ///
+    /// Last file in this reader may still be written into, so this number may soon become invalid. Empty reader has last item
+    /// size of 0. With `SizeCachePolicy::Manual`, previously measured size is returned until `refresh_last_size` is called.
+    pub fn get_last_item_size(&mut self) -> io::Result<u64> {
+        match (self.size_cache_policy, self.cached_last_size) {
+            (SizeCachePolicy::Manual, Some(size)) => Ok(size),
+            _ => self.refresh_last_size(),
+        }
+    }
+
+    /// Measure last item, updating cached size if `SizeCachePolicy::Manual` is used.
+    pub fn refresh_last_size(&mut self) -> io::Result<u64> {
+        let size = self.measure_last_item_size()?;
+        if self.size_cache_policy == SizeCachePolicy::Manual {
+            self.cached_last_size = Some(size);
+        }
+        Ok(size)
+    }
+
+    /// Set policy used to obtain size of the last item. Cached size is discarded.
+    pub fn set_size_cache_policy(&mut self, policy: SizeCachePolicy) {
+        self.size_cache_policy = policy;
+        self.cached_last_size = None;
+    }
+
+    pub fn get_size_cache_policy(&self) -> SizeCachePolicy {
+        self.size_cache_policy
+    }
+
+    fn measure_last_item_size(&mut self) -> io::Result<u64> {
+        let Some(last_item) = self.items.last_mut() else {
+            return Ok(0);
+        };
+        let original_offset = last_item.stream_position()?;
+        let size = last_item.seek(io::SeekFrom::End(0))?;
+        last_item.seek(io::SeekFrom::Start(original_offset))?;
+        Ok(size)
+    }
+}
+
+fn empty_reader_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "cannot seek past the end of empty reader",
+    )
+}
+
+fn item_index_error(item_index: usize, len: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("item index {item_index} is out of range for reader with {len} items"),
+    )
+}
+
+/// Size of blocks read by `Multireader::last_lines` while scanning backwards.
+const LAST_LINES_BLOCK_SIZE: usize = 8 * 1024;
+
+/// Find first occurrence of non-empty `needle` in `haystack`.
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    memchr::memchr_iter(needle[0], haystack)
+        .find(|&position| haystack[position..].starts_with(needle))
+}
+
+pub(crate) fn produce_total_offsets(mut items: Vec<u64>) -> Vec<u64> {
+    let mut total = 0;
+    for item in &mut items {
+        total += *item;
+        *item = total;
+    }
+    items
+}
+
+impl<R: Read + Seek> Multireader<R> {
+    /// Read from current item only, returning 0 once it is exhausted even if following items have data.
+    ///
+    /// Cursor stays in exhausted item until regular read or seek moves it further (eg. via `skip_to_next_item`). Note that
+    /// regular reads that stop exactly at the end of an item with known size already move cursor into the following item.
+    pub fn read_within_item(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(item) = self.current_item()? else {
+            return Ok(0);
+        };
+        let size_read = item.read(buf)?;
+        self.global_offset += size_read as u64;
+        self.record_read(self.current_item, size_read as u64);
+        Ok(size_read)
+    }
+
+    /// Read bytes starting at arbitrary global offset without moving the cursor, continuing into following items if needed.
+    ///
+    /// Global offset is left untouched. If current item is used for reading, it is repositioned on next sequential read.
+    /// Returns 0 if `global_offset` lies past the end of the last item.
+    pub fn read_at(&mut self, global_offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let mut total_read = 0;
+        while total_read < buf.len() {
+            let offset = global_offset + total_read as u64;
+            let size_read = match self.read_item_at(offset, &mut buf[total_read..]) {
+                Ok(size) => size,
+                Err(_) if total_read > 0 => break,
+                Err(e) => return Err(e),
+            };
+            if size_read == 0 {
+                break;
+            }
+            total_read += size_read;
+        }
+        Ok(total_read)
+    }
+
+    /// Copy everything from current position to the end of the last item into `writer`, returning number of bytes copied.
+    ///
+    /// Each item is copied with `io::copy`, so specializations for file-backed readers and writers may be used. Cursor is
+    /// moved to the end of the last item.
+    pub fn copy_remaining_to<W: Write + ?Sized>(&mut self, writer: &mut W) -> io::Result<u64> {
+        let mut total_copied = 0;
+        while let Some(item) = self.current_item()? {
+            let size_copied = io::copy(item, writer)?;
+            self.global_offset += size_copied;
+            self.record_read(self.current_item, size_copied);
+            total_copied += size_copied;
+            if self.get_current_item_index() + 1 >= self.len() {
+                break;
+            }
+            self.move_past_drained_item();
+        }
+        Ok(total_copied)
+    }
+
+    /// Read bytes starting from current position without consuming them, continuing into following items if needed.
+    ///
+    /// Global offset is not changed and subsequent reads return the same bytes. Unlike `fill_buf`, amount of peeked bytes is
+    /// not limited by buffers of underlying items.
+    pub fn peek(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_at(self.global_offset, buf)
+    }
+
+    /// Like `read_at` but fills the whole buffer, returning UnexpectedEof io error if there is not enough data.
+    pub fn read_exact_at(&mut self, global_offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        let size_read = self.read_at(global_offset, buf)?;
+        if size_read < buf.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get up to `n` last lines of the whole reader (across all items), similar to `tail -n`.
+    ///
+    /// Data is scanned backwards from the end in blocks, so only the tail of the reader is read. Line terminators (`\n` or
+    /// `\r\n`) are stripped, last line is returned even if it is not terminated. Global offset is left untouched. Returns
+    /// InvalidData io error if lines are not valid UTF-8.
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use filetrack::Multireader;
+    /// let items = vec![Cursor::new(b"first\nsecond\n".to_vec()), Cursor::new(b"third\n".to_vec())];
+    /// let mut reader = Multireader::new(items)?;
+    /// assert_eq!(reader.last_lines(2)?, vec!["second", "third"]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn last_lines(&mut self, n: usize) -> io::Result<Vec<String>> {
+        if n == 0 {
+            return Ok(vec![]);
+        }
+        let total_size = self.get_total_size()?;
+        let lines_start = self.last_lines_start(n, total_size)?;
+
+        let mut content = vec![0; (total_size - lines_start) as usize];
+        self.read_exact_at(lines_start, &mut content)?;
+        let content = String::from_utf8(content).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )
+        })?;
+        Ok(content.lines().map(String::from).collect())
+    }
+
+    /// Global offset at which the last `n` lines of data up to `total_size` start, 0 if there are fewer lines. Newline at the
+    /// very end terminates the last line rather than starting a new one. See `last_lines`.
+    pub(crate) fn last_lines_start(&mut self, n: usize, total_size: u64) -> io::Result<u64> {
+        if n == 0 {
+            return Ok(total_size);
+        }
+        let mut block = vec![0; LAST_LINES_BLOCK_SIZE];
+        let mut newlines_found = 0;
+        let mut block_end = total_size;
+        while block_end > 0 {
+            let block_start = block_end.saturating_sub(block.len() as u64);
+            let block = &mut block[..(block_end - block_start) as usize];
+            self.read_exact_at(block_start, block)?;
+            for position in memchr::memrchr_iter(b'\n', block) {
+                let newline_offset = block_start + position as u64;
+                // newline at the very end terminates last line rather than starting a new one
+                if newline_offset + 1 == total_size {
+                    continue;
+                }
+                newlines_found += 1;
+                if newlines_found == n {
+                    return Ok(newline_offset + 1);
+                }
+            }
+            block_end = block_start;
+        }
+        Ok(0)
+    }
+
+    /// Perform single read from an item containing `global_offset`.
+    fn read_item_at(&mut self, global_offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        // make sure that item containing offset is known rather than only its start
+        self.discover_sizes_until(global_offset + 1)?;
+        let item_index = self.find_item_index(global_offset);
+        let Some(item_start) = self.item_start_offset(item_index) else {
+            return Ok(0);
+        };
+        if self.positioned_item == Some(item_index) {
+            self.positioned_item = None;
+        }
+        let item = &mut self.items[item_index];
+        item.seek(SeekFrom::Start(global_offset - item_start))?;
+        item.read(buf)
+    }
+
+    fn read_current_item(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // cursor may have been left at the end of an item by `*_within_item` methods
+        self.advance_current_item_index();
+        let Some(item) = self.current_item()? else {
+            return Ok(0);
+        };
+        let size_read = item.read(buf)?;
+        self.global_offset += size_read as u64;
+        self.record_read(self.current_item, size_read as u64);
+        self.advance_current_item_index();
+        Ok(size_read)
+    }
+
+    fn read_vectored_current_item(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        // cursor may have been left at the end of an item by `*_within_item` methods
+        self.advance_current_item_index();
+        let Some(item) = self.current_item()? else {
+            return Ok(0);
+        };
+        let size_read = item.read_vectored(bufs)?;
+        self.global_offset += size_read as u64;
+        self.record_read(self.current_item, size_read as u64);
+        self.advance_current_item_index();
+        Ok(size_read)
+    }
+}
+
+impl<R: Read + Seek> Read for Multireader<R> {
+    /// Read into `buf`, continuing into following items until buffer is full or all items are exhausted.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut total_read = 0;
+        while total_read < buf.len() {
+            let size_read = match self.read_current_item(&mut buf[total_read..]) {
+                Ok(size) => size,
+                // do not lose bytes that were already placed into buffer
+                Err(_) if total_read > 0 => break,
+                Err(e) => return Err(e),
+            };
+            total_read += size_read;
+
+            if size_read == 0 {
+                if self.get_current_item_index() + 1 >= self.len() {
+                    break;
+                }
+                self.move_past_drained_item();
+            }
+        }
+        Ok(total_read)
+    }
+
+    /// Vectored read that is forwarded to current item, continuing into following items until all buffers are full
+    /// or all items are exhausted.
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let mut bufs = bufs;
+        // drop empty buffers so that exhausted input can be told apart by zero-sized read
+        IoSliceMut::advance_slices(&mut bufs, 0);
+        let mut total_read = 0;
+        while !bufs.is_empty() {
+            let size_read = match self.read_vectored_current_item(bufs) {
+                Ok(size) => size,
+                Err(_) if total_read > 0 => break,
+                Err(e) => return Err(e),
+            };
+            total_read += size_read;
+
+            if size_read == 0 {
+                if self.get_current_item_index() + 1 >= self.len() {
+                    break;
+                }
+                self.move_past_drained_item();
+                continue;
+            }
+            IoSliceMut::advance_slices(&mut bufs, size_read);
+        }
+        Ok(total_read)
+    }
+
+    /// Read all bytes until the end of the last item, reserving space for them in advance.
+    ///
+    /// Space is reserved based on total size at the moment of call. Data that is written into last item while reading
+    /// is still returned.
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let remaining = self.get_total_size()?.saturating_sub(self.global_offset);
+        buf.reserve(remaining as usize);
+        let original_len = buf.len();
+        let mut item_index = self.get_current_item_index();
+        while let Some(item) = self.current_item()? {
+            let len_before = buf.len();
+            let result = item.read_to_end(buf);
+            // some bytes may have been read even if error occurred
+            self.global_offset += (buf.len() - len_before) as u64;
+            self.record_read(self.current_item, (buf.len() - len_before) as u64);
+            self.advance_current_item_index();
+            result?;
+
+            // if item size was known, we have already moved on to the next item
+            if self.get_current_item_index() == item_index {
+                if item_index + 1 >= self.len() {
+                    break;
+                }
+                self.move_past_drained_item();
+            }
+            item_index = self.get_current_item_index();
+        }
+        Ok(buf.len() - original_len)
+    }
+
+    /// Read all bytes until the end of the last item and append them to `buf`. See `read_to_end` for details.
+    ///
+    /// If read data is not valid UTF-8, InvalidData error is returned and `buf` is left unchanged.
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        let mut bytes = std::mem::take(buf).into_bytes();
+        let original_len = bytes.len();
+        let result = self.read_to_end(&mut bytes);
+        match String::from_utf8(bytes) {
+            Ok(string) => {
+                *buf = string;
+                result
+            }
+            Err(e) => {
+                let mut bytes = e.into_bytes();
+                bytes.truncate(original_len);
+                // original content came from a String, so it is valid
+                *buf = String::from_utf8(bytes).unwrap_or_default();
+                result?;
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "stream did not contain valid UTF-8",
+                ))
+            }
+        }
+    }
+}
+
+impl<R: BufRead + Seek> Multireader<R> {
+    /// Check if there is anything left to read without consuming it.
+    ///
+    /// Unlike checking result of `fill_buf` this does not stop at an exhausted item. Sizes of following items are discovered if
+    /// needed and last item is measured at the moment of call. Global offset is not changed.
+    pub fn has_data_left(&mut self) -> io::Result<bool> {
+        let Some(item) = self.current_item()? else {
+            return Ok(false);
+        };
+        if !item.fill_buf()?.is_empty() {
+            return Ok(true);
+        }
+        let item_index = self.get_current_item_index();
+        if item_index + 1 >= self.len() {
+            return Ok(false);
+        }
+        self.discover_all_sizes()?;
+        let following_items_start = self.offsets[item_index];
+        Ok(self.get_known_end() > following_items_start || self.get_last_item_size()? > 0)
+    }
+
+    /// Scan forward from current position for `needle`, which may span boundaries between items.
+    ///
+    /// If found, cursor is left at the start of the match and its global offset is returned. Otherwise cursor is left at
+    /// EOF and None is returned. Empty needle matches at current position.
+    ///
+    /// ```rust
+    /// # use std::io::{Cursor, Read};
+    /// # use filetrack::Multireader;
+    /// let mut reader = Multireader::new(vec![Cursor::new(b"ab{\"".to_vec()), Cursor::new(b"id\"}".to_vec())])?;
+    /// assert_eq!(reader.find(b"\"id")?, Some(3));
+    /// # let mut buf = String::new();
+    /// reader.read_to_string(&mut buf)?;
+    /// assert_eq!(buf, "\"id\"}");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn find(&mut self, needle: &[u8]) -> io::Result<Option<u64>> {
+        if needle.is_empty() {
+            return Ok(Some(self.global_offset));
+        }
+        // last bytes preceding current buffer that may start a match spanning into it
+        let mut tail = Vec::with_capacity(2 * needle.len());
+        loop {
+            let buf = self.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(None);
+            }
+            let buf_len = buf.len();
+            let tail_len = tail.len();
+            tail.extend_from_slice(&buf[..buf_len.min(needle.len() - 1)]);
+            // match position relative to the start of current buffer
+            let match_start = match find_bytes(&tail, needle) {
+                Some(position) if position < tail_len => Some(position as i64 - tail_len as i64),
+                _ => find_bytes(buf, needle).map(|position| position as i64),
+            };
+            if buf_len >= needle.len() - 1 {
+                tail.clear();
+                tail.extend_from_slice(&buf[buf_len + 1 - needle.len()..]);
+            } else {
+                tail.drain(..tail.len().saturating_sub(needle.len() - 1));
+            }
+
+            let buf_start = self.global_offset;
+            match match_start {
+                Some(position) if position >= 0 => {
+                    self.consume(position as usize);
+                    return Ok(Some(self.global_offset));
+                }
+                Some(position) => {
+                    let match_offset = buf_start - position.unsigned_abs();
+                    self.seek(SeekFrom::Start(match_offset))?;
+                    return Ok(Some(match_offset));
+                }
+                None => self.consume(buf_len),
+            }
+        }
+    }
+
+    /// Like `BufRead::read_line`, but stops at the end of current item. See `read_within_item` for details.
+    pub fn read_line_within_item(&mut self, buf: &mut String) -> io::Result<usize> {
+        let mut line = vec![];
+        let size_read = self.read_until_within_item(b'\n', &mut line)?;
+        match String::from_utf8(line) {
+            Ok(line) => {
+                buf.push_str(&line);
+                Ok(size_read)
+            }
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )),
+        }
+    }
+
+    fn read_until_within_item(&mut self, delim: u8, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let mut total_read = 0;
+        while let Some(item) = self.current_item()? {
+            let available = item.fill_buf()?;
+            let (found, used) = match available.iter().position(|&byte| byte == delim) {
+                Some(position) => (true, position + 1),
+                None => (false, available.len()),
+            };
+            buf.extend_from_slice(&available[..used]);
+            item.consume(used);
+            self.global_offset += used as u64;
+            self.record_read(self.current_item, used as u64);
+            total_read += used;
+            if found || used == 0 {
+                break;
+            }
+        }
+        Ok(total_read)
+    }
+
+    /// Like `BufRead::read_until`, but skips records longer than `max` bytes (including delimiter).
+    ///
+    /// Longer record is not kept in memory: it is skipped up to and including its delimiter, nothing is appended to `buf`
+    /// and TooLong error is returned, so that reading may continue from the next record. If data ends before delimiter
+    /// (eg. record is still being written), the rest of the record is skipped by the next call once it appears, provided
+    /// cursor was not moved meanwhile. Last record that is not longer than `max` is returned even without delimiter.
+    pub fn read_until_limited(
+        &mut self,
+        delim: u8,
+        buf: &mut Vec<u8>,
+        max: usize,
+    ) -> Result<usize, ReadLimitedError> {
+        let mut unfinished_skip = self.unfinished_skip.take();
+        let record = read_record_limited(
+            self,
+            &mut unfinished_skip,
+            Self::get_global_offset,
+            Self::get_global_offset,
+            delim,
+            buf,
+            max,
+        );
+        self.unfinished_skip = unfinished_skip;
+        match record? {
+            LimitedRecord::Read(size_read) => Ok(size_read),
+            LimitedRecord::TooLong { start, .. } => {
+                Err(ReadLimitedError::TooLong { offset: start, max })
+            }
+        }
+    }
+
+    /// Like `BufRead::read_line`, but skips lines longer than `max` bytes. See `read_until_limited` for details.
+    ///
+    /// Like `BufRead::read_line` does, InvalidData io error is returned if line is not valid UTF-8. Such line is consumed
+    /// and dropped, nothing is appended to `buf`.
+    pub fn read_line_limited(
+        &mut self,
+        buf: &mut String,
+        max: usize,
+    ) -> Result<usize, ReadLimitedError> {
+        let mut line = vec![];
+        let size_read = self.read_until_limited(b'\n', &mut line, max)?;
+        match String::from_utf8(line) {
+            Ok(line) => {
+                buf.push_str(&line);
+                Ok(size_read)
+            }
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )
+            .into()),
+        }
+    }
+
+    /// Read line as raw bytes, like `BufRead::read_until` with `\n` delimiter. Returns number of bytes read.
+    pub fn read_line_raw(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.read_until(b'\n', buf)
+    }
+
+    /// Like `BufRead::read_line`, but invalid UTF-8 sequences are replaced with U+FFFD instead of failing with
+    /// InvalidData error, so that occasional binary junk in a log does not stop reading.
+    ///
+    /// Returned number of raw bytes read may differ from the length appended to `buf`, only the former is to be used for
+    /// offset computations.
+    pub fn read_line_lossy(&mut self, buf: &mut String) -> io::Result<usize> {
+        let mut line = vec![];
+        let size_read = self.read_line_raw(&mut line)?;
+        buf.push_str(&String::from_utf8_lossy(&line));
+        Ok(size_read)
+    }
+
+    /// Iterate over lines like `BufRead::lines` does, additionally yielding global offset at which each line starts.
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use filetrack::Multireader;
+    /// let items = vec![Cursor::new("first\nsec"), Cursor::new("ond\nthird")];
+    /// let mut reader = Multireader::new(items)?;
+    /// let lines = reader.lines_with_offsets().collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(lines, vec![(0, "first".to_string()), (6, "second".to_string()), (13, "third".to_string())]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn lines_with_offsets(&mut self) -> LinesWithOffsets<'_, R> {
+        LinesWithOffsets { reader: self }
+    }
+
+    /// Iterate over blocks of `chunk_size` bytes, last one of which may be shorter.
+    ///
+    /// Panics if `chunk_size` is 0.
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use filetrack::Multireader;
+    /// let items = vec![Cursor::new(vec![1, 2, 3]), Cursor::new(vec![4, 5])];
+    /// let mut reader = Multireader::new(items)?;
+    /// let chunks = reader.chunks(2).collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(chunks[1].data, vec![3, 4]);
+    /// assert_eq!(chunks[1].offset, 2);
+    /// assert!(chunks[2].is_last);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn chunks(&mut self, chunk_size: usize) -> Chunks<'_, R> {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        Chunks {
+            reader: self,
+            chunk_size,
+        }
+    }
+
+    /// Iterate over records separated by `delim` byte, yielding each record along with its position.
+    ///
+    /// Last record does not need to be terminated by delimiter.
+    ///
+    /// ```rust
+    /// # use std::io::Cursor;
+    /// # use filetrack::Multireader;
+    /// let items = vec![Cursor::new(b"ab\0c".to_vec()), Cursor::new(b"d\0".to_vec())];
+    /// let mut reader = Multireader::new(items)?;
+    /// let records = reader.records(b'\0').collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(records[1].data, b"cd");
+    /// assert_eq!(records[1].offset, 3);
+    /// assert_eq!(records[1].len, 3);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn records(&mut self, delim: u8) -> Records<'_, R> {
+        Records {
+            reader: self,
+            delim,
+        }
+    }
+}
+
+/// Reader limited to a number of bytes that advances borrowed `Multireader`, see `Multireader::take_scoped`.
+pub struct ScopedTake<'a, R: Seek> {
+    reader: &'a mut Multireader<R>,
+    limit: u64,
+}
+
+impl<R: Seek> ScopedTake<'_, R> {
+    /// Number of bytes that can still be read.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Global offset of borrowed reader.
+    pub fn get_global_offset(&self) -> u64 {
+        self.reader.get_global_offset()
+    }
+}
+
+impl<R: Read + Seek> Read for ScopedTake<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let max_len = buf.len().min(self.limit.try_into().unwrap_or(usize::MAX));
+        let size_read = self.reader.read(&mut buf[..max_len])?;
+        self.limit -= size_read as u64;
+        Ok(size_read)
+    }
+}
+
+impl<R: BufRead + Seek> BufRead for ScopedTake<'_, R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.limit == 0 {
+            return Ok(&[]);
+        }
+        let limit = self.limit.try_into().unwrap_or(usize::MAX);
+        let buf = self.reader.fill_buf()?;
+        Ok(&buf[..buf.len().min(limit)])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let amt = amt.min(self.limit.try_into().unwrap_or(usize::MAX));
+        self.reader.consume(amt);
+        self.limit -= amt as u64;
+    }
+}
+
+/// Fixed-size block read from `Multireader`, see `Multireader::chunks`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// content of chunk, full-sized unless this is the last chunk
+    pub data: Vec<u8>,
+    /// global offset at which chunk starts
+    pub offset: u64,
+    /// true if there was no more data after this chunk at the moment it was read
+    pub is_last: bool,
+}
+
+/// Iterator over fixed-size blocks of `Multireader`, see `Multireader::chunks`.
+pub struct Chunks<'a, R: Seek> {
+    reader: &'a mut Multireader<R>,
+    chunk_size: usize,
+}
+
+impl<R: BufRead + Seek> Chunks<'_, R> {
+    fn read_chunk(&mut self) -> io::Result<Option<Chunk>> {
+        let offset = self.reader.get_global_offset();
+        let mut data = Vec::with_capacity(self.chunk_size);
+        self.reader
+            .take_scoped(self.chunk_size as u64)
+            .read_to_end(&mut data)?;
+        if data.is_empty() {
+            return Ok(None);
+        }
+        let is_last = data.len() < self.chunk_size || !self.reader.has_data_left()?;
+        Ok(Some(Chunk {
+            data,
+            offset,
+            is_last,
+        }))
+    }
+}
+
+impl<R: BufRead + Seek> Iterator for Chunks<'_, R> {
+    type Item = io::Result<Chunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_chunk().transpose()
+    }
+}
+
+/// Possible errors of `Multireader::read_until_limited` and `Multireader::read_line_limited`.
+#[derive(Error, Debug)]
+pub enum ReadLimitedError {
+    #[error("while reading underlying items")]
+    IO(#[from] io::Error),
+    #[error("record starting at offset {offset} is longer than {max} bytes")]
+    TooLong { offset: u64, max: usize },
+}
+
+/// Position at which skipping of too long record stopped because data ended before its delimiter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct UnfinishedSkip {
+    global_offset: u64,
+    delim: u8,
+}
+
+/// Outcome of `read_record_limited`.
+pub(crate) enum LimitedRecord<T> {
+    /// number of bytes appended to buffer
+    Read(usize),
+    /// record was skipped, `length` is number of bytes skipped so far
+    TooLong { start: T, length: u64 },
+}
+
+/// Implementation of `Multireader::read_until_limited` shared with `InodeAwareReader`. `global_offset` tells position of
+/// `reader`, while `record_start` describes start of a too long record once reader has data.
+pub(crate) fn read_record_limited<B: BufRead, T>(
+    reader: &mut B,
+    unfinished_skip: &mut Option<UnfinishedSkip>,
+    global_offset: impl Fn(&B) -> u64,
+    record_start: impl FnOnce(&B) -> T,
+    delim: u8,
+    buf: &mut Vec<u8>,
+    max: usize,
+) -> io::Result<LimitedRecord<T>> {
+    let unfinished = UnfinishedSkip {
+        global_offset: global_offset(reader),
+        delim,
+    };
+    if unfinished_skip.take() == Some(unfinished) {
+        let (found, _) = consume_until(reader, delim, |_| {})?;
+        if !found {
+            *unfinished_skip = Some(UnfinishedSkip {
+                global_offset: global_offset(reader),
+                delim,
+            });
+            return Ok(LimitedRecord::Read(0));
+        }
+    }
+    let has_data = loop {
+        match reader.fill_buf() {
+            Ok(available) => break !available.is_empty(),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    };
+    if !has_data {
+        return Ok(LimitedRecord::Read(0));
+    }
+    let start = record_start(reader);
+
+    let original_len = buf.len();
+    let mut too_long = false;
+    let (found, length) = consume_until(reader, delim, |part| {
+        too_long |= buf.len() - original_len + part.len() > max;
+        if !too_long {
+            buf.extend_from_slice(part);
+        }
+    })?;
+    if !too_long {
+        return Ok(LimitedRecord::Read(length as usize));
+    }
+    buf.truncate(original_len);
+    if !found {
+        *unfinished_skip = Some(UnfinishedSkip {
+            global_offset: global_offset(reader),
+            delim,
+        });
+    }
+    Ok(LimitedRecord::TooLong { start, length })
+}
+
+/// Consume data up to and including `delim` or until data ends, passing consumed parts to `f`. Returns whether delimiter
+/// was found and number of bytes consumed.
+fn consume_until<B: BufRead>(
+    reader: &mut B,
+    delim: u8,
+    mut f: impl FnMut(&[u8]),
+) -> io::Result<(bool, u64)> {
+    let mut consumed = 0;
+    loop {
+        let (found, used) = match reader.fill_buf() {
+            Ok(available) => {
+                let (found, used) = match available.iter().position(|&byte| byte == delim) {
+                    Some(position) => (true, position + 1),
+                    None => (false, available.len()),
+                };
+                f(&available[..used]);
+                (found, used)
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        reader.consume(used);
+        consumed += used as u64;
+        if found || used == 0 {
+            return Ok((found, consumed));
+        }
+    }
+}
+
+/// Delimited record read from `Multireader`, see `Multireader::records`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// content of record without delimiter
+    pub data: Vec<u8>,
+    /// global offset at which record starts
+    pub offset: u64,
+    /// number of bytes occupied by record including delimiter (if present)
+    pub len: u64,
+}
+
+/// Iterator over delimited records of `Multireader`, see `Multireader::records`.
+pub struct Records<'a, R: Seek> {
+    reader: &'a mut Multireader<R>,
+    delim: u8,
+}
+
+impl<R: BufRead + Seek> Iterator for Records<'_, R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.reader.get_global_offset();
+        let mut data = vec![];
+        match self.reader.read_until(self.delim, &mut data) {
+            Ok(0) => None,
+            Ok(len) => {
+                if data.last() == Some(&self.delim) {
+                    data.pop();
+                }
+                Some(Ok(Record {
+                    data,
+                    offset,
+                    len: len as u64,
+                }))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Iterator over lines of `Multireader` together with their starting global offsets, see `Multireader::lines_with_offsets`.
+///
+/// Like `BufRead::lines`, yielded lines do not include trailing newline (`\n` or `\r\n`).
+pub struct LinesWithOffsets<'a, R: Seek> {
+    reader: &'a mut Multireader<R>,
+}
+
+impl<R: BufRead + Seek> Iterator for LinesWithOffsets<'_, R> {
+    type Item = io::Result<(u64, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line_start = self.reader.get_global_offset();
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => {
+                if line.ends_with('\n') {
+                    line.pop();
+                    if line.ends_with('\r') {
+                        line.pop();
+                    }
                 }
+                Some(Ok((line_start, line)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl<R: BufRead + Seek> BufRead for Multireader<R> {
+    /// Fill buffer of current item, moving on to the following items if current one is drained.
+    ///
+    /// Returned slice is empty only if there is no data left in all remaining items.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        loop {
+            let item_index = self.get_current_item_index();
+            // no need to check for emptiness when item is known to have data ahead
+            let has_data_ahead = self
+                .offsets
+                .get(item_index)
+                .is_some_and(|&item_end| item_end > self.global_offset)
+                || (self.positioned_item == Some(item_index)
+                    && self.buffered_end > self.global_offset);
+            if has_data_ahead || item_index + 1 >= self.len() {
+                break;
+            }
+            // we cannot return buffer from inside of the loop due to borrowck limitations,
+            // so we only check for emptiness here and fill the buffer again afterwards
+            let Some(item) = self.current_item()? else {
+                break;
+            };
+            if !item.fill_buf()?.is_empty() {
+                break;
+            }
+            self.move_past_drained_item();
+        }
+        if self.current_item()?.is_none() {
+            return Ok(&[]);
+        }
+        let buf = self.items[self.current_item].fill_buf()?;
+        self.buffered_end = self.global_offset + buf.len() as u64;
+        Ok(buf)
+    }
+
+    /// Mark `amt` bytes returned by `fill_buf` as read.
+    ///
+    /// `amt` is clamped to the number of bytes that are still outstanding from the last `fill_buf` call, so consuming
+    /// more than was returned (or consuming without filling buffer first) cannot move global offset past data that was
+    /// actually handed out.
+    fn consume(&mut self, amt: usize) {
+        let item_index = self.get_current_item_index();
+        if self.positioned_item != Some(item_index) {
+            return;
+        }
+        let outstanding = self.buffered_end.saturating_sub(self.global_offset);
+        let amt = amt.min(outstanding as usize);
+        if amt == 0 {
+            return;
+        }
+        self.items[item_index].consume(amt);
+        self.global_offset += amt as u64;
+        self.record_read(item_index, amt as u64);
+        self.advance_current_item_index();
+    }
+}
+
+impl<R: CloneReader + Seek> Multireader<R> {
+    /// Create another reader over the same items with independent position, initially equal to position of this one.
+    ///
+    /// Offsets table is shared at the moment of call, so sizes discovered so far are not measured again. Files can be
+    /// cloned only on Linux and Windows, see `CloneReader` implementation for `BufReader<File>`.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        let items = self
+            .items
+            .iter()
+            .map(CloneReader::try_clone_reader)
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            items,
+            offsets: self.offsets.clone(),
+            global_offset: self.global_offset,
+            current_item: self.current_item,
+            positioned_item: None,
+            buffered_end: 0,
+            size_cache_policy: self.size_cache_policy,
+            cached_last_size: self.cached_last_size,
+            // hook may not be cloneable
+            item_change_hook: None,
+            stats_enabled: false,
+            stats: ReaderStats::default(),
+            unfinished_skip: self.unfinished_skip,
+        })
+    }
+
+    /// Split reader into two independent readers covering `[0, global_offset)` and `[global_offset, end)` respectively.
+    ///
+    /// If split falls inside an item, head gets a length-limited view of it while tail gets a view of cloned handle starting at
+    /// split point. Both readers are positioned at their start. Offset past the end of the last item produces empty tail.
+    /// Splitting inside a file is possible only on Linux and Windows, see `CloneReader` implementation for `BufReader<File>`.
+    pub fn split_at(
+        mut self,
+        global_offset: u64,
+    ) -> io::Result<(WindowedMultireader<R>, WindowedMultireader<R>)> {
+        if self.is_empty() {
+            return Ok((Multireader::new(vec![])?, Multireader::new(vec![])?));
+        }
+        self.discover_sizes_until(global_offset)?;
+        let split_index = self.find_item_index(global_offset);
+        let split_item_start = self.item_start_offset(split_index).unwrap_or_default();
+        let local_offset = global_offset - split_item_start;
+
+        // items before split one have known sizes since they precede split offset
+        let mut head_sizes: Vec<_> = (0..split_index)
+            .map(|item_index| self.get_item_size_cached(item_index).unwrap_or_default())
+            .collect();
+        let mut tail_items = self.items.split_off(split_index);
+        let mut head_items = self
+            .items
+            .into_iter()
+            .map(|item| ItemWindow::new(item, 0, None))
+            .collect::<io::Result<Vec<_>>>()?;
+        if local_offset > 0 {
+            let split_item = tail_items[0].try_clone_reader()?;
+            head_items.push(ItemWindow::new(split_item, 0, Some(local_offset))?);
+            head_sizes.push(local_offset);
+        }
+        let head = Multireader::with_sizes(head_items, head_sizes)?;
+
+        let mut tail_items = tail_items.drain(..);
+        let split_item = tail_items
+            .next()
+            .map(|item| ItemWindow::new(item, local_offset, None));
+        let tail_items = split_item
+            .into_iter()
+            .chain(tail_items.map(|item| ItemWindow::new(item, 0, None)))
+            .collect::<io::Result<_>>()?;
+        let mut tail = Multireader::new(tail_items)?;
+        tail.offsets = self.offsets[split_index..]
+            .iter()
+            .map(|item_end| item_end - global_offset)
+            .collect();
+        Ok((head, tail))
+    }
+}
+
+impl Multireader<BufReader<File>> {
+    /// Open files located at `paths` (in order) and create a Multireader from them.
+    ///
+    /// Error produced while opening a file mentions its path.
+    ///
+    /// ```rust
+    /// # use std::io::Read;
+    /// # use filetrack::Multireader;
+    /// let dir = tempfile::tempdir()?;
+    /// std::fs::write(dir.path().join("log.1"), "old\n")?;
+    /// std::fs::write(dir.path().join("log"), "new\n")?;
+    ///
+    /// let mut reader = Multireader::from_paths([dir.path().join("log.1"), dir.path().join("log")])?;
+    /// let mut content = String::new();
+    /// reader.read_to_string(&mut content)?;
+    /// assert_eq!(content, "old\nnew\n");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_paths(paths: impl IntoIterator<Item = impl AsRef<Path>>) -> io::Result<Self> {
+        Self::from_files(paths, BufReader::new)
+    }
+
+    /// Like `from_paths` but allows specifying capacity of `BufReader` created for each file.
+    pub fn from_paths_with_capacity(
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+        capacity: usize,
+    ) -> io::Result<Self> {
+        Self::from_files(paths, |file| BufReader::with_capacity(capacity, file))
+    }
+
+    fn from_files(
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+        wrap: impl Fn(File) -> BufReader<File>,
+    ) -> io::Result<Self> {
+        let items = paths
+            .into_iter()
+            .map(|path| {
+                let path = path.as_ref();
+                File::open(path).map(&wrap).map_err(|e| {
+                    io::Error::new(e.kind(), format!("while opening {}: {e}", path.display()))
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        Self::new(items)
+    }
+}
+
+impl Multireader<Box<dyn ReadSeek>> {
+    /// Create a Multireader from readers of different types.
+    ///
+    /// ```rust
+    /// # use std::io::{BufReader, Cursor, Read, Seek, Write};
+    /// # use filetrack::{BoxedMultireader, Multireader, ReadSeek};
+    /// // eg. rotated file that was decompressed into memory
+    /// let inflated = Cursor::new(b"old line\n".to_vec());
+    /// let mut file = tempfile::tempfile()?;
+    /// file.write_all(b"new line\n")?;
+    /// file.rewind()?;
+    ///
+    /// let items: Vec<Box<dyn ReadSeek>> = vec![Box::new(inflated), Box::new(BufReader::new(file))];
+    /// let mut reader: BoxedMultireader = Multireader::boxed(items)?;
+    /// let mut content = String::new();
+    /// reader.read_to_string(&mut content)?;
+    /// assert_eq!(content, "old line\nnew line\n");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn boxed(items: Vec<Box<dyn ReadSeek>>) -> io::Result<BoxedMultireader> {
+        Self::new(items)
+    }
+}
+
+/// Same as `Multireader::new`.
+///
+/// ```rust
+/// # use std::io::Cursor;
+/// # use filetrack::Multireader;
+/// let reader = Multireader::try_from(vec![Cursor::new(vec![1, 2, 3]), Cursor::new(vec![4, 5])])?;
+/// assert_eq!(reader.len(), 2);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+impl<R: Seek> TryFrom<Vec<R>> for Multireader<R> {
+    type Error = io::Error;
+
+    fn try_from(items: Vec<R>) -> io::Result<Self> {
+        Self::new(items)
+    }
+}
+
+/// Consumes reader yielding underlying items in order, same as `into_inner().into_iter()`.
+impl<R: Seek> IntoIterator for Multireader<R> {
+    type Item = R;
+    type IntoIter = std::vec::IntoIter<R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_inner().into_iter()
+    }
+}
+
+/// Prints indexing state and position without requiring underlying items to implement `Debug`.
+impl<R: Seek> Debug for Multireader<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Multireader")
+            .field("items", &self.len())
+            .field("offsets", &self.offsets)
+            .field("global_offset", &self.global_offset)
+            .field("current_item_index", &self.get_current_item_index())
+            .field("local_offset", &self.get_local_offset())
+            .finish()
+    }
+}
+
+impl<R: Seek> Seek for Multireader<R> {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        match pos {
+            io::SeekFrom::Start(offset) => {
+                if self.is_empty() {
+                    return match offset {
+                        0 => Ok(0),
+                        _ => Err(empty_reader_error()),
+                    };
+                }
+                self.discover_sizes_until(offset)?;
+                self.set_global_offset(offset);
+                // other items will be positioned once they become current
+                let item_index = self.get_current_item_index();
+                let local_offset = self.get_local_offset();
+                self.items[item_index].seek(io::SeekFrom::Start(local_offset))?;
+                self.set_positioned_item(item_index);
+
+                Ok(self.global_offset)
+            }
+            io::SeekFrom::End(offset) => {
+                let total_size = self.get_total_size()?;
+                let real_offset = total_size as i64 + offset;
+                if real_offset < 0 {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "negative real offset after seek",
+                    ));
+                }
+                self.seek(io::SeekFrom::Start(real_offset as u64))
+            }
+            // position is tracked by us, so there is no need to touch underlying items
+            io::SeekFrom::Current(0) => Ok(self.global_offset),
+            io::SeekFrom::Current(offset) => {
+                let new_position = self.global_offset as i64 + offset;
+                if new_position < 0 {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "negative real offset after seek",
+                    ));
+                }
+                self.seek(io::SeekFrom::Start(new_position as u64))
+            }
+        }
+    }
+
+    /// Seek to the start of reader. Only first item is touched, others will be positioned once they become current.
+    fn rewind(&mut self) -> io::Result<()> {
+        if let Some(first_item) = self.items.first_mut() {
+            first_item.rewind()?;
+            self.set_positioned_item(0);
+        }
+        self.set_global_offset(0);
+        Ok(())
+    }
+
+    /// Returns global offset without performing any seeks in underlying items.
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.global_offset)
+    }
+
+    /// Seek relative to current position, preserving buffers of underlying items if possible.
+    ///
+    /// If target position lies inside current item, seek is forwarded to item's `seek_relative` (which for `BufReader` does
+    /// not discard buffer if target is inside of it). Otherwise regular seek is performed. Note that if size of current item
+    /// was not discovered yet and it is not the last one, only moving backwards is forwarded to the item.
+    fn seek_relative(&mut self, offset: i64) -> io::Result<()> {
+        let target = self.global_offset as i64 + offset;
+        if target < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "negative real offset after seek",
+            ));
+        }
+        let target = target as u64;
+        let item_index = self.get_current_item_index();
+        let inside_current_item = target >= self.get_bytes_before_current_item()
+            && match self.offsets.get(item_index) {
+                Some(&item_end) => target <= item_end,
+                // last item may grow, while for item with unknown size only moving back is safe
+                None => item_index + 1 >= self.len() || target <= self.global_offset,
+            };
+
+        if !inside_current_item || self.positioned_item != Some(item_index) {
+            self.seek(SeekFrom::Start(target))?;
+            return Ok(());
+        }
+        self.items[item_index].seek_relative(offset)?;
+        // buffer of an item may be discarded by seek
+        self.buffered_end = 0;
+        self.set_global_offset(target);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Cursor, IoSliceMut, Read, Seek, SeekFrom},
+        sync::{Arc, Mutex},
+    };
+
+    use rstest::{fixture, rstest};
+
+    use super::{
+        Multireader, MultireaderSnapshot, MultireaderSummary, ReadLimitedError, ReaderStats,
+        RestoreError, SizeCachePolicy, WindowedMultireader,
+    };
+
+    type FakeReader = Multireader<Cursor<Vec<u8>>>;
+
+    /// Cursor that counts seeks performed on it.
+    struct SeekCountingCursor {
+        inner: Cursor<Vec<u8>>,
+        seeks: usize,
+    }
+
+    impl SeekCountingCursor {
+        fn new(content: Vec<u8>) -> Self {
+            Self {
+                inner: Cursor::new(content),
+                seeks: 0,
+            }
+        }
+    }
+
+    impl Read for SeekCountingCursor {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.inner.read(buf)
+        }
+    }
+
+    impl BufRead for SeekCountingCursor {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            self.inner.fill_buf()
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.inner.consume(amt)
+        }
+    }
+
+    impl Seek for SeekCountingCursor {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.seeks += 1;
+            self.inner.seek(pos)
+        }
+    }
+
+    /// Cursor that counts reads performed on it.
+    struct ReadCountingCursor {
+        inner: Cursor<Vec<u8>>,
+        reads: usize,
+    }
+
+    impl Read for ReadCountingCursor {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.reads += 1;
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for ReadCountingCursor {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    fn counting_reader(items: usize) -> Multireader<SeekCountingCursor> {
+        let items = (0..items)
+            .map(|i| SeekCountingCursor::new(vec![i as u8; 3]))
+            .collect();
+        Multireader::new(items).unwrap()
+    }
+
+    fn seek_counts(reader: Multireader<SeekCountingCursor>) -> Vec<usize> {
+        reader
+            .into_inner()
+            .into_iter()
+            .map(|item| item.seeks)
+            .collect()
+    }
+
+    #[fixture]
+    fn singleitem_reader() -> FakeReader {
+        Multireader::new(vec![Cursor::new(vec![1, 2, 3])]).unwrap()
+    }
+
+    #[fixture]
+    fn multiitem_reader() -> FakeReader {
+        Multireader::new(vec![Cursor::new(vec![1, 2, 3]), Cursor::new(vec![4, 5])]).unwrap()
+    }
+
+    #[rstest]
+    fn reader_should_read_from_one_item(mut singleitem_reader: FakeReader) {
+        let mut buf = vec![];
+        singleitem_reader.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, vec![1, 2, 3])
+    }
+
+    #[rstest]
+    fn reader_should_seek_into_inner_item(mut singleitem_reader: FakeReader) {
+        singleitem_reader.seek(std::io::SeekFrom::Start(1)).unwrap();
+        assert_eq!(singleitem_reader.get_global_offset(), 1);
+        assert_eq!(singleitem_reader.get_local_offset(), 1);
+
+        let mut buf = vec![255];
+        singleitem_reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, vec![2]);
+    }
+
+    #[rstest]
+    fn reader_should_seek_into_first_item(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek(std::io::SeekFrom::Start(1)).unwrap();
+        assert_eq!(multiitem_reader.get_global_offset(), 1);
+        assert_eq!(multiitem_reader.get_local_offset(), 1);
+        assert_eq!(multiitem_reader.get_current_item_index(), 0);
+
+        let mut buf = vec![255];
+        multiitem_reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, vec![2]);
+    }
+
+    #[rstest]
+    fn reader_should_seek_into_second_item(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek(std::io::SeekFrom::Start(4)).unwrap();
+        assert_eq!(multiitem_reader.get_global_offset(), 4);
+        assert_eq!(multiitem_reader.get_local_offset(), 1);
+        assert_eq!(multiitem_reader.get_current_item_index(), 1);
+
+        let mut buf = vec![255];
+        multiitem_reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, vec![5]);
+    }
+
+    #[test]
+    fn combining_read_and_bufread_should_advance_offset_properly() {
+        let text = "first\nsecond".to_string();
+        let mut reader = Multireader::new(vec![Cursor::new(text)]).unwrap();
+        let mut input = String::new();
+        reader.read_line(&mut input).unwrap();
+        let mut buf = vec![];
+        reader.read_to_end(&mut buf).unwrap();
+        assert_eq!(reader.get_global_offset(), 12);
+    }
+
+    #[rstest]
+    fn total_size_is_computed_correctly(mut multiitem_reader: FakeReader) {
+        assert_eq!(multiitem_reader.get_total_size().unwrap(), 5)
+    }
+
+    fn read_to_end(mut r: impl Read) -> Vec<u8> {
+        let mut buf = vec![];
+        r.read_to_end(&mut buf).unwrap();
+        buf
+    }
+
+    #[rstest]
+    #[case(0, 0, b"\x01\x02\x03\x04\x05")]
+    #[case(1, 3, b"\x04\x05")]
+    fn seek_to_item_start_works(
+        mut multiitem_reader: FakeReader,
+        #[case] item: usize,
+        #[case] expected_offset: u64,
+        #[case] expected_content: &'static [u8],
+    ) {
+        assert_eq!(
+            multiitem_reader.seek_to_item_start(item).unwrap(),
+            expected_offset
+        );
+        assert_eq!(read_to_end(multiitem_reader), expected_content)
+    }
+
+    #[rstest]
+    #[case(0, 0, 0)]
+    #[case(0, 1, 1)]
+    #[case(1, 0, 3)]
+    #[case(1, 1, 4)]
+    fn seek_by_local_index_works(
+        mut multiitem_reader: FakeReader,
+        #[case] item_idx: usize,
+        #[case] index_inside_item: u64,
+        #[case] expected_offset: u64,
+    ) {
+        multiitem_reader
+            .seek_by_local_index(item_idx, std::io::SeekFrom::Start(index_inside_item))
+            .unwrap();
+
+        assert_eq!(multiitem_reader.get_global_offset(), expected_offset)
+    }
+
+    #[fixture]
+    fn split_line_reader() -> FakeReader {
+        Multireader::new(vec![
+            Cursor::new(b"hel".to_vec()),
+            Cursor::new(b"lo\nworld".to_vec()),
+        ])
+        .unwrap()
+    }
+
+    #[rstest]
+    fn read_line_continues_across_items(mut split_line_reader: FakeReader) {
+        let mut input = String::new();
+        split_line_reader.read_line(&mut input).unwrap();
+        assert_eq!(input, "hello\n");
+        assert_eq!(split_line_reader.get_global_offset(), 6);
+        assert_eq!(split_line_reader.get_current_item_index(), 1);
+        assert_eq!(split_line_reader.get_local_offset(), 3);
+    }
+
+    #[rstest]
+    fn read_until_continues_across_items(mut split_line_reader: FakeReader) {
+        let mut buf = vec![];
+        split_line_reader.read_until(b'\n', &mut buf).unwrap();
+        assert_eq!(buf, b"hello\n");
+        buf.clear();
+        split_line_reader.read_until(b'\n', &mut buf).unwrap();
+        assert_eq!(buf, b"world");
+        assert_eq!(split_line_reader.get_global_offset(), 11);
+    }
+
+    #[rstest]
+    fn lines_continue_across_items(split_line_reader: FakeReader) {
+        let lines = split_line_reader
+            .lines()
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(lines, vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn fill_buf_skips_drained_items() {
+        let mut reader = Multireader::new(vec![
+            Cursor::new(b"a".to_vec()),
+            Cursor::new(vec![]),
+            Cursor::new(b"b".to_vec()),
+        ])
+        .unwrap();
+        assert_eq!(reader.fill_buf().unwrap(), b"a");
+        reader.consume(1);
+        assert_eq!(reader.fill_buf().unwrap(), b"b");
+        assert_eq!(reader.get_current_item_index(), 2);
+        assert_eq!(reader.get_global_offset(), 1);
+    }
+
+    #[test]
+    fn single_read_fills_buffer_from_multiple_items() {
+        let mut reader = Multireader::new(vec![
+            Cursor::new(vec![1, 2]),
+            Cursor::new(vec![3]),
+            Cursor::new(vec![4, 5, 6]),
+        ])
+        .unwrap();
+        let mut buf = [0u8; 8192];
+        assert_eq!(reader.read(&mut buf).unwrap(), 6);
+        assert_eq!(&buf[..6], &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(reader.get_global_offset(), 6);
+        assert_eq!(reader.get_current_item_index(), 2);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    }
+
+    #[rstest]
+    fn read_stops_when_buffer_is_full(mut multiitem_reader: FakeReader) {
+        let mut buf = [0u8; 4];
+        assert_eq!(multiitem_reader.read(&mut buf).unwrap(), 4);
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert_eq!(multiitem_reader.get_global_offset(), 4);
+        assert_eq!(multiitem_reader.get_local_offset(), 1);
+    }
+
+    #[test]
+    fn single_item_reads_behave_like_underlying_reader() {
+        let content = (0..1_000_000u32).map(|i| i as u8).collect::<Vec<_>>();
+        let mut plain = Cursor::new(content.clone());
+        let mut reader = Multireader::new(vec![Cursor::new(content)]).unwrap();
+
+        let mut plain_buf = [0u8; 8192];
+        let mut buf = [0u8; 8192];
+        loop {
+            let plain_size = plain.read(&mut plain_buf).unwrap();
+            let size = reader.read(&mut buf).unwrap();
+            assert_eq!(size, plain_size);
+            assert_eq!(buf[..size], plain_buf[..plain_size]);
+            assert_eq!(reader.get_global_offset(), plain.position());
+            if size == 0 {
+                break;
+            }
+        }
+    }
+
+    #[fixture]
+    fn empty_reader() -> FakeReader {
+        Multireader::new(vec![]).unwrap()
+    }
+
+    #[rstest]
+    fn empty_reader_yields_eof(mut empty_reader: FakeReader) {
+        assert!(empty_reader.is_empty());
+        assert_eq!(empty_reader.len(), 0);
+        assert_eq!(read_to_end(&mut empty_reader), b"");
+        assert_eq!(empty_reader.fill_buf().unwrap(), b"");
+        empty_reader.consume(0);
+        let mut input = String::new();
+        assert_eq!(empty_reader.read_line(&mut input).unwrap(), 0);
+        assert_eq!(empty_reader.get_global_offset(), 0);
+        assert_eq!(empty_reader.get_local_offset(), 0);
+    }
+
+    #[rstest]
+    fn empty_reader_has_zero_size(mut empty_reader: FakeReader) {
+        assert_eq!(empty_reader.get_total_size().unwrap(), 0);
+        assert_eq!(empty_reader.get_last_item_size().unwrap(), 0);
+        assert_eq!(empty_reader.get_current_item_size(), None);
+        assert_eq!(empty_reader.get_bytes_before_current_item(), 0);
+    }
+
+    #[rstest]
+    #[case(SeekFrom::Start(0))]
+    #[case(SeekFrom::End(0))]
+    #[case(SeekFrom::Current(0))]
+    fn empty_reader_allows_seeking_to_zero(mut empty_reader: FakeReader, #[case] pos: SeekFrom) {
+        assert_eq!(empty_reader.seek(pos).unwrap(), 0);
+        assert_eq!(empty_reader.seek_to_item_start(0).unwrap(), 0);
+    }
+
+    #[rstest]
+    #[case(SeekFrom::Start(1))]
+    #[case(SeekFrom::End(1))]
+    #[case(SeekFrom::End(-1))]
+    #[case(SeekFrom::Current(1))]
+    fn empty_reader_rejects_seeking_elsewhere(mut empty_reader: FakeReader, #[case] pos: SeekFrom) {
+        assert_eq!(
+            empty_reader.seek(pos).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            empty_reader
+                .seek_current_item(SeekFrom::Start(0))
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[rstest]
+    fn push_preserves_position(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek(SeekFrom::Start(4)).unwrap();
+        multiitem_reader.push(Cursor::new(vec![6, 7])).unwrap();
+        assert_eq!(multiitem_reader.len(), 3);
+        assert_eq!(multiitem_reader.get_global_offset(), 4);
+        assert_eq!(multiitem_reader.get_current_item_index(), 1);
+        assert_eq!(multiitem_reader.get_local_offset(), 1);
+        assert_eq!(multiitem_reader.get_total_size().unwrap(), 7);
+
+        assert_eq!(read_to_end(&mut multiitem_reader), b"\x05\x06\x07");
+        assert_eq!(multiitem_reader.get_global_offset(), 7);
+        assert_eq!(multiitem_reader.get_current_item_index(), 2);
+    }
+
+    #[rstest]
+    fn push_rewinds_new_item(mut singleitem_reader: FakeReader) {
+        let mut item = Cursor::new(vec![4, 5]);
+        item.seek(SeekFrom::End(0)).unwrap();
+        singleitem_reader.push(item).unwrap();
+        assert_eq!(read_to_end(&mut singleitem_reader), b"\x01\x02\x03\x04\x05");
+    }
+
+    #[rstest]
+    fn push_into_empty_reader(mut empty_reader: FakeReader) {
+        empty_reader.push(Cursor::new(vec![1, 2])).unwrap();
+        assert_eq!(empty_reader.len(), 1);
+        assert_eq!(empty_reader.get_total_size().unwrap(), 2);
+        assert_eq!(read_to_end(&mut empty_reader), b"\x01\x02");
+    }
+
+    #[rstest]
+    fn pop_front_keeps_first_item_while_it_is_read(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek(SeekFrom::Start(2)).unwrap();
+        assert!(multiitem_reader.pop_front().is_none());
+        assert_eq!(multiitem_reader.len(), 2);
+        assert_eq!(multiitem_reader.get_global_offset(), 2);
+    }
+
+    #[rstest]
+    fn pop_front_rebases_offsets(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek(SeekFrom::Start(4)).unwrap();
+        let removed = multiitem_reader.pop_front().unwrap();
+        assert_eq!(removed.into_inner(), vec![1, 2, 3]);
+        assert_eq!(multiitem_reader.len(), 1);
+        assert_eq!(multiitem_reader.get_current_item_index(), 0);
+        assert_eq!(multiitem_reader.get_local_offset(), 1);
+        assert_eq!(multiitem_reader.get_global_offset(), 1);
+        assert_eq!(multiitem_reader.get_total_size().unwrap(), 2);
+        assert_eq!(read_to_end(&mut multiitem_reader), b"\x05");
+    }
+
+    #[test]
+    fn drop_consumed_preserves_following_reads() {
+        let items = || {
+            vec![
+                Cursor::new(vec![1, 2]),
+                Cursor::new(vec![3]),
+                Cursor::new(vec![4, 5]),
+                Cursor::new(vec![6]),
+            ]
+        };
+        let mut original = Multireader::new(items()).unwrap();
+        let mut rebased = Multireader::new(items()).unwrap();
+        original.seek(SeekFrom::Start(4)).unwrap();
+        rebased.seek(SeekFrom::Start(4)).unwrap();
+
+        let removed = rebased.drop_consumed();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(rebased.len(), 2);
+        assert_eq!(rebased.get_current_item_index(), 0);
+        assert_eq!(rebased.get_local_offset(), original.get_local_offset());
+        assert_eq!(rebased.get_global_offset(), 1);
+        assert_eq!(read_to_end(&mut rebased), read_to_end(&mut original));
+    }
+
+    #[test]
+    fn reader_with_sizes_works_like_regular_one() {
+        let items = || vec![Cursor::new(vec![1, 2, 3]), Cursor::new(vec![4, 5])];
+        let mut discovered = Multireader::new(items()).unwrap();
+        let mut provided = Multireader::with_sizes(items(), vec![3, 2]).unwrap();
+        provided.validate_sizes().unwrap();
+
+        provided.seek(SeekFrom::Start(4)).unwrap();
+        discovered.seek(SeekFrom::Start(4)).unwrap();
+        assert_eq!(
+            provided.get_current_item_index(),
+            discovered.get_current_item_index()
+        );
+        assert_eq!(read_to_end(&mut provided), read_to_end(&mut discovered));
+    }
+
+    #[test]
+    fn reader_with_sizes_rejects_mismatched_lengths() {
+        let result = Multireader::with_sizes(vec![Cursor::new(vec![1, 2, 3])], vec![3, 2]);
+        assert_eq!(
+            result.err().map(|e| e.kind()),
+            Some(std::io::ErrorKind::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn validation_detects_wrong_sizes() {
+        let mut reader = Multireader::with_sizes(
+            vec![Cursor::new(vec![1, 2, 3]), Cursor::new(vec![4, 5])],
+            vec![2, 2],
+        )
+        .unwrap();
+        reader.seek(SeekFrom::Start(1)).unwrap();
+        assert_eq!(
+            reader.validate_sizes().unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+        // validation does not move the cursor
+        let mut buf = [0];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [2]);
+    }
+
+    #[test]
+    fn construction_does_not_seek() {
+        assert_eq!(seek_counts(counting_reader(5)), vec![0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn reading_does_not_touch_following_items() {
+        let mut reader = counting_reader(5);
+        let mut buf = [0; 2];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(seek_counts(reader), vec![1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn seeking_does_not_touch_following_items() {
+        let mut reader = counting_reader(5);
+        reader.seek(SeekFrom::Start(4)).unwrap();
+        let mut buf = [0; 1];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [1]);
+        let counts = seek_counts(reader);
+        assert_eq!(counts[2..], [0, 0, 0]);
+    }
+
+    #[test]
+    fn lazy_discovery_matches_known_sizes() {
+        let items = || {
+            vec![
+                Cursor::new(vec![1, 2, 3]),
+                Cursor::new(vec![4]),
+                Cursor::new(vec![5, 6]),
+            ]
+        };
+        let mut lazy = Multireader::new(items()).unwrap();
+        let mut known = Multireader::with_sizes(items(), vec![3, 1, 2]).unwrap();
+        loop {
+            let mut lazy_buf = [0];
+            let mut known_buf = [0];
+            let lazy_size = lazy.read(&mut lazy_buf).unwrap();
+            let known_size = known.read(&mut known_buf).unwrap();
+            assert_eq!(lazy_size, known_size);
+            assert_eq!(lazy_buf, known_buf);
+            assert_eq!(lazy.get_global_offset(), known.get_global_offset());
+            if lazy_size == 0 {
+                break;
+            }
+        }
+        assert_eq!(lazy.get_total_size().unwrap(), 6);
+    }
+
+    fn assert_cached_index_is_valid<R: std::io::Seek>(reader: &Multireader<R>) {
+        assert_eq!(
+            reader.get_current_item_index(),
+            reader.compute_current_item_index()
+        );
+    }
+
+    #[test]
+    fn many_small_consumes_across_many_items() {
+        let items = (0..100).map(|i| Cursor::new(vec![i as u8; 7])).collect();
+        let mut reader = Multireader::new(items).unwrap();
+        let mut content = vec![];
+        loop {
+            let buf = reader.fill_buf().unwrap();
+            if buf.is_empty() {
+                break;
+            }
+            let amt = buf.len().min(3);
+            content.extend_from_slice(&buf[..amt]);
+            reader.consume(amt);
+            assert_cached_index_is_valid(&reader);
+        }
+        assert_eq!(content.len(), 700);
+        assert_eq!(reader.get_current_item_index(), 99);
+        assert_eq!(reader.get_global_offset(), 700);
+        assert!(content
+            .chunks(7)
+            .enumerate()
+            .all(|(i, chunk)| chunk == [i as u8; 7]));
+    }
+
+    #[test]
+    fn cached_index_agrees_with_recomputed_one() {
+        let items = (0..10).map(|i| Cursor::new(vec![i as u8; i])).collect();
+        let mut reader = Multireader::new(items).unwrap();
+        let mut buf = [0; 4];
+        while reader.read(&mut buf).unwrap() != 0 {
+            assert_cached_index_is_valid(&reader);
+        }
+        for offset in [0, 1, 10, 44, 45, 3, 0] {
+            reader.seek(SeekFrom::Start(offset)).unwrap();
+            assert_cached_index_is_valid(&reader);
+        }
+        reader.seek(SeekFrom::End(-2)).unwrap();
+        assert_cached_index_is_valid(&reader);
+        reader.seek_current_item(SeekFrom::Start(0)).unwrap();
+        assert_cached_index_is_valid(&reader);
+        reader.push(Cursor::new(vec![10])).unwrap();
+        assert_cached_index_is_valid(&reader);
+        reader.drop_consumed();
+        assert_cached_index_is_valid(&reader);
+    }
+
+    #[test]
+    fn seek_touches_only_target_item() {
+        let items = (0..5)
+            .map(|i| SeekCountingCursor::new(vec![i; 3]))
+            .collect();
+        let mut reader = Multireader::with_sizes(items, vec![3; 5]).unwrap();
+        let total_seeks = |reader: &Multireader<SeekCountingCursor>| -> usize {
+            reader.items.iter().map(|item| item.seeks).sum()
+        };
+        let mut buf = [0];
+        for (offset, expected_item) in [(13, 4), (1, 0), (7, 2), (6, 2), (14, 4)] {
+            let seeks_before = total_seeks(&reader);
+            reader.seek(SeekFrom::Start(offset)).unwrap();
+            assert_eq!(total_seeks(&reader), seeks_before + 1);
+            reader.read_exact(&mut buf).unwrap();
+            assert_eq!(buf, [expected_item]);
+            // item was positioned by seek, so read must not seek again
+            assert_eq!(total_seeks(&reader), seeks_before + 1);
+        }
+    }
+
+    #[test]
+    fn items_are_positioned_when_reached_after_seeking_back() {
+        let mut reader = Multireader::new(vec![
+            Cursor::new(vec![1, 2]),
+            Cursor::new(vec![3, 4]),
+            Cursor::new(vec![5, 6]),
+        ])
+        .unwrap();
+        reader.seek(SeekFrom::Start(5)).unwrap();
+        reader.seek(SeekFrom::Start(3)).unwrap();
+        reader.seek(SeekFrom::Start(1)).unwrap();
+        assert_eq!(read_to_end(&mut reader), b"\x02\x03\x04\x05\x06");
+    }
+
+    #[test]
+    fn seek_relative_preserves_buffer() {
+        let items = [b"first\nsecond\n".to_vec(), b"third\n".to_vec()]
+            .into_iter()
+            .map(|content| {
+                BufReader::new(ReadCountingCursor {
+                    inner: Cursor::new(content),
+                    reads: 0,
+                })
+            })
+            .collect();
+        let mut reader = Multireader::with_sizes(items, vec![13, 6]).unwrap();
+        let mut input = String::new();
+        reader.read_line(&mut input).unwrap();
+        let reads_before = reader.items[0].get_ref().reads;
+
+        reader.seek_relative(-(input.len() as i64)).unwrap();
+        assert_eq!(reader.get_global_offset(), 0);
+        input.clear();
+        reader.read_line(&mut input).unwrap();
+        assert_eq!(input, "first\n");
+        reader.seek_relative(2).unwrap();
+        input.clear();
+        reader.read_line(&mut input).unwrap();
+        assert_eq!(input, "cond\n");
+        assert_eq!(reader.items[0].get_ref().reads, reads_before);
+    }
+
+    #[rstest]
+    fn seek_relative_crosses_items(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek_relative(4).unwrap();
+        assert_eq!(multiitem_reader.get_global_offset(), 4);
+        assert_eq!(multiitem_reader.get_current_item_index(), 1);
+        multiitem_reader.seek_relative(-3).unwrap();
+        assert_eq!(multiitem_reader.get_current_item_index(), 0);
+        assert_eq!(read_to_end(&mut multiitem_reader), b"\x02\x03\x04\x05");
+        assert!(multiitem_reader.seek_relative(-6).is_err());
+    }
+
+    #[test]
+    fn stream_position_does_not_seek() {
+        let mut reader = counting_reader(3);
+        let mut buf = [0; 2];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(reader.stream_position().unwrap(), 2);
+        reader.fill_buf().unwrap();
+        reader.consume(1);
+        assert_eq!(reader.stream_position().unwrap(), 3);
+        reader.read_exact(&mut buf).unwrap();
+        #[allow(clippy::seek_from_current)]
+        let position = reader.seek(SeekFrom::Current(0)).unwrap();
+        assert_eq!(position, 5);
+        assert_eq!(
+            reader.stream_position().unwrap(),
+            reader.get_global_offset()
+        );
+        // each item was positioned once when it became current
+        assert_eq!(seek_counts(reader), vec![1, 1, 0]);
+    }
+
+    #[rstest]
+    fn vectored_read_spans_items(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek(SeekFrom::Start(1)).unwrap();
+        let mut first = [0; 3];
+        let mut empty = [0; 0];
+        let mut second = [0; 8];
+        let mut bufs = [
+            IoSliceMut::new(&mut first),
+            IoSliceMut::new(&mut empty),
+            IoSliceMut::new(&mut second),
+        ];
+        assert_eq!(multiitem_reader.read_vectored(&mut bufs).unwrap(), 4);
+        assert_eq!(first, [2, 3, 4]);
+        assert_eq!(second[..1], [5]);
+        assert_eq!(multiitem_reader.get_global_offset(), 5);
+        assert_eq!(multiitem_reader.get_current_item_index(), 1);
+        assert_eq!(multiitem_reader.get_local_offset(), 2);
+    }
+
+    #[rstest]
+    fn vectored_read_stops_when_buffers_are_full(mut multiitem_reader: FakeReader) {
+        let mut first = [0; 2];
+        let mut second = [0; 2];
+        let mut bufs = [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)];
+        assert_eq!(multiitem_reader.read_vectored(&mut bufs).unwrap(), 4);
+        assert_eq!([first, second].concat(), vec![1, 2, 3, 4]);
+        assert_eq!(multiitem_reader.get_global_offset(), 4);
+        assert_eq!(multiitem_reader.get_local_offset(), 1);
+    }
+
+    #[test]
+    fn read_to_end_reserves_space_once() {
+        let items = (0..4u8)
+            .map(|i| Cursor::new(vec![i; 1_000_000 + i as usize]))
+            .collect::<Vec<_>>();
+        let expected = items
+            .iter()
+            .flat_map(|item| item.get_ref().clone())
+            .collect::<Vec<_>>();
+        let mut reader = Multireader::new(items).unwrap();
+        let mut buf = vec![];
+        assert_eq!(reader.read_to_end(&mut buf).unwrap(), expected.len());
+        assert_eq!(buf, expected);
+        assert_eq!(buf.capacity(), expected.len());
+        assert_eq!(reader.get_global_offset(), expected.len() as u64);
+        assert_eq!(reader.read_to_end(&mut buf).unwrap(), 0);
+    }
+
+    #[rstest]
+    fn read_to_end_appends_from_current_position(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek(SeekFrom::Start(2)).unwrap();
+        let mut buf = vec![0];
+        assert_eq!(multiitem_reader.read_to_end(&mut buf).unwrap(), 3);
+        assert_eq!(buf, vec![0, 3, 4, 5]);
+    }
+
+    #[test]
+    fn read_to_string_works_across_items() {
+        let mut reader = Multireader::new(vec![Cursor::new("hel"), Cursor::new("lo")]).unwrap();
+        let mut input = String::from(">");
+        assert_eq!(reader.read_to_string(&mut input).unwrap(), 5);
+        assert_eq!(input, ">hello");
+    }
+
+    #[test]
+    fn read_to_string_rejects_invalid_utf8() {
+        let mut reader =
+            Multireader::new(vec![Cursor::new(b"ok".to_vec()), Cursor::new(vec![0xff])]).unwrap();
+        let mut input = String::from(">");
+        assert_eq!(
+            reader.read_to_string(&mut input).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+        assert_eq!(input, ">");
+    }
+
+    #[rstest]
+    fn items_are_accessible_by_index(mut multiitem_reader: FakeReader) {
+        assert_eq!(multiitem_reader.get_item(1).unwrap().get_ref(), &vec![4, 5]);
+        assert!(multiitem_reader.get_item(2).is_none());
+        multiitem_reader
+            .get_item_mut(0)
+            .unwrap()
+            .get_mut()
+            .push(255);
+        assert_eq!(multiitem_reader.get_item(0).unwrap().get_ref().len(), 4);
+    }
+
+    #[rstest]
+    fn resync_adopts_external_seek(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek(SeekFrom::Start(3)).unwrap();
+        multiitem_reader
+            .current_item()
+            .unwrap()
+            .unwrap()
+            .seek(SeekFrom::Start(1))
+            .unwrap();
+        multiitem_reader.resync_current_item().unwrap();
+        assert_eq!(multiitem_reader.get_global_offset(), 4);
+        assert_eq!(multiitem_reader.get_local_offset(), 1);
+        assert_eq!(read_to_end(&mut multiitem_reader), b"\x05");
+    }
+
+    #[rstest]
+    fn external_seek_without_resync_is_undone(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek(SeekFrom::Start(1)).unwrap();
+        multiitem_reader
+            .get_item_mut(0)
+            .unwrap()
+            .seek(SeekFrom::Start(0))
+            .unwrap();
+        assert_eq!(read_to_end(&mut multiitem_reader), b"\x02\x03\x04\x05");
+    }
+
+    #[rstest]
+    fn resync_of_empty_reader_does_nothing(mut empty_reader: FakeReader) {
+        empty_reader.resync_current_item().unwrap();
+        assert!(empty_reader.current_item().unwrap().is_none());
+    }
+
+    #[rstest]
+    fn item_boundaries_of_single_item(mut singleitem_reader: FakeReader) {
+        singleitem_reader.get_total_size().unwrap();
+        assert_eq!(singleitem_reader.item_sizes(), vec![None]);
+        assert_eq!(singleitem_reader.item_start_offset(0), Some(0));
+        assert_eq!(singleitem_reader.item_end_offset(0), None);
+        assert_eq!(singleitem_reader.item_start_offset(1), None);
+    }
+
+    #[rstest]
+    fn item_boundaries_are_discovered_lazily(mut multiitem_reader: FakeReader) {
+        assert_eq!(multiitem_reader.item_sizes(), vec![None, None]);
+        assert_eq!(multiitem_reader.item_start_offset(1), None);
+        multiitem_reader.get_total_size().unwrap();
+        assert_eq!(multiitem_reader.item_sizes(), vec![Some(3), None]);
+        assert_eq!(multiitem_reader.item_start_offset(1), Some(3));
+        assert_eq!(multiitem_reader.item_end_offset(0), Some(3));
+        assert_eq!(multiitem_reader.item_end_offset(1), None);
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(4)]
+    fn item_boundaries_agree_with_current_item(
+        mut multiitem_reader: FakeReader,
+        #[case] offset: u64,
+    ) {
+        multiitem_reader.seek(SeekFrom::Start(offset)).unwrap();
+        let index = multiitem_reader.get_current_item_index();
+        assert_eq!(
+            multiitem_reader.item_start_offset(index),
+            Some(multiitem_reader.get_bytes_before_current_item())
+        );
+        assert_eq!(
+            multiitem_reader.item_sizes()[index],
+            multiitem_reader.get_current_item_size()
+        );
+    }
+
+    fn three_item_reader() -> FakeReader {
+        let items = vec![
+            Cursor::new(vec![1, 2, 3]),
+            Cursor::new(vec![4, 5]),
+            Cursor::new(vec![6, 7, 8, 9]),
+        ];
+        Multireader::with_sizes(items, vec![3, 2, 4]).unwrap()
+    }
+
+    #[rstest]
+    #[case(0, Some((0, 0)))]
+    #[case(2, Some((0, 2)))]
+    #[case(3, Some((1, 0)))]
+    #[case(4, Some((1, 1)))]
+    #[case(5, Some((2, 0)))]
+    #[case(9, Some((2, 4)))]
+    #[case(100, Some((2, 95)))]
+    fn global_to_local_with_known_sizes(
+        #[case] global: u64,
+        #[case] expected: Option<(usize, u64)>,
+    ) {
+        assert_eq!(three_item_reader().global_to_local(global), expected);
+    }
+
+    #[rstest]
+    #[case(0, 0, Some(0))]
+    #[case(0, 3, Some(3))]
+    #[case(0, 4, None)]
+    #[case(1, 0, Some(3))]
+    #[case(1, 2, Some(5))]
+    #[case(1, 3, None)]
+    #[case(2, 0, Some(5))]
+    #[case(2, 10, Some(15))]
+    #[case(3, 0, None)]
+    fn local_to_global_with_known_sizes(
+        #[case] item_index: usize,
+        #[case] local: u64,
+        #[case] expected: Option<u64>,
+    ) {
+        assert_eq!(
+            three_item_reader().local_to_global(item_index, local),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(0, Some((0, 0)))]
+    #[case(1, None)]
+    #[case(3, None)]
+    fn global_to_local_with_undiscovered_sizes(
+        mut multiitem_reader: FakeReader,
+        #[case] global: u64,
+        #[case] expected: Option<(usize, u64)>,
+    ) {
+        assert_eq!(multiitem_reader.global_to_local(global), expected);
+        assert_eq!(multiitem_reader.local_to_global(1, 0), None);
+
+        // position of cursor tells that current item spans at least up to it
+        multiitem_reader.read_exact(&mut [0; 2]).unwrap();
+        assert_eq!(multiitem_reader.global_to_local(2), Some((0, 2)));
+        assert_eq!(multiitem_reader.local_to_global(0, 2), Some(2));
+        assert_eq!(multiitem_reader.global_to_local(3), None);
+    }
+
+    #[rstest]
+    fn conversions_agree_with_cursor(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 4]).unwrap();
+        let expected = (
+            multiitem_reader.get_current_item_index(),
+            multiitem_reader.get_local_offset(),
+        );
+        assert_eq!(multiitem_reader.global_to_local(4), Some(expected));
+        assert_eq!(multiitem_reader.global_to_local(3), Some((1, 0)));
+        assert_eq!(
+            multiitem_reader.local_to_global(expected.0, expected.1),
+            Some(4)
+        );
+    }
+
+    #[rstest]
+    fn conversions_on_empty_reader(empty_reader: FakeReader) {
+        assert_eq!(empty_reader.global_to_local(0), None);
+        assert_eq!(empty_reader.local_to_global(0, 0), None);
+    }
+
+    #[rstest]
+    #[case(0, 9)]
+    #[case(2, 7)]
+    #[case(3, 6)]
+    #[case(4, 5)]
+    #[case(6, 3)]
+    #[case(9, 0)]
+    fn remaining_bytes_from_any_item(#[case] position: u64, #[case] expected: u64) {
+        let mut reader = three_item_reader();
+        reader.seek(SeekFrom::Start(position)).unwrap();
+        assert_eq!(reader.remaining_bytes().unwrap(), expected);
+        assert_eq!(reader.get_global_offset(), position);
+        assert_eq!(read_to_end(&mut reader).len() as u64, expected);
+    }
+
+    #[rstest]
+    fn remaining_bytes_discovers_sizes(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 1]).unwrap();
+        assert_eq!(multiitem_reader.remaining_bytes().unwrap(), 4);
+        assert_eq!(read_to_end(&mut multiitem_reader), vec![2, 3, 4, 5]);
+        assert_eq!(multiitem_reader.remaining_bytes().unwrap(), 0);
+    }
+
+    #[rstest]
+    fn remaining_bytes_of_empty_reader(mut empty_reader: FakeReader) {
+        assert_eq!(empty_reader.remaining_bytes().unwrap(), 0);
+    }
+
+    #[rstest]
+    fn has_data_left_at_item_boundary(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 3]).unwrap();
+        assert!(multiitem_reader.has_data_left().unwrap());
+        assert_eq!(multiitem_reader.get_global_offset(), 3);
+        assert_eq!(read_to_end(&mut multiitem_reader), vec![4, 5]);
+    }
+
+    #[rstest]
+    fn has_data_left_skips_empty_items() {
+        let items = vec![
+            Cursor::new(vec![1]),
+            Cursor::new(vec![]),
+            Cursor::new(vec![]),
+        ];
+        let mut reader = Multireader::new(items).unwrap();
+        reader.read_exact(&mut [0; 1]).unwrap();
+        assert!(!reader.has_data_left().unwrap());
+
+        reader.get_item_mut(2).unwrap().get_mut().push(2);
+        assert!(reader.has_data_left().unwrap());
+        assert_eq!(read_to_end(&mut reader), vec![2]);
+    }
+
+    #[rstest]
+    fn has_data_left_at_end_of_growing_item(mut multiitem_reader: FakeReader) {
+        assert_eq!(read_to_end(&mut multiitem_reader).len(), 5);
+        assert!(!multiitem_reader.has_data_left().unwrap());
+
+        multiitem_reader.get_item_mut(1).unwrap().get_mut().push(6);
+        assert!(multiitem_reader.has_data_left().unwrap());
+        assert_eq!(read_to_end(&mut multiitem_reader), vec![6]);
+    }
+
+    #[rstest]
+    fn empty_reader_has_no_data_left(mut empty_reader: FakeReader) {
+        assert!(!empty_reader.has_data_left().unwrap());
+    }
+
+    #[rstest]
+    fn skip_to_next_item_from_middle_of_item(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 1]).unwrap();
+        assert_eq!(multiitem_reader.skip_to_next_item().unwrap(), Some(3));
+        assert_eq!(multiitem_reader.get_current_item_index(), 1);
+        assert_eq!(multiitem_reader.get_local_offset(), 0);
+        assert_eq!(read_to_end(&mut multiitem_reader), vec![4, 5]);
+    }
+
+    #[rstest]
+    fn skip_to_next_item_in_last_item_is_noop(mut multiitem_reader: FakeReader) {
+        multiitem_reader.seek(SeekFrom::Start(4)).unwrap();
+        assert_eq!(multiitem_reader.skip_to_next_item().unwrap(), None);
+        assert_eq!(multiitem_reader.get_global_offset(), 4);
+        assert_eq!(read_to_end(&mut multiitem_reader), vec![5]);
+    }
+
+    #[rstest]
+    fn skip_to_next_item_in_empty_reader(mut empty_reader: FakeReader) {
+        assert_eq!(empty_reader.skip_to_next_item().unwrap(), None);
+    }
+
+    #[rstest]
+    fn rewind_after_reading_lines(mut split_line_reader: FakeReader) {
+        let mut line = String::new();
+        split_line_reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "hello\n");
+        assert_eq!(split_line_reader.get_current_item_index(), 1);
+
+        split_line_reader.rewind().unwrap();
+        assert_eq!(split_line_reader.get_global_offset(), 0);
+        assert_eq!(split_line_reader.get_current_item_index(), 0);
+        assert_cached_index_is_valid(&split_line_reader);
+
+        line.clear();
+        split_line_reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "hello\n");
+    }
+
+    #[rstest]
+    fn rewind_seeks_only_first_item() {
+        let mut reader = counting_reader(3);
+        read_to_end(&mut reader);
+        let before = seek_counts(reader);
+
+        let mut reader = counting_reader(3);
+        read_to_end(&mut reader);
+        reader.rewind().unwrap();
+        let after = seek_counts(reader);
+        assert_eq!(after[0], before[0] + 1);
+        assert_eq!(after[1..], before[1..]);
+    }
+
+    #[rstest]
+    fn rewind_current_item_after_reading_lines(mut split_line_reader: FakeReader) {
+        let mut line = String::new();
+        split_line_reader.read_line(&mut line).unwrap();
+        line.clear();
+        split_line_reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "world");
+
+        assert_eq!(split_line_reader.rewind_current_item().unwrap(), 3);
+        assert_eq!(split_line_reader.get_current_item_index(), 1);
+        assert_eq!(split_line_reader.get_local_offset(), 0);
+        assert_cached_index_is_valid(&split_line_reader);
+
+        line.clear();
+        split_line_reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "lo\n");
+
+        split_line_reader.rewind().unwrap();
+        split_line_reader.read_exact(&mut [0; 2]).unwrap();
+        assert_eq!(split_line_reader.rewind_current_item().unwrap(), 0);
+        line.clear();
+        split_line_reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "hello\n");
+    }
+
+    #[rstest]
+    fn rewind_empty_reader(mut empty_reader: FakeReader) {
+        empty_reader.rewind().unwrap();
+        assert_eq!(empty_reader.rewind_current_item().unwrap(), 0);
+        assert_eq!(empty_reader.get_global_offset(), 0);
+    }
+
+    #[rstest]
+    fn debug_output_contains_position(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 4]).unwrap();
+        let output = format!("{multiitem_reader:?}");
+        assert!(output.starts_with("Multireader"));
+        assert!(output.contains("items: 2"));
+        assert!(output.contains("offsets: [3]"));
+        assert!(output.contains("global_offset: 4"));
+        assert!(output.contains("current_item_index: 1"));
+        assert!(output.contains("local_offset: 1"));
+    }
+
+    #[rstest]
+    fn summary_matches_position(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 4]).unwrap();
+        assert_eq!(
+            multiitem_reader.summary(),
+            MultireaderSummary {
+                items: 2,
+                offsets: vec![3],
+                global_offset: 4,
+                current_item_index: 1,
+                local_offset: 1,
+            }
+        );
+    }
+
+    #[rstest]
+    fn iteration_follows_construction_order(multiitem_reader: FakeReader) {
+        let contents: Vec<_> = multiitem_reader
+            .iter()
+            .map(|item| item.get_ref().clone())
+            .collect();
+        assert_eq!(contents, vec![vec![1, 2, 3], vec![4, 5]]);
+
+        let contents: Vec<_> = multiitem_reader
+            .into_iter()
+            .map(|item| item.into_inner())
+            .collect();
+        assert_eq!(contents, vec![vec![1, 2, 3], vec![4, 5]]);
+    }
+
+    #[rstest]
+    fn consuming_iteration_matches_into_inner() {
+        let read_partially = || {
+            let mut reader = split_line_reader();
+            reader.read_exact(&mut [0; 4]).unwrap();
+            reader
+        };
+        let from_iter: Vec<_> = read_partially().into_iter().collect();
+        let from_inner: Vec<_> = read_partially().into_inner().into_iter().collect();
+        assert_eq!(from_iter, from_inner);
+    }
+
+    #[rstest]
+    fn iter_mut_desynchronizes_items(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 1]).unwrap();
+        for item in multiitem_reader.iter_mut() {
+            item.set_position(0);
+        }
+        assert_eq!(read_to_end(&mut multiitem_reader), vec![2, 3, 4, 5]);
+    }
+
+    fn collect_lines(reader: &mut FakeReader) -> Vec<(u64, String)> {
+        reader
+            .lines_with_offsets()
+            .collect::<std::io::Result<_>>()
+            .unwrap()
+    }
+
+    #[rstest]
+    #[case(&["ab\n", "cd\n"], &[(0, "ab"), (3, "cd")])]
+    #[case(&["ab", "\ncd\n"], &[(0, "ab"), (3, "cd")])]
+    #[case(&["ab\nc", "d"], &[(0, "ab"), (3, "cd")])]
+    #[case(&["ab\r", "\ncd"], &[(0, "ab"), (4, "cd")])]
+    #[case(&["\n", "\n"], &[(0, ""), (1, "")])]
+    #[case(&["", "ab"], &[(0, "ab")])]
+    fn lines_with_offsets_across_items(#[case] items: &[&str], #[case] expected: &[(u64, &str)]) {
+        let items = items
+            .iter()
+            .map(|item| Cursor::new(item.as_bytes().to_vec()))
+            .collect();
+        let mut reader = Multireader::new(items).unwrap();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|&(offset, line)| (offset, line.to_string()))
+            .collect();
+        assert_eq!(collect_lines(&mut reader), expected);
+    }
+
+    #[rstest]
+    fn lines_with_offsets_continue_from_position(mut split_line_reader: FakeReader) {
+        split_line_reader.seek(SeekFrom::Start(1)).unwrap();
+        assert_eq!(
+            collect_lines(&mut split_line_reader),
+            vec![(1, "ello".to_string()), (6, "world".to_string())]
+        );
+        assert_eq!(split_line_reader.get_global_offset(), 11);
+    }
+
+    fn collect_records(reader: &mut FakeReader, delim: u8) -> Vec<(u64, Vec<u8>, u64)> {
+        reader
+            .records(delim)
+            .map(|record| record.map(|record| (record.offset, record.data, record.len)))
+            .collect::<std::io::Result<_>>()
+            .unwrap()
+    }
+
+    #[rstest]
+    #[case(&["ab\0", "cd\0"], &[(0, "ab", 3), (3, "cd", 3)])]
+    #[case(&["ab", "\0cd\0"], &[(0, "ab", 3), (3, "cd", 3)])]
+    #[case(&["ab\0c", "d"], &[(0, "ab", 3), (3, "cd", 2)])]
+    #[case(&["\0", "\0\0"], &[(0, "", 1), (1, "", 1), (2, "", 1)])]
+    #[case(&["", "ab"], &[(0, "ab", 2)])]
+    fn records_across_items(#[case] items: &[&str], #[case] expected: &[(u64, &str, u64)]) {
+        let items = items
+            .iter()
+            .map(|item| Cursor::new(item.as_bytes().to_vec()))
+            .collect();
+        let mut reader = Multireader::new(items).unwrap();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|&(offset, data, len)| (offset, data.as_bytes().to_vec(), len))
+            .collect();
+        assert_eq!(collect_records(&mut reader, b'\0'), expected);
+    }
+
+    #[rstest]
+    fn records_with_newline_delimiter_match_lines(mut split_line_reader: FakeReader) {
+        assert_eq!(
+            collect_records(&mut split_line_reader, b'\n'),
+            vec![(0, b"hello".to_vec(), 6), (6, b"world".to_vec(), 5)]
+        );
+    }
+
+    #[rstest]
+    fn read_at_does_not_move_cursor(mut split_line_reader: FakeReader) {
+        split_line_reader.read_exact(&mut [0; 4]).unwrap();
+        let mut buf = [0; 2];
+        assert_eq!(split_line_reader.read_at(0, &mut buf).unwrap(), 2);
+        assert_eq!(&buf, b"he");
+        assert_eq!(split_line_reader.get_global_offset(), 4);
+        assert_eq!(split_line_reader.get_current_item_index(), 1);
+
+        let mut line = String::new();
+        split_line_reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "o\n");
+        assert_eq!(read_to_end(&mut split_line_reader), b"world");
+    }
+
+    #[rstest]
+    fn read_at_spans_items(mut split_line_reader: FakeReader) {
+        let mut buf = [0; 4];
+        split_line_reader.read_exact_at(1, &mut buf).unwrap();
+        assert_eq!(&buf, b"ello");
+        assert_eq!(split_line_reader.get_global_offset(), 0);
+        assert_eq!(read_to_end(&mut split_line_reader), b"hello\nworld");
+    }
+
+    #[rstest]
+    #[case(9, 2)]
+    #[case(11, 0)]
+    #[case(100, 0)]
+    fn read_at_near_end(
+        mut split_line_reader: FakeReader,
+        #[case] offset: u64,
+        #[case] expected: usize,
+    ) {
+        let mut buf = [0; 4];
+        assert_eq!(
+            split_line_reader.read_at(offset, &mut buf).unwrap(),
+            expected
+        );
+        let err = split_line_reader
+            .read_exact_at(offset, &mut buf)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[rstest]
+    fn read_at_in_empty_reader(mut empty_reader: FakeReader) {
+        assert_eq!(empty_reader.read_at(0, &mut [0; 4]).unwrap(), 0);
+    }
+
+    #[rstest]
+    fn read_within_item_stops_at_item_end(mut multiitem_reader: FakeReader) {
+        let mut buf = [0; 8];
+        assert_eq!(multiitem_reader.read_within_item(&mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], &[1, 2, 3]);
+        assert_eq!(multiitem_reader.read_within_item(&mut buf).unwrap(), 0);
+        assert_eq!(multiitem_reader.remaining_in_current_item().unwrap(), 0);
+        assert_eq!(multiitem_reader.read_within_item(&mut buf).unwrap(), 0);
+        assert_eq!(multiitem_reader.get_current_item_index(), 0);
+
+        assert_eq!(multiitem_reader.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], &[4, 5]);
+    }
+
+    #[rstest]
+    fn read_within_item_then_skip_to_next_item(mut multiitem_reader: FakeReader) {
+        multiitem_reader.discover_all_sizes().unwrap();
+        let mut buf = [0; 8];
+        assert_eq!(multiitem_reader.read_within_item(&mut buf).unwrap(), 3);
+        assert_eq!(multiitem_reader.read_within_item(&mut buf).unwrap(), 0);
+        assert_eq!(multiitem_reader.skip_to_next_item().unwrap(), Some(3));
+        assert_eq!(multiitem_reader.read_within_item(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], &[4, 5]);
+    }
+
+    #[rstest]
+    fn read_line_within_item_stops_at_item_end(mut split_line_reader: FakeReader) {
+        let mut line = String::new();
+        assert_eq!(
+            split_line_reader.read_line_within_item(&mut line).unwrap(),
+            3
+        );
+        assert_eq!(line, "hel");
+        assert_eq!(
+            split_line_reader.read_line_within_item(&mut line).unwrap(),
+            0
+        );
+        assert_eq!(line, "hel");
+
+        split_line_reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "hello\n");
+        line.clear();
+        assert_eq!(
+            split_line_reader.read_line_within_item(&mut line).unwrap(),
+            5
+        );
+        assert_eq!(line, "world");
+    }
+
+    #[rstest]
+    #[case(0, 3)]
+    #[case(2, 1)]
+    #[case(3, 2)]
+    #[case(5, 0)]
+    fn remaining_in_current_item_at_position(
+        mut multiitem_reader: FakeReader,
+        #[case] position: u64,
+        #[case] expected: u64,
+    ) {
+        multiitem_reader.seek(SeekFrom::Start(position)).unwrap();
+        assert_eq!(
+            multiitem_reader.remaining_in_current_item().unwrap(),
+            expected
+        );
+        assert_eq!(multiitem_reader.get_global_offset(), position);
+    }
+
+    #[rstest]
+    fn remaining_in_current_item_shrinks_by_bytes_read(mut multiitem_reader: FakeReader) {
+        let mut observed = vec![];
+        loop {
+            let remaining = multiitem_reader.remaining_in_current_item().unwrap();
+            let item_size = multiitem_reader
+                .get_item_size(multiitem_reader.get_current_item_index())
+                .unwrap();
+            assert_eq!(remaining + multiitem_reader.get_local_offset(), item_size);
+            observed.push((multiitem_reader.get_current_item_index(), remaining));
+            if multiitem_reader.read(&mut [0; 1]).unwrap() == 0 {
+                break;
+            }
+        }
+        // boundary is crossed right after the last byte of first item is read
+        assert_eq!(
+            observed,
+            vec![(0, 3), (0, 2), (0, 1), (1, 2), (1, 1), (1, 0)]
+        );
+    }
+
+    #[rstest]
+    fn peek_across_boundary_then_read(mut split_line_reader: FakeReader) {
+        split_line_reader.read_exact(&mut [0; 1]).unwrap();
+        let summary = split_line_reader.summary();
+        let mut peeked = [0; 4];
+        assert_eq!(split_line_reader.peek(&mut peeked).unwrap(), 4);
+        assert_eq!(&peeked, b"ello");
+        assert_eq!(split_line_reader.get_global_offset(), summary.global_offset);
+        assert_eq!(
+            split_line_reader.get_current_item_index(),
+            summary.current_item_index
+        );
+        assert_eq!(split_line_reader.get_local_offset(), summary.local_offset);
+
+        let mut read = [0; 4];
+        split_line_reader.read_exact(&mut read).unwrap();
+        assert_eq!(read, peeked);
+        assert_eq!(split_line_reader.get_global_offset(), 5);
+    }
+
+    #[rstest]
+    fn peek_near_end(mut split_line_reader: FakeReader) {
+        split_line_reader.seek(SeekFrom::Start(9)).unwrap();
+        let mut peeked = [0; 4];
+        assert_eq!(split_line_reader.peek(&mut peeked).unwrap(), 2);
+        assert_eq!(&peeked[..2], b"ld");
+        assert_eq!(read_to_end(&mut split_line_reader), b"ld");
+        assert_eq!(split_line_reader.peek(&mut peeked).unwrap(), 0);
+    }
+
+    #[rstest]
+    fn peek_through_bufreader_items() {
+        let items = vec![
+            BufReader::with_capacity(2, Cursor::new(b"hel".to_vec())),
+            BufReader::with_capacity(2, Cursor::new(b"lo\nworld".to_vec())),
+        ];
+        let mut reader = Multireader::new(items).unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let mut peeked = [0; 5];
+        reader.peek(&mut peeked).unwrap();
+        assert_eq!(&peeked, b"world");
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "world");
+    }
+
+    #[rstest]
+    #[case(0, b"hello\nworld")]
+    #[case(2, b"llo\nworld")]
+    #[case(3, b"lo\nworld")]
+    #[case(7, b"orld")]
+    #[case(11, b"")]
+    fn copy_remaining_from_position(
+        mut split_line_reader: FakeReader,
+        #[case] position: u64,
+        #[case] expected: &[u8],
+    ) {
+        split_line_reader.seek(SeekFrom::Start(position)).unwrap();
+        let mut sink = vec![];
+        let copied = split_line_reader.copy_remaining_to(&mut sink).unwrap();
+        assert_eq!(copied, expected.len() as u64);
+        assert_eq!(sink, expected);
+        assert_eq!(split_line_reader.get_global_offset(), 11);
+        assert_eq!(split_line_reader.get_current_item_index(), 1);
+        assert_eq!(read_to_end(&mut split_line_reader), b"");
+    }
+
+    #[rstest]
+    fn copy_remaining_after_partial_read(mut split_line_reader: FakeReader) {
+        let mut line = String::new();
+        split_line_reader.read_line(&mut line).unwrap();
+        let mut sink = Cursor::new(vec![]);
+        assert_eq!(split_line_reader.copy_remaining_to(&mut sink).unwrap(), 5);
+        assert_eq!(sink.into_inner(), b"world");
+    }
+
+    #[rstest]
+    fn copy_remaining_from_empty_reader(mut empty_reader: FakeReader) {
+        let mut sink = vec![];
+        assert_eq!(empty_reader.copy_remaining_to(&mut sink).unwrap(), 0);
+    }
+
+    #[rstest]
+    fn refresh_sizes_after_middle_item_grew() {
+        let mut reader = three_item_reader();
+        reader.seek(SeekFrom::Start(6)).unwrap();
+        reader.get_item_mut(1).unwrap().get_mut().extend([10, 11]);
+
+        let changed = reader.refresh_sizes().unwrap();
+        assert_eq!(changed.items, vec![(1, 2)]);
+        assert_eq!(reader.get_current_item_index(), 2);
+        assert_eq!(reader.get_local_offset(), 1);
+        assert_eq!(reader.get_global_offset(), 8);
+        assert_eq!(read_to_end(&mut reader), vec![7, 8, 9]);
+
+        reader.rewind().unwrap();
+        assert_eq!(
+            read_to_end(&mut reader),
+            vec![1, 2, 3, 4, 5, 10, 11, 6, 7, 8, 9]
+        );
+    }
+
+    #[rstest]
+    fn refresh_sizes_keeps_position_in_grown_item() {
+        let mut reader = three_item_reader();
+        reader.seek(SeekFrom::Start(4)).unwrap();
+        reader.get_item_mut(0).unwrap().get_mut().push(0);
+        reader.get_item_mut(1).unwrap().get_mut().push(10);
+
+        let changed = reader.refresh_sizes().unwrap();
+        assert_eq!(changed.items, vec![(0, 1), (1, 1)]);
+        assert_eq!(reader.get_global_offset(), 5);
+        assert_cached_index_is_valid(&reader);
+        assert_eq!(read_to_end(&mut reader), vec![5, 10, 6, 7, 8, 9]);
+    }
+
+    #[rstest]
+    fn refresh_sizes_clamps_position_in_shrunk_item() {
+        let mut reader = three_item_reader();
+        reader.seek(SeekFrom::Start(4)).unwrap();
+        reader.get_item_mut(1).unwrap().get_mut().truncate(1);
+
+        let changed = reader.refresh_sizes().unwrap();
+        assert_eq!(changed.items, vec![(1, -1)]);
+        assert_eq!(reader.get_global_offset(), 4);
+        assert_eq!(read_to_end(&mut reader), vec![6, 7, 8, 9]);
+    }
+
+    #[rstest]
+    fn refresh_sizes_without_changes(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 4]).unwrap();
+        let changed = multiitem_reader.refresh_sizes().unwrap();
+        assert!(changed.is_empty());
+        assert_eq!(multiitem_reader.get_global_offset(), 4);
+        assert_eq!(read_to_end(&mut multiitem_reader), vec![5]);
+    }
+
+    #[rstest]
+    fn refresh_sizes_of_empty_reader(mut empty_reader: FakeReader) {
+        assert!(empty_reader.refresh_sizes().unwrap().is_empty());
+    }
+
+    fn reader_with_empty_items(items: &[&str]) -> FakeReader {
+        let items = items
+            .iter()
+            .map(|item| Cursor::new(item.as_bytes().to_vec()))
+            .collect();
+        Multireader::new(items).unwrap()
+    }
+
+    const EMPTY_ITEM_LAYOUTS: &[&[&str]] = &[
+        &["", "ab\n", "cd"],
+        &["ab\n", "", "cd"],
+        &["ab\n", "cd", ""],
+        &["", "", "ab\n", "", "", "cd", "", ""],
+        &["", ""],
+    ];
+
+    fn joined_content(items: &[&str]) -> Vec<u8> {
+        items.concat().into_bytes()
+    }
+
+    #[rstest]
+    fn empty_items_are_skipped_while_reading() {
+        for &items in EMPTY_ITEM_LAYOUTS {
+            let expected = joined_content(items);
+            assert_eq!(read_to_end(reader_with_empty_items(items)), expected);
+
+            let mut reader = reader_with_empty_items(items);
+            let mut content = vec![];
+            let mut byte = [0];
+            while reader.read(&mut byte).unwrap() > 0 {
+                content.push(byte[0]);
+            }
+            assert_eq!(content, expected);
+
+            let mut reader = reader_with_empty_items(items);
+            let lines: Vec<_> = (&mut reader).lines().map(Result::unwrap).collect();
+            let expected_lines: Vec<_> = String::from_utf8(expected)
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect();
+            assert_eq!(lines, expected_lines);
+        }
+    }
+
+    #[rstest]
+    fn seeking_with_empty_items() {
+        for &items in EMPTY_ITEM_LAYOUTS {
+            let expected = joined_content(items);
+            for position in 0..=expected.len() {
+                let mut reader = reader_with_empty_items(items);
+                reader.seek(SeekFrom::Start(position as u64)).unwrap();
+                assert_cached_index_is_valid(&reader);
+                assert_eq!(read_to_end(&mut reader), &expected[position..]);
+                assert_eq!(reader.get_total_size().unwrap(), expected.len() as u64);
+            }
+        }
+    }
+
+    #[rstest]
+    fn seek_to_empty_item_start_points_at_next_non_empty_item() {
+        let items = ["", "", "ab\n", "", "", "cd", "", ""];
+        for (item_index, expected_offset, expected_index) in [
+            (0, 0, 2),
+            (1, 0, 2),
+            (2, 0, 2),
+            (3, 3, 5),
+            (4, 3, 5),
+            (5, 3, 5),
+            (6, 5, 7),
+            (7, 5, 7),
+        ] {
+            let mut reader = reader_with_empty_items(&items);
+            assert_eq!(
+                reader.seek_to_item_start(item_index).unwrap(),
+                expected_offset
+            );
+            assert_eq!(reader.get_current_item_index(), expected_index);
+            assert_eq!(reader.get_local_offset(), 0);
+            assert_cached_index_is_valid(&reader);
+            assert_ne!(reader.get_current_item_size(), Some(0));
+            assert_eq!(
+                read_to_end(&mut reader),
+                &joined_content(&items)[expected_offset as usize..]
+            );
+        }
+    }
+
+    #[rstest]
+    fn offset_math_with_empty_items() {
+        let mut reader = reader_with_empty_items(&["ab", "", "", "cd"]);
+        reader.discover_all_sizes().unwrap();
+        assert_eq!(reader.item_sizes(), vec![Some(2), Some(0), Some(0), None]);
+        assert_eq!(reader.item_start_offset(1), Some(2));
+        assert_eq!(reader.item_end_offset(2), Some(2));
+        assert_eq!(reader.global_to_local(2), Some((3, 0)));
+        assert_eq!(reader.local_to_global(1, 0), Some(2));
+        assert_eq!(reader.local_to_global(1, 1), None);
+
+        reader.read_exact(&mut [0; 2]).unwrap();
+        assert_eq!(reader.get_current_item_index(), 3);
+        assert_eq!(reader.get_local_offset(), 0);
+    }
+
+    #[rstest]
+    fn seek_to_item_start_rejects_out_of_range_index(mut multiitem_reader: FakeReader) {
+        let err = multiitem_reader.seek_to_item_start(2).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[rstest]
+    fn cloned_reader_has_independent_position(mut split_line_reader: FakeReader) {
+        split_line_reader.read_exact(&mut [0; 2]).unwrap();
+        let mut clone = split_line_reader.try_clone().unwrap();
+        assert_eq!(clone.get_global_offset(), 2);
+
+        let mut line = String::new();
+        clone.read_line(&mut line).unwrap();
+        assert_eq!(line, "llo\n");
+        split_line_reader.seek(SeekFrom::Start(7)).unwrap();
+        line.clear();
+        clone.read_line(&mut line).unwrap();
+        assert_eq!(line, "world");
+        assert_eq!(read_to_end(&mut split_line_reader), b"orld");
+    }
+
+    #[cfg(any(target_os = "linux", windows))]
+    #[rstest]
+    fn cloned_file_reader_has_independent_position() {
+        let items = [b"hel".as_slice(), b"lo\nworld"]
+            .into_iter()
+            .map(|content| {
+                let mut file = tempfile::tempfile().unwrap();
+                std::io::Write::write_all(&mut file, content).unwrap();
+                BufReader::with_capacity(2, file)
+            })
+            .collect();
+        let mut reader = Multireader::new(items).unwrap();
+        reader.read_exact(&mut [0; 4]).unwrap();
+        let mut clone = reader.try_clone().unwrap();
+
+        let mut original_buf = [0; 3];
+        let mut cloned_buf = [0; 3];
+        reader.read_exact(&mut original_buf[..1]).unwrap();
+        clone.rewind().unwrap();
+        clone.read_exact(&mut cloned_buf).unwrap();
+        reader.read_exact(&mut original_buf[1..]).unwrap();
+        assert_eq!(&original_buf, b"o\nw");
+        assert_eq!(&cloned_buf, b"hel");
+        assert_eq!(read_to_end(&mut clone), b"lo\nworld");
+        assert_eq!(read_to_end(&mut reader), b"orld");
+    }
+
+    #[rstest]
+    fn concat_reads_both_readers(multiitem_reader: FakeReader, singleitem_reader: FakeReader) {
+        let mut reader = Multireader::concat(multiitem_reader, singleitem_reader).unwrap();
+        assert_eq!(reader.len(), 3);
+        assert_eq!(read_to_end(&mut reader), vec![1, 2, 3, 4, 5, 1, 2, 3]);
+        assert_eq!(reader.get_total_size().unwrap(), 8);
+
+        reader.seek(SeekFrom::Start(5)).unwrap();
+        assert_eq!(reader.get_current_item_index(), 2);
+        assert_eq!(read_to_end(&mut reader), vec![1, 2, 3]);
+    }
+
+    #[rstest]
+    fn concat_preserves_position_of_first(
+        mut multiitem_reader: FakeReader,
+        mut singleitem_reader: FakeReader,
+    ) {
+        singleitem_reader.read_exact(&mut [0; 2]).unwrap();
+        multiitem_reader.read_exact(&mut [0; 1]).unwrap();
+        let mut reader = Multireader::concat(singleitem_reader, multiitem_reader).unwrap();
+        assert_eq!(reader.get_global_offset(), 2);
+        assert_cached_index_is_valid(&reader);
+        assert_eq!(read_to_end(&mut reader), vec![3, 1, 2, 3, 4, 5]);
+    }
+
+    #[rstest]
+    fn concat_at_end_of_first_moves_into_second(
+        mut singleitem_reader: FakeReader,
+        multiitem_reader: FakeReader,
+    ) {
+        read_to_end(&mut singleitem_reader);
+        let mut reader = Multireader::concat(singleitem_reader, multiitem_reader).unwrap();
+        assert_eq!(reader.get_current_item_index(), 1);
+        assert_eq!(reader.get_local_offset(), 0);
+        assert_eq!(read_to_end(&mut reader), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[rstest]
+    fn concat_with_empty_reader(multiitem_reader: FakeReader) {
+        let reader = Multireader::concat(empty_reader(), multiitem_reader).unwrap();
+        let mut reader = Multireader::concat(reader, empty_reader()).unwrap();
+        assert_eq!(reader.len(), 2);
+        assert_eq!(read_to_end(&mut reader), vec![1, 2, 3, 4, 5]);
+    }
+
+    /// Adapter that counts bytes read through it.
+    struct CountingAdapter<R> {
+        inner: R,
+        bytes_read: usize,
+    }
+
+    impl<R: Read> Read for CountingAdapter<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let size_read = self.inner.read(buf)?;
+            self.bytes_read += size_read;
+            Ok(size_read)
+        }
+    }
+
+    impl<R: Seek> Seek for CountingAdapter<R> {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[rstest]
+    fn map_items_preserves_position(mut split_line_reader: FakeReader) {
+        split_line_reader.read_exact(&mut [0; 4]).unwrap();
+        let mut reader = split_line_reader
+            .map_items(|inner| CountingAdapter {
+                inner,
+                bytes_read: 0,
+            })
+            .unwrap();
+        assert_eq!(reader.get_global_offset(), 4);
+        assert_eq!(reader.get_current_item_index(), 1);
+        assert_eq!(reader.get_local_offset(), 1);
+
+        assert_eq!(read_to_end(&mut reader), b"o\nworld");
+        reader.rewind().unwrap();
+        assert_eq!(read_to_end(&mut reader), b"hello\nworld");
+        let bytes_read: Vec<_> = reader.iter().map(|item| item.bytes_read).collect();
+        assert_eq!(bytes_read, vec![3, 15]);
+    }
+
+    #[rstest]
+    fn map_items_repositions_current_item(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 1]).unwrap();
+        // wrapper is handed item with arbitrary position
+        let mut reader = multiitem_reader
+            .map_items(|mut item| {
+                item.set_position(0);
+                item
+            })
+            .unwrap();
+        assert_eq!(read_to_end(&mut reader), vec![2, 3, 4, 5]);
+    }
 
-                Ok(self.global_offset)
-            }
-            io::SeekFrom::End(offset) => {
-                let total_size = self.get_total_size()?;
-                let real_offset = total_size as i64 + offset;
-                if real_offset < 0 {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::InvalidInput,
-                        "negative real offset after seek",
-                    ));
-                }
-                self.seek(io::SeekFrom::Start(real_offset as u64))
-            }
-            io::SeekFrom::Current(offset) => {
-                let new_position = self.global_offset as i64 + offset;
-                if new_position < 0 {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::InvalidInput,
-                        "negative real offset after seek",
-                    ));
-                }
-                self.seek(io::SeekFrom::Start(new_position as u64))
+    #[rstest]
+    fn map_items_of_empty_reader(empty_reader: FakeReader) {
+        let reader = empty_reader.map_items(BufReader::new).unwrap();
+        assert!(reader.is_empty());
+    }
+
+    fn item_contents(removed: Vec<Cursor<Vec<u8>>>) -> Vec<Vec<u8>> {
+        removed.into_iter().map(Cursor::into_inner).collect()
+    }
+
+    #[rstest]
+    fn retain_from_zero_keeps_everything() {
+        let mut reader = three_item_reader();
+        reader.read_exact(&mut [0; 4]).unwrap();
+        assert!(reader.retain_from(0).is_empty());
+        assert_eq!(reader.len(), 3);
+        assert_eq!(reader.get_global_offset(), 4);
+        assert_eq!(read_to_end(&mut reader), vec![5, 6, 7, 8, 9]);
+    }
+
+    #[rstest]
+    fn retain_from_before_current_item() {
+        let mut reader = three_item_reader();
+        reader.read_exact(&mut [0; 4]).unwrap();
+        assert_eq!(item_contents(reader.retain_from(1)), vec![vec![1, 2, 3]]);
+        assert_eq!(reader.len(), 2);
+        assert_eq!(reader.get_global_offset(), 1);
+        assert_eq!(reader.get_current_item_index(), 0);
+        assert_eq!(reader.get_local_offset(), 1);
+        assert_eq!(reader.item_sizes(), vec![Some(2), None]);
+        assert_cached_index_is_valid(&reader);
+        assert_eq!(read_to_end(&mut reader), vec![5, 6, 7, 8, 9]);
+    }
+
+    #[rstest]
+    fn retain_from_clamps_position_inside_removed_items() {
+        let mut reader = three_item_reader();
+        reader.read_exact(&mut [0; 1]).unwrap();
+        assert_eq!(
+            item_contents(reader.retain_from(2)),
+            vec![vec![1, 2, 3], vec![4, 5]]
+        );
+        assert_eq!(reader.len(), 1);
+        assert_eq!(reader.get_global_offset(), 0);
+        assert_eq!(reader.get_current_item_index(), 0);
+        assert_eq!(read_to_end(&mut reader), vec![6, 7, 8, 9]);
+    }
+
+    #[rstest]
+    fn retain_from_with_undiscovered_sizes(mut split_line_reader: FakeReader) {
+        assert_eq!(
+            item_contents(split_line_reader.retain_from(1)),
+            vec![b"hel".to_vec()]
+        );
+        assert_eq!(split_line_reader.get_total_size().unwrap(), 8);
+        assert_eq!(read_to_end(&mut split_line_reader), b"lo\nworld");
+    }
+
+    #[rstest]
+    fn retain_from_past_end_removes_everything(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 4]).unwrap();
+        assert_eq!(multiitem_reader.retain_from(5).len(), 2);
+        assert!(multiitem_reader.is_empty());
+        assert_eq!(multiitem_reader.get_global_offset(), 0);
+        assert_eq!(read_to_end(&mut multiitem_reader), b"");
+    }
+
+    fn concat_halves<R: BufRead + Seek>(
+        (head, tail): (WindowedMultireader<R>, WindowedMultireader<R>),
+    ) -> (Vec<u8>, Vec<u8>) {
+        (read_to_end(head), read_to_end(tail))
+    }
+
+    #[rstest]
+    fn split_at_any_offset() {
+        let content = b"hello\nworld";
+        for offset in 0..=content.len() + 1 {
+            for reader in [
+                split_line_reader(),
+                reader_with_empty_items(&["hel", "", "lo\nwo", "rld"]),
+            ] {
+                let (head, tail) = concat_halves(reader.split_at(offset as u64).unwrap());
+                let expected_split = offset.min(content.len());
+                assert_eq!(head, &content[..expected_split]);
+                assert_eq!(tail, &content[expected_split..]);
             }
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::io::{BufRead, Cursor, Read, Seek};
+    #[rstest]
+    fn split_halves_support_seeking() {
+        let (mut head, mut tail) = split_line_reader().split_at(4).unwrap();
+        assert_eq!(head.get_total_size().unwrap(), 4);
+        assert_eq!(tail.get_total_size().unwrap(), 7);
+        tail.seek(SeekFrom::Start(3)).unwrap();
+        assert_eq!(read_to_end(&mut tail), b"orld");
+        head.seek(SeekFrom::End(-2)).unwrap();
+        assert_eq!(read_to_end(&mut head), b"ll");
+    }
 
-    use rstest::{fixture, rstest};
+    #[rstest]
+    fn split_file_backed_reader() {
+        let items = [b"hel".as_slice(), b"lo\nworld"]
+            .into_iter()
+            .map(|content| {
+                let mut file = tempfile::tempfile().unwrap();
+                std::io::Write::write_all(&mut file, content).unwrap();
+                BufReader::new(file)
+            })
+            .collect();
+        let reader = Multireader::new(items).unwrap();
+        let (head, tail) = concat_halves(reader.split_at(5).unwrap());
+        assert_eq!(head, b"hello");
+        assert_eq!(tail, b"\nworld");
+    }
 
-    use super::Multireader;
+    #[rstest]
+    fn split_empty_reader(empty_reader: FakeReader) {
+        let (head, tail) = empty_reader.split_at(0).unwrap();
+        assert!(head.is_empty() && tail.is_empty());
+    }
 
-    type FakeReader = Multireader<Cursor<Vec<u8>>>;
+    #[rstest]
+    fn take_scoped_across_boundary(mut split_line_reader: FakeReader) {
+        split_line_reader.read_exact(&mut [0; 2]).unwrap();
+        {
+            let mut take = split_line_reader.take_scoped(3);
+            assert_eq!(read_to_end(&mut take), b"llo");
+            assert_eq!(take.limit(), 0);
+        }
+        assert_eq!(split_line_reader.get_global_offset(), 5);
+        assert_eq!(split_line_reader.get_current_item_index(), 1);
+        assert_eq!(read_to_end(&mut split_line_reader), b"\nworld");
+    }
 
-    #[fixture]
-    fn singleitem_reader() -> FakeReader {
-        Multireader::new(vec![Cursor::new(vec![1, 2, 3])]).unwrap()
+    #[rstest]
+    fn take_scoped_lines(mut split_line_reader: FakeReader) {
+        let lines: Vec<_> = split_line_reader
+            .take_scoped(8)
+            .lines()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, vec!["hello", "wo"]);
+        assert_eq!(split_line_reader.get_global_offset(), 8);
+        let mut line = String::new();
+        split_line_reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "rld");
     }
 
-    #[fixture]
-    fn multiitem_reader() -> FakeReader {
-        Multireader::new(vec![Cursor::new(vec![1, 2, 3]), Cursor::new(vec![4, 5])]).unwrap()
+    #[rstest]
+    fn take_scoped_past_end(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_exact(&mut [0; 1]).unwrap();
+        let mut take = multiitem_reader.take_scoped(100);
+        assert_eq!(read_to_end(&mut take), vec![2, 3, 4, 5]);
+        assert_eq!(take.limit(), 96);
+        assert_eq!(multiitem_reader.get_global_offset(), 5);
+    }
+
+    fn collect_chunks(reader: &mut FakeReader, chunk_size: usize) -> Vec<(u64, Vec<u8>, bool)> {
+        reader
+            .chunks(chunk_size)
+            .map(|chunk| chunk.map(|chunk| (chunk.offset, chunk.data, chunk.is_last)))
+            .collect::<std::io::Result<_>>()
+            .unwrap()
     }
 
     #[rstest]
-    fn reader_should_read_from_one_item(mut singleitem_reader: FakeReader) {
-        let mut buf = vec![];
-        singleitem_reader.read_to_end(&mut buf).unwrap();
-        assert_eq!(buf, vec![1, 2, 3])
+    fn chunks_dividing_items_evenly() {
+        let mut reader = reader_with_empty_items(&["ab", "cd", "ef"]);
+        assert_eq!(
+            collect_chunks(&mut reader, 2),
+            vec![
+                (0, b"ab".to_vec(), false),
+                (2, b"cd".to_vec(), false),
+                (4, b"ef".to_vec(), true)
+            ]
+        );
     }
 
     #[rstest]
-    fn reader_should_seek_into_inner_item(mut singleitem_reader: FakeReader) {
-        singleitem_reader.seek(std::io::SeekFrom::Start(1)).unwrap();
-        assert_eq!(singleitem_reader.get_global_offset(), 1);
-        assert_eq!(singleitem_reader.get_local_offset(), 1);
+    #[case(3, &[(0, "hel", false), (3, "lo\n", false), (6, "wor", false), (9, "ld", true)])]
+    #[case(4, &[(0, "hell", false), (4, "o\nwo", false), (8, "rld", true)])]
+    #[case(11, &[(0, "hello\nworld", true)])]
+    #[case(100, &[(0, "hello\nworld", true)])]
+    fn chunks_across_items(
+        mut split_line_reader: FakeReader,
+        #[case] chunk_size: usize,
+        #[case] expected: &[(u64, &str, bool)],
+    ) {
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|&(offset, data, is_last)| (offset, data.as_bytes().to_vec(), is_last))
+            .collect();
+        assert_eq!(collect_chunks(&mut split_line_reader, chunk_size), expected);
+        assert_eq!(split_line_reader.get_global_offset(), 11);
+    }
 
-        let mut buf = vec![255];
-        singleitem_reader.read_exact(&mut buf).unwrap();
-        assert_eq!(buf, vec![2]);
+    #[rstest]
+    fn chunks_of_exhausted_reader(mut empty_reader: FakeReader, mut multiitem_reader: FakeReader) {
+        assert!(collect_chunks(&mut empty_reader, 2).is_empty());
+        read_to_end(&mut multiitem_reader);
+        assert!(collect_chunks(&mut multiitem_reader, 2).is_empty());
     }
 
     #[rstest]
-    fn reader_should_seek_into_first_item(mut multiitem_reader: FakeReader) {
-        multiitem_reader.seek(std::io::SeekFrom::Start(1)).unwrap();
-        assert_eq!(multiitem_reader.get_global_offset(), 1);
-        assert_eq!(multiitem_reader.get_local_offset(), 1);
-        assert_eq!(multiitem_reader.get_current_item_index(), 0);
+    #[case(3, Err(0))]
+    #[case(5, Err(0))]
+    #[case(6, Ok("hello\n"))]
+    #[case(7, Ok("hello\n"))]
+    #[case(100, Ok("hello\n"))]
+    fn read_until_limited_across_boundary(
+        mut split_line_reader: FakeReader,
+        #[case] max: usize,
+        #[case] expected: Result<&str, u64>,
+    ) {
+        let mut buf = vec![];
+        let result = split_line_reader.read_until_limited(b'\n', &mut buf, max);
+        match expected {
+            Ok(line) => {
+                assert_eq!(result.unwrap(), line.len());
+                assert_eq!(buf, line.as_bytes());
+            }
+            Err(offset) => {
+                assert!(matches!(
+                    result,
+                    Err(ReadLimitedError::TooLong { offset: o, max: m }) if o == offset && m == max
+                ));
+                assert!(buf.is_empty());
+            }
+        }
+        // long line is skipped entirely
+        assert_eq!(split_line_reader.get_global_offset(), 6);
+    }
 
-        let mut buf = vec![255];
-        multiitem_reader.read_exact(&mut buf).unwrap();
-        assert_eq!(buf, vec![2]);
+    #[rstest]
+    #[case(4, false)]
+    #[case(5, true)]
+    #[case(6, true)]
+    fn read_line_limited_at_last_line(
+        mut split_line_reader: FakeReader,
+        #[case] max: usize,
+        #[case] fits: bool,
+    ) {
+        split_line_reader.seek(SeekFrom::Start(6)).unwrap();
+        let mut line = String::new();
+        let result = split_line_reader.read_line_limited(&mut line, max);
+        if fits {
+            assert_eq!(result.unwrap(), 5);
+            assert_eq!(line, "world");
+        } else {
+            assert!(matches!(
+                result,
+                Err(ReadLimitedError::TooLong { offset: 6, .. })
+            ));
+            assert_eq!(line, "");
+            assert_eq!(split_line_reader.get_global_offset(), 11);
+        }
     }
 
     #[rstest]
-    fn reader_should_seek_into_second_item(mut multiitem_reader: FakeReader) {
-        multiitem_reader.seek(std::io::SeekFrom::Start(4)).unwrap();
-        assert_eq!(multiitem_reader.get_global_offset(), 4);
-        assert_eq!(multiitem_reader.get_local_offset(), 1);
-        assert_eq!(multiitem_reader.get_current_item_index(), 1);
+    fn read_line_limited_skips_long_line(mut split_line_reader: FakeReader) {
+        let mut line = String::new();
+        assert!(matches!(
+            split_line_reader.read_line_limited(&mut line, 2),
+            Err(ReadLimitedError::TooLong { offset: 0, max: 2 })
+        ));
+        assert_eq!(line, "");
+        split_line_reader.read_line_limited(&mut line, 100).unwrap();
+        assert_eq!(line, "world");
+    }
 
-        let mut buf = vec![255];
-        multiitem_reader.read_exact(&mut buf).unwrap();
-        assert_eq!(buf, vec![5]);
+    #[test]
+    fn read_line_limited_drops_invalid_line() {
+        let mut reader = Multireader::new(vec![Cursor::new(b"\xff\nok\n".to_vec())]).unwrap();
+        let mut line = String::new();
+        assert!(matches!(
+            reader.read_line_limited(&mut line, 10),
+            Err(ReadLimitedError::IO(e)) if e.kind() == std::io::ErrorKind::InvalidData
+        ));
+        assert_eq!(line, "");
+        reader.read_line_limited(&mut line, 10).unwrap();
+        assert_eq!(line, "ok\n");
     }
 
     #[test]
-    fn combining_read_and_bufread_should_advance_offset_properly() {
-        let text = "first\nsecond".to_string();
-        let mut reader = Multireader::new(vec![Cursor::new(text)]).unwrap();
-        let mut input = String::new();
-        reader.read_line(&mut input).unwrap();
+    fn rest_of_long_record_is_skipped_once_written() {
+        let mut reader = Multireader::new(vec![Cursor::new(b"long rec".to_vec())]).unwrap();
         let mut buf = vec![];
-        reader.read_to_end(&mut buf).unwrap();
-        assert_eq!(reader.get_global_offset(), 12);
+        assert!(matches!(
+            reader.read_until_limited(b'\n', &mut buf, 4),
+            Err(ReadLimitedError::TooLong { offset: 0, max: 4 })
+        ));
+        assert_eq!(reader.read_until_limited(b'\n', &mut buf, 4).unwrap(), 0);
+
+        let item = reader.get_item_mut(0).unwrap().get_mut();
+        item.extend_from_slice(b"ord\nnext\n");
+        assert_eq!(reader.read_until_limited(b'\n', &mut buf, 100).unwrap(), 5);
+        assert_eq!(buf, b"next\n");
     }
 
-    #[rstest]
-    fn total_size_is_computed_correctly(mut multiitem_reader: FakeReader) {
-        assert_eq!(multiitem_reader.get_total_size().unwrap(), 5)
+    fn junk_reader() -> FakeReader {
+        let items = vec![
+            Cursor::new(b"ok\nbro".to_vec()),
+            Cursor::new(b"\xffken\nnext".to_vec()),
+        ];
+        Multireader::new(items).unwrap()
     }
 
-    fn read_to_end(mut r: impl Read) -> Vec<u8> {
-        let mut buf = vec![];
-        r.read_to_end(&mut buf).unwrap();
-        buf
+    #[test]
+    fn read_line_lossy_replaces_invalid_utf8() {
+        let mut line = String::new();
+        let mut strict_reader = junk_reader();
+        strict_reader.read_line(&mut line).unwrap();
+        let error = strict_reader.read_line(&mut line).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        let mut reader = junk_reader();
+        line.clear();
+        reader.read_line_lossy(&mut line).unwrap();
+        line.clear();
+        assert_eq!(reader.read_line_lossy(&mut line).unwrap(), 8);
+        assert_eq!(line, "bro\u{FFFD}ken\n");
+        assert_eq!(reader.get_global_offset(), 11);
+
+        let mut raw = vec![];
+        assert_eq!(reader.read_line_raw(&mut raw).unwrap(), 4);
+        assert_eq!(raw, b"next");
+    }
+
+    fn last_item_seeks(reader: &Multireader<SeekCountingCursor>) -> usize {
+        reader.get_item(reader.len() - 1).unwrap().seeks
     }
 
     #[rstest]
-    #[case(0, 0, b"\x01\x02\x03\x04\x05")]
-    #[case(1, 3, b"\x04\x05")]
-    fn seek_to_item_start_works(
-        mut multiitem_reader: FakeReader,
-        #[case] item: usize,
-        #[case] expected_offset: u64,
-        #[case] expected_content: &'static [u8],
+    #[case(SizeCachePolicy::AlwaysRefresh, 12)]
+    #[case(SizeCachePolicy::Manual, 6)]
+    fn seeking_from_end_with_size_cache_policy(
+        #[case] policy: SizeCachePolicy,
+        #[case] expected_seeks: usize,
     ) {
+        let mut reader = counting_reader(2);
+        reader.set_size_cache_policy(policy);
+        // each seek positions last item, measuring it takes 3 more seeks
+        for _ in 0..3 {
+            reader.seek(SeekFrom::End(-1)).unwrap();
+        }
+        assert_eq!(last_item_seeks(&reader), expected_seeks);
+        assert_eq!(read_to_end(&mut reader), vec![1]);
+    }
+
+    #[rstest]
+    fn cached_size_agrees_after_refresh() {
+        let mut cached = split_line_reader();
+        cached.set_size_cache_policy(SizeCachePolicy::Manual);
+        let mut uncached = split_line_reader();
+        assert_eq!(cached.get_total_size().unwrap(), 11);
+
+        for reader in [&mut cached, &mut uncached] {
+            reader.get_item_mut(1).unwrap().get_mut().extend(b"!\n");
+        }
+        assert_eq!(cached.get_total_size().unwrap(), 11);
+        assert_eq!(uncached.get_total_size().unwrap(), 13);
+
+        assert_eq!(cached.refresh_last_size().unwrap(), 10);
+        assert_eq!(cached.get_total_size().unwrap(), 13);
+        for reader in [&mut cached, &mut uncached] {
+            reader.seek(SeekFrom::End(-3)).unwrap();
+        }
+        assert_eq!(read_to_end(&mut cached), read_to_end(&mut uncached));
+    }
+
+    #[rstest]
+    fn cached_size_is_reset_by_push(mut split_line_reader: FakeReader) {
+        split_line_reader.set_size_cache_policy(SizeCachePolicy::Manual);
+        assert_eq!(split_line_reader.get_total_size().unwrap(), 11);
+        split_line_reader
+            .get_item_mut(1)
+            .unwrap()
+            .get_mut()
+            .push(b'!');
+        split_line_reader.push(Cursor::new(b"?".to_vec())).unwrap();
+        assert_eq!(split_line_reader.get_total_size().unwrap(), 13);
+        assert_eq!(read_to_end(&mut split_line_reader), b"hello\nworld!?");
+    }
+
+    fn record_item_changes<R: Seek>(
+        reader: &mut Multireader<R>,
+    ) -> Arc<Mutex<Vec<(usize, usize)>>> {
+        let changes = Arc::new(Mutex::new(vec![]));
+        let sink = changes.clone();
+        reader
+            .set_item_change_hook(move |previous, new| sink.lock().unwrap().push((previous, new)));
+        changes
+    }
+
+    #[rstest]
+    fn item_change_hook_on_full_read(mut multiitem_reader: FakeReader) {
+        let changes = record_item_changes(&mut multiitem_reader);
+        assert_eq!(read_to_end(&mut multiitem_reader), vec![1, 2, 3, 4, 5]);
+        assert_eq!(*changes.lock().unwrap(), vec![(0, 1)]);
+    }
+
+    #[rstest]
+    fn item_change_hook_on_bytewise_reads(mut multiitem_reader: FakeReader) {
+        let changes = record_item_changes(&mut multiitem_reader);
+        let mut byte = [0];
+        while multiitem_reader.read(&mut byte).unwrap() > 0 {}
+        assert_eq!(*changes.lock().unwrap(), vec![(0, 1)]);
+    }
+
+    #[rstest]
+    fn item_change_hook_on_buffered_reads(mut split_line_reader: FakeReader) {
+        let changes = record_item_changes(&mut split_line_reader);
+        let lines: Vec<_> = (&mut split_line_reader)
+            .lines()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, vec!["hello", "world"]);
+        assert_eq!(*changes.lock().unwrap(), vec![(0, 1)]);
+    }
+
+    #[rstest]
+    fn item_change_hook_on_seeks() {
+        let mut reader = three_item_reader();
+        let changes = record_item_changes(&mut reader);
+        reader.seek_to_item_start(2).unwrap();
+        reader.seek(SeekFrom::Start(1)).unwrap();
+        reader.seek(SeekFrom::Start(2)).unwrap();
+        reader.seek(SeekFrom::End(-5)).unwrap();
+        reader.rewind().unwrap();
         assert_eq!(
-            multiitem_reader.seek_to_item_start(item).unwrap(),
-            expected_offset
+            *changes.lock().unwrap(),
+            vec![(0, 2), (2, 0), (0, 1), (1, 0)]
         );
-        assert_eq!(read_to_end(multiitem_reader), expected_content)
+
+        reader.remove_item_change_hook();
+        reader.seek(SeekFrom::End(0)).unwrap();
+        assert_eq!(changes.lock().unwrap().len(), 4);
+    }
+
+    fn item_bytes(reader: &FakeReader) -> Vec<u64> {
+        reader.stats().items.iter().map(|item| item.bytes).collect()
     }
 
     #[rstest]
-    #[case(0, 0, 0)]
-    #[case(0, 1, 1)]
-    #[case(1, 0, 3)]
-    #[case(1, 1, 4)]
-    fn seek_by_local_index_works(
-        mut multiitem_reader: FakeReader,
-        #[case] item_idx: usize,
-        #[case] index_inside_item: u64,
-        #[case] expected_offset: u64,
-    ) {
-        multiitem_reader
-            .seek_by_local_index(item_idx, std::io::SeekFrom::Start(index_inside_item))
+    fn stats_count_bytes_of_each_item(mut multiitem_reader: FakeReader) {
+        multiitem_reader.enable_stats();
+        multiitem_reader.read_to_end(&mut vec![]).unwrap();
+        assert_eq!(item_bytes(&multiitem_reader), vec![3, 2]);
+        let stats = multiitem_reader.stats();
+        assert_eq!(stats.total_bytes, 5);
+        assert_eq!(stats.item_transitions, 1);
+    }
+
+    #[rstest]
+    fn stats_count_read_calls(mut multiitem_reader: FakeReader) {
+        multiitem_reader.enable_stats();
+        while multiitem_reader.read(&mut [0; 1]).unwrap() > 0 {}
+        let reads: Vec<_> = multiitem_reader
+            .stats()
+            .items
+            .iter()
+            .map(|item| item.reads)
+            .collect();
+        assert_eq!(reads, vec![3, 2]);
+        assert_eq!(item_bytes(&multiitem_reader), vec![3, 2]);
+    }
+
+    #[rstest]
+    fn stats_count_buffered_reads(mut split_line_reader: FakeReader) {
+        split_line_reader.enable_stats();
+        let mut line = String::new();
+        split_line_reader.read_line(&mut line).unwrap();
+        assert_eq!(item_bytes(&split_line_reader), vec![3, 3]);
+        split_line_reader.read_line(&mut line).unwrap();
+        assert_eq!(item_bytes(&split_line_reader), vec![3, 8]);
+        assert_eq!(split_line_reader.stats().item_transitions, 1);
+    }
+
+    #[rstest]
+    fn stats_are_not_collected_by_default(mut multiitem_reader: FakeReader) {
+        multiitem_reader.read_to_end(&mut vec![]).unwrap();
+        assert_eq!(*multiitem_reader.stats(), ReaderStats::default());
+    }
+
+    #[test]
+    fn stats_of_removed_items_are_dropped() {
+        let mut reader = three_item_reader();
+        reader.enable_stats();
+        reader.read_to_end(&mut vec![]).unwrap();
+        let total_bytes = reader.stats().total_bytes;
+        reader.retain_from(1);
+        assert_eq!(reader.stats().items.len(), 2);
+        assert_eq!(reader.stats().total_bytes, total_bytes);
+    }
+
+    #[rstest]
+    fn from_paths_with_capacity_reads_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = [dir.path().join("log.1"), dir.path().join("log")];
+        std::fs::write(&paths[0], "hel").unwrap();
+        std::fs::write(&paths[1], "lo\nworld").unwrap();
+
+        let mut reader = Multireader::from_paths_with_capacity(&paths, 2).unwrap();
+        assert!(reader.iter().all(|item| item.capacity() == 2));
+        assert_eq!(read_to_end(&mut reader), b"hello\nworld");
+    }
+
+    #[rstest]
+    fn from_paths_reports_missing_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().join("log");
+        std::fs::write(&existing, "").unwrap();
+        let missing = dir.path().join("log.1");
+
+        let err = Multireader::from_paths([&missing, &existing]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        assert!(err.to_string().contains(&missing.display().to_string()));
+    }
+
+    #[rstest]
+    fn get_item_size_while_in_first_item() {
+        let mut reader = reader_with_empty_items(&["abc", "", "de", "fghi"]);
+        reader.read_exact(&mut [0; 1]).unwrap();
+        assert_eq!(reader.get_item_size_cached(2), None);
+        assert_eq!(reader.get_item_size(3).unwrap(), 4);
+        assert_eq!(reader.get_item_size(2).unwrap(), 2);
+        assert_eq!(reader.get_item_size_cached(2), Some(2));
+        assert_eq!(reader.get_item_size(1).unwrap(), 0);
+        assert_eq!(reader.get_item_size(0).unwrap(), 3);
+        assert_eq!(reader.get_item_size_cached(3), None);
+
+        assert_eq!(reader.get_global_offset(), 1);
+        assert_eq!(reader.get_current_item_index(), 0);
+        assert_eq!(read_to_end(&mut reader), b"bcdefghi");
+    }
+
+    #[rstest]
+    fn get_item_size_out_of_range(mut multiitem_reader: FakeReader, mut empty_reader: FakeReader) {
+        let err = multiitem_reader.get_item_size(2).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(empty_reader.get_item_size(0).is_err());
+        assert_eq!(multiitem_reader.get_item_size_cached(2), None);
+    }
+
+    #[rstest]
+    fn consume_is_clamped_to_filled_buffer(mut split_line_reader: FakeReader) {
+        assert_eq!(split_line_reader.fill_buf().unwrap(), b"hel");
+        split_line_reader.consume(100);
+        assert_eq!(split_line_reader.get_global_offset(), 3);
+        // nothing is outstanding after buffer was consumed completely
+        split_line_reader.consume(5);
+        assert_eq!(split_line_reader.get_global_offset(), 3);
+        assert_eq!(read_to_end(&mut split_line_reader), b"lo\nworld");
+    }
+
+    #[rstest]
+    fn consume_without_fill_buf_does_nothing(mut split_line_reader: FakeReader) {
+        split_line_reader.consume(2);
+        assert_eq!(split_line_reader.get_global_offset(), 0);
+
+        let mut buf = [0; 2];
+        split_line_reader.read_exact(&mut buf).unwrap();
+        split_line_reader.consume(4);
+        assert_eq!(split_line_reader.get_global_offset(), 2);
+        assert_eq!(read_to_end(&mut split_line_reader), b"llo\nworld");
+    }
+
+    #[rstest]
+    fn consume_after_seek_current_item_does_nothing(mut split_line_reader: FakeReader) {
+        assert_eq!(split_line_reader.fill_buf().unwrap(), b"hel");
+        split_line_reader
+            .seek_current_item(SeekFrom::Start(1))
             .unwrap();
+        split_line_reader.consume(2);
+        assert_eq!(split_line_reader.get_global_offset(), 1);
+        assert_eq!(read_to_end(&mut split_line_reader), b"ello\nworld");
+    }
 
-        assert_eq!(multiitem_reader.get_global_offset(), expected_offset)
+    #[test]
+    fn consume_is_clamped_to_buffer_of_inner_reader() {
+        let items = ["abcd", "ef"]
+            .into_iter()
+            .map(|content| BufReader::with_capacity(2, Cursor::new(content.as_bytes().to_vec())))
+            .collect();
+        let mut reader = Multireader::new(items).unwrap();
+        assert_eq!(reader.fill_buf().unwrap(), b"ab");
+        reader.consume(3);
+        assert_eq!(reader.get_global_offset(), 2);
+        // partially consumed buffer is clamped to its remainder
+        assert_eq!(reader.fill_buf().unwrap(), b"cd");
+        reader.consume(1);
+        reader.consume(7);
+        assert_eq!(reader.get_global_offset(), 4);
+        assert_eq!(read_to_end(&mut reader), b"ef");
+    }
+
+    #[test]
+    fn snapshot_round_trips_through_bincode() {
+        let mut reader = three_item_reader();
+        reader.read_exact(&mut [0; 6]).unwrap();
+        let snapshot = reader.snapshot();
+        let serialized = bincode::serialize(&snapshot).unwrap();
+        let deserialized: MultireaderSnapshot = bincode::deserialize(&serialized).unwrap();
+        assert_eq!(deserialized, snapshot);
+
+        // sizes are discovered during restore
+        let items = [vec![1, 2, 3], vec![4, 5], vec![6, 7, 8, 9]];
+        let mut restored = Multireader::new(items.map(Cursor::new).to_vec()).unwrap();
+        restored.restore(&deserialized).unwrap();
+        assert_eq!(restored.get_global_offset(), 6);
+        assert_eq!(restored.get_current_item_index(), 2);
+        assert_eq!(read_to_end(&mut restored), vec![7, 8, 9]);
+    }
+
+    #[rstest]
+    #[case(vec![vec![1, 2, 3], vec![4, 5]])]
+    #[case(vec![vec![1, 2, 3], vec![4], vec![6, 7, 8, 9]])]
+    fn restore_rejects_mismatched_reader(#[case] items: Vec<Vec<u8>>) {
+        let mut reader = three_item_reader();
+        reader.read_exact(&mut [0; 6]).unwrap();
+        let snapshot = reader.snapshot();
+
+        let mut other = Multireader::new(items.into_iter().map(Cursor::new).collect()).unwrap();
+        other.read_exact(&mut [0; 1]).unwrap();
+        let error = other.restore(&snapshot).unwrap_err();
+        assert!(matches!(
+            error,
+            RestoreError::ItemCountMismatch { .. } | RestoreError::SizeMismatch { .. }
+        ));
+        assert_eq!(other.get_global_offset(), 1);
+    }
+
+    #[rstest]
+    #[case(&["abc{\"", "id\":1}"], 4)]
+    #[case(&["abc{", "\"i", "d", "\":1}"], 4)]
+    #[case(&["{\"i{\"", "id\""], 4)]
+    #[case(&["", "xx\"id\"", ""], 2)]
+    fn find_handles_matches_across_items(#[case] items: &[&str], #[case] expected: u64) {
+        let mut reader = reader_with_empty_items(items);
+        assert_eq!(reader.find(b"\"id\"").unwrap(), Some(expected));
+        assert_eq!(reader.get_global_offset(), expected);
+        let rest = joined_content(items)[expected as usize..].to_vec();
+        assert_eq!(read_to_end(&mut reader), rest);
+    }
+
+    #[test]
+    fn find_works_with_small_item_buffers() {
+        let items = ["xy\"", "i", "d\"z"]
+            .into_iter()
+            .map(|content| BufReader::with_capacity(1, Cursor::new(content.as_bytes().to_vec())))
+            .collect();
+        let mut reader = Multireader::new(items).unwrap();
+        assert_eq!(reader.find(b"\"id\"").unwrap(), Some(2));
+        assert_eq!(read_to_end(&mut reader), b"\"id\"z");
+    }
+
+    #[rstest]
+    fn find_leaves_cursor_at_eof_when_not_found(mut split_line_reader: FakeReader) {
+        assert_eq!(split_line_reader.find(b"low").unwrap(), None);
+        assert_eq!(split_line_reader.get_global_offset(), 11);
+        assert_eq!(split_line_reader.find(b"").unwrap(), Some(11));
+    }
+
+    #[rstest]
+    fn find_searches_from_current_position(mut split_line_reader: FakeReader) {
+        assert_eq!(split_line_reader.find(b"l").unwrap(), Some(2));
+        split_line_reader.read_exact(&mut [0; 1]).unwrap();
+        assert_eq!(split_line_reader.find(b"l").unwrap(), Some(3));
+        split_line_reader.read_exact(&mut [0; 1]).unwrap();
+        assert_eq!(split_line_reader.find(b"l").unwrap(), Some(9));
+        assert_eq!(split_line_reader.find(b"").unwrap(), Some(9));
+    }
+
+    #[rstest]
+    #[case(&["a\nb\n", "c\nd"], 2, &["c", "d"])]
+    #[case(&["a\nb\n", "c\nd\n"], 3, &["b", "c", "d"])]
+    #[case(&["a\nb\n", "c\n", ""], 3, &["a", "b", "c"])]
+    #[case(&["a\nb", "c\n", ""], 5, &["a", "bc"])]
+    #[case(&["a\r\nb\n", "\nc"], 2, &["", "c"])]
+    #[case(&["a\n", "b"], 0, &[])]
+    #[case(&["", ""], 2, &[])]
+    #[case(&["\n"], 2, &[""])]
+    fn last_lines_walks_into_earlier_items(
+        #[case] items: &[&str],
+        #[case] n: usize,
+        #[case] expected: &[&str],
+    ) {
+        let mut reader = reader_with_empty_items(items);
+        reader.read_exact(&mut [0; 1]).unwrap_or_default();
+        let offset = reader.get_global_offset();
+        assert_eq!(reader.last_lines(n).unwrap(), expected);
+        assert_eq!(reader.get_global_offset(), offset);
+        assert_eq!(
+            read_to_end(&mut reader),
+            joined_content(items)[offset as usize..]
+        );
+    }
+
+    #[test]
+    fn last_lines_scans_multiple_blocks() {
+        let lines = (0..5000).map(|i| format!("line {i}\n")).collect::<Vec<_>>();
+        let (first, second) = lines.split_at(4990);
+        let mut reader = Multireader::new(vec![
+            Cursor::new(first.concat().into_bytes()),
+            Cursor::new(second.concat().into_bytes()),
+        ])
+        .unwrap();
+        let last_lines = reader.last_lines(2000).unwrap();
+        assert_eq!(last_lines.len(), 2000);
+        assert_eq!(last_lines[0], "line 3000");
+        assert_eq!(last_lines[1999], "line 4999");
     }
 }
@@ -1,9 +1,211 @@
 use std::{
-    io,
-    os::unix::prelude::MetadataExt,
+    fmt, fs, io,
     path::{Path, PathBuf},
 };
 
+/// Naming scheme of rotated logs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RotationNaming {
+    /// `path.1`, `path.2` and so on, `path.1` being the most recent rotated log.
+    #[default]
+    Numeric,
+    /// `path-YYYYMMDD` as produced by `dateext` option of logrotate, optionally followed by `-` and more digits (eg.
+    /// `path-20240115-10` with hourly rotation). Ordered by embedded date.
+    DateExt,
+    /// Numeric if there are any numbered rotated logs, dateext otherwise.
+    Auto,
+}
+
+/// Why a log that exists is not read, see `InodeAwareReader::resolution_report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SkipReason {
+    /// There are more rotated logs than search depth allows.
+    BeyondDepth,
+    /// Numbering of rotated logs has a gap before this one, so it was not reached.
+    AfterGap,
+    /// Log was last modified before cutoff, see `InodeAwareReaderBuilder::min_mtime`.
+    OlderThanCutoff,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SkipReason::BeyondDepth => "beyond search depth",
+            SkipReason::AfterGap => "after a gap in numbering",
+            SkipReason::OlderThanCutoff => "older than cutoff",
+        })
+    }
+}
+
+/// Defines how rotated versions of a log are named and where they are located.
+///
+/// ## Ordering contract
+///
+/// `candidates` must return paths of existing rotated logs ordered from the **oldest** to the **newest** one, not
+/// including live log itself (which is always read last). If there are more than `max_depth` rotated logs, only
+/// `max_depth` most recent ones should be returned.
+///
+/// Schemes are implemented for `RotationNaming` and for closures with matching signature (which ignore `suffixes`):
+///
+/// ```rust no_run
+/// # use std::{io, path::{Path, PathBuf}};
+/// # use filetrack::{InodeAwareReader, path_utils::RotationScheme};
+/// // rotated logs are moved into `archive/` next to live log, timestamp in name sorts chronologically
+/// let archive = |live_path: &Path, max_depth: usize| -> io::Result<Vec<PathBuf>> {
+///     let mut paths = std::fs::read_dir(live_path.with_file_name("archive"))?
+///         .map(|entry| entry.map(|entry| entry.path()))
+///         .collect::<io::Result<Vec<_>>>()?;
+///     paths.sort();
+///     Ok(paths.split_off(paths.len().saturating_sub(max_depth)))
+/// };
+/// let reader = InodeAwareReader::from_rotated_logs_with_scheme("/var/log/service.log", 5, archive)?;
+/// # Ok::<(), io::Error>(())
+/// ```
+pub trait RotationScheme {
+    /// Find up to `max_depth` most recent rotated versions of `live_path`, ordered from oldest to newest.
+    ///
+    /// `suffixes` are suffixes of compressed logs that can be decompressed (eg. `.gz`), rotated logs with one of these
+    /// suffixes appended should be considered as well.
+    fn candidates(
+        &self,
+        live_path: &Path,
+        max_depth: usize,
+        suffixes: &[&str],
+    ) -> io::Result<Vec<PathBuf>>;
+
+    /// Paths that `candidates` considered but did not return, for diagnostics (see
+    /// `InodeAwareReader::resolution_report`). Paths of existing logs come with the reason they were skipped, paths that
+    /// were probed but do not exist come with None. Nothing is reported by default.
+    fn rejected(
+        &self,
+        _live_path: &Path,
+        _max_depth: usize,
+        _suffixes: &[&str],
+    ) -> io::Result<Vec<(PathBuf, Option<SkipReason>)>> {
+        Ok(vec![])
+    }
+}
+
+impl<F> RotationScheme for F
+where
+    F: Fn(&Path, usize) -> io::Result<Vec<PathBuf>>,
+{
+    fn candidates(
+        &self,
+        live_path: &Path,
+        max_depth: usize,
+        _suffixes: &[&str],
+    ) -> io::Result<Vec<PathBuf>> {
+        self(live_path, max_depth)
+    }
+}
+
+impl RotationScheme for RotationNaming {
+    fn candidates(
+        &self,
+        live_path: &Path,
+        max_depth: usize,
+        suffixes: &[&str],
+    ) -> io::Result<Vec<PathBuf>> {
+        naming_candidates(*self, live_path, None, max_depth, suffixes)
+    }
+
+    fn rejected(
+        &self,
+        live_path: &Path,
+        max_depth: usize,
+        suffixes: &[&str],
+    ) -> io::Result<Vec<(PathBuf, Option<SkipReason>)>> {
+        naming_rejected(*self, live_path, None, max_depth, suffixes)
+    }
+}
+
+/// Rotated logs that may be moved into another directory, as done by `olddir` option of logrotate (eg. `mail.log` in
+/// `/var/log` and `mail.log.1` in `/var/log/archive`).
+///
+/// Rotated logs named according to `naming` are searched for both next to live log and in `dir`, so generations may be
+/// split between the two. Relative `dir` is resolved against directory of live log, same as logrotate does.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OldDir {
+    pub naming: RotationNaming,
+    pub dir: PathBuf,
+}
+
+impl OldDir {
+    /// Search for numbered rotated logs in `dir` as well.
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            naming: RotationNaming::Numeric,
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn resolve_dir(&self, live_path: &Path) -> PathBuf {
+        live_path.parent().unwrap_or(Path::new("")).join(&self.dir)
+    }
+}
+
+impl RotationScheme for OldDir {
+    fn candidates(
+        &self,
+        live_path: &Path,
+        max_depth: usize,
+        suffixes: &[&str],
+    ) -> io::Result<Vec<PathBuf>> {
+        let dir = self.resolve_dir(live_path);
+        naming_candidates(self.naming, live_path, Some(&dir), max_depth, suffixes)
+    }
+
+    fn rejected(
+        &self,
+        live_path: &Path,
+        max_depth: usize,
+        suffixes: &[&str],
+    ) -> io::Result<Vec<(PathBuf, Option<SkipReason>)>> {
+        let dir = self.resolve_dir(live_path);
+        naming_rejected(self.naming, live_path, Some(&dir), max_depth, suffixes)
+    }
+}
+
+fn naming_candidates(
+    naming: RotationNaming,
+    live_path: &Path,
+    olddir: Option<&Path>,
+    max_depth: usize,
+    suffixes: &[&str],
+) -> io::Result<Vec<PathBuf>> {
+    match naming {
+        RotationNaming::Numeric => Ok(numeric_candidates(live_path, olddir, max_depth, suffixes)),
+        RotationNaming::DateExt => dateext_candidates(live_path, olddir, max_depth, suffixes),
+        RotationNaming::Auto => {
+            let numeric = numeric_candidates(live_path, olddir, max_depth, suffixes);
+            if !numeric.is_empty() {
+                return Ok(numeric);
+            }
+            dateext_candidates(live_path, olddir, max_depth, suffixes)
+        }
+    }
+}
+
+fn naming_rejected(
+    naming: RotationNaming,
+    live_path: &Path,
+    olddir: Option<&Path>,
+    max_depth: usize,
+    suffixes: &[&str],
+) -> io::Result<Vec<(PathBuf, Option<SkipReason>)>> {
+    match naming {
+        RotationNaming::Numeric => Ok(numeric_rejected(live_path, olddir, max_depth, suffixes)),
+        RotationNaming::DateExt => dateext_rejected(live_path, olddir, max_depth, suffixes),
+        RotationNaming::Auto => {
+            if find_numbered(live_path, olddir, 1, suffixes).is_some() {
+                return Ok(numeric_rejected(live_path, olddir, max_depth, suffixes));
+            }
+            dateext_rejected(live_path, olddir, max_depth, suffixes)
+        }
+    }
+}
+
 /// Search for logfile and its rotated versions as `path`, `path.1`, `path.2` and so on.
 ///
 /// Returns pairs of path and inode.
@@ -11,28 +213,271 @@ pub fn glob_rotated_logs(
     path: impl AsRef<Path>,
     max_depth: usize,
 ) -> io::Result<Vec<(PathBuf, u64)>> {
-    let mut result = vec![];
+    glob_rotated_logs_with_suffixes(path, max_depth, &[])
+}
+
+/// Like `glob_rotated_logs`, but if `path.N` does not exist, also probes `path.N{suffix}` for every suffix in order (eg.
+/// `mail.log.2.gz` for suffix `.gz`), which allows finding compressed rotated logs.
+///
+/// Returns pairs of path and inode, ordered from oldest to newest.
+pub fn glob_rotated_logs_with_suffixes(
+    path: impl AsRef<Path>,
+    max_depth: usize,
+    suffixes: &[&str],
+) -> io::Result<Vec<(PathBuf, u64)>> {
+    glob_rotated_logs_with_scheme(path, max_depth, &RotationNaming::Numeric, suffixes)
+}
 
-    result.push((path.as_ref().to_path_buf(), get_inode_by_path(&path)?));
+/// Search for logfile and its rotated versions named according to `naming`, probing compressed versions with `suffixes`
+/// as well (see `glob_rotated_logs_with_suffixes`). Up to `max_depth` most recent rotated logs are returned.
+///
+/// Returns pairs of path and inode, ordered from oldest to newest.
+pub fn glob_rotated_logs_with_naming(
+    path: impl AsRef<Path>,
+    max_depth: usize,
+    naming: RotationNaming,
+    suffixes: &[&str],
+) -> io::Result<Vec<(PathBuf, u64)>> {
+    glob_rotated_logs_with_scheme(path, max_depth, &naming, suffixes)
+}
+
+/// Like `glob_rotated_logs_with_suffixes`, but numbered rotated logs are searched for in `olddir` as well, see `OldDir`.
+///
+/// Returns pairs of path and inode, ordered from oldest to newest.
+pub fn glob_rotated_logs_with_olddir(
+    path: impl AsRef<Path>,
+    max_depth: usize,
+    olddir: impl AsRef<Path>,
+    suffixes: &[&str],
+) -> io::Result<Vec<(PathBuf, u64)>> {
+    glob_rotated_logs_with_scheme(path, max_depth, &OldDir::new(olddir), suffixes)
+}
 
+/// Search for logfile and its rotated versions found by `scheme`.
+///
+/// Returns pairs of path and inode, ordered from oldest to newest with `path` itself being the last one.
+pub fn glob_rotated_logs_with_scheme(
+    path: impl AsRef<Path>,
+    max_depth: usize,
+    scheme: &(impl RotationScheme + ?Sized),
+    suffixes: &[&str],
+) -> io::Result<Vec<(PathBuf, u64)>> {
+    let path = path.as_ref();
+    let live_inode = file_id(path)?;
+    let mut result = scheme
+        .candidates(path, max_depth, suffixes)?
+        .into_iter()
+        .map(|path| {
+            let inode = file_id(&path)?;
+            Ok((path, inode))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    result.push((path.to_path_buf(), live_inode));
+    Ok(result)
+}
+
+/// Find `path.1`, `path.2` and so on (or their versions with one of `suffixes` appended) up to the first missing one.
+fn numeric_candidates(
+    path: &Path,
+    olddir: Option<&Path>,
+    max_depth: usize,
+    suffixes: &[&str],
+) -> Vec<PathBuf> {
+    let mut result = vec![];
     for i in 1..=max_depth {
-        let path = append_extension(path.as_ref().to_path_buf(), i.to_string());
-        if !path.exists() {
+        let Some(path) = find_numbered(path, olddir, i, suffixes) else {
             break;
+        };
+        result.push(path);
+    }
+    result.reverse();
+    result
+}
+
+/// Find numbered logs that are not returned by `numeric_candidates`: the first missing one, existing ones after it up to
+/// `max_depth` and the one just beyond `max_depth`.
+fn numeric_rejected(
+    path: &Path,
+    olddir: Option<&Path>,
+    max_depth: usize,
+    suffixes: &[&str],
+) -> Vec<(PathBuf, Option<SkipReason>)> {
+    let Some(first_missing) =
+        (1..=max_depth).find(|&i| find_numbered(path, olddir, i, suffixes).is_none())
+    else {
+        return find_numbered(path, olddir, max_depth + 1, suffixes)
+            .map(|path| (path, Some(SkipReason::BeyondDepth)))
+            .into_iter()
+            .collect();
+    };
+    let mut result = vec![(
+        append_extension(path.to_path_buf(), first_missing.to_string()),
+        None,
+    )];
+    result.extend(
+        (first_missing + 1..=max_depth)
+            .filter_map(|i| find_numbered(path, olddir, i, suffixes))
+            .map(|path| (path, Some(SkipReason::AfterGap))),
+    );
+    result
+}
+
+/// Find `path.i` or its version with one of `suffixes` appended, next to `path` first and then in `olddir`.
+fn find_numbered(
+    path: &Path,
+    olddir: Option<&Path>,
+    i: usize,
+    suffixes: &[&str],
+) -> Option<PathBuf> {
+    let rotated_path = append_extension(path.to_path_buf(), i.to_string());
+    let moved_path = olddir
+        .zip(rotated_path.file_name())
+        .map(|(olddir, file_name)| olddir.join(file_name));
+    std::iter::once(rotated_path)
+        .chain(moved_path)
+        .flat_map(|rotated_path| {
+            std::iter::once(rotated_path.clone()).chain(
+                suffixes
+                    .iter()
+                    .map(move |suffix| append_suffix(rotated_path.clone(), suffix)),
+            )
+        })
+        .find(|path| path.exists())
+}
+
+/// Find rotated logs named `path-YYYYMMDD` in the same directory, ordered by date.
+fn dateext_candidates(
+    path: &Path,
+    olddir: Option<&Path>,
+    max_depth: usize,
+    suffixes: &[&str],
+) -> io::Result<Vec<PathBuf>> {
+    let mut rotated = all_dateext_logs(path, olddir, suffixes)?;
+    Ok(rotated.split_off(rotated.len().saturating_sub(max_depth)))
+}
+
+/// Find rotated logs named `path-YYYYMMDD` that are older than `max_depth` most recent ones, newest first.
+fn dateext_rejected(
+    path: &Path,
+    olddir: Option<&Path>,
+    max_depth: usize,
+    suffixes: &[&str],
+) -> io::Result<Vec<(PathBuf, Option<SkipReason>)>> {
+    let mut rotated = all_dateext_logs(path, olddir, suffixes)?;
+    rotated.truncate(rotated.len().saturating_sub(max_depth));
+    Ok(rotated
+        .into_iter()
+        .rev()
+        .map(|path| (path, Some(SkipReason::BeyondDepth)))
+        .collect())
+}
+
+/// Find all rotated logs named `path-YYYYMMDD` in the same directory and in `olddir` (if it exists), ordered by date.
+fn all_dateext_logs(
+    path: &Path,
+    olddir: Option<&Path>,
+    suffixes: &[&str],
+) -> io::Result<Vec<PathBuf>> {
+    let Some(prefix) = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| format!("{name}-"))
+    else {
+        return Ok(vec![]);
+    };
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let entries = match olddir.map(fs::read_dir) {
+        Some(Ok(entries)) => Some(entries),
+        Some(Err(e)) if e.kind() == io::ErrorKind::NotFound => None,
+        Some(Err(e)) => return Err(e),
+        None => None,
+    };
+
+    // pairs of parsed date stamp (which is ordered the same way as logs are rotated) and path
+    let mut rotated = vec![];
+    for entry in fs::read_dir(directory)?.chain(entries.into_iter().flatten()) {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(stamp) = file_name
+            .to_str()
+            .and_then(|file_name| file_name.strip_prefix(&prefix))
+        else {
+            continue;
+        };
+        let stamp = suffixes
+            .iter()
+            .find_map(|suffix| stamp.strip_suffix(suffix))
+            .unwrap_or(stamp);
+        if let Some(stamp) = parse_date_stamp(stamp) {
+            rotated.push((stamp, entry.path()));
         }
-        let inode = get_inode_by_path(&path)?;
-        result.push((path, inode));
     }
+    rotated.sort();
+    Ok(rotated.into_iter().map(|(_, path)| path).collect())
+}
 
-    result.reverse();
+/// Parse `stamp` that looks like `YYYYMMDD`, optionally followed by `-` and a number, into date and that number. None if
+/// `stamp` has another format.
+fn parse_date_stamp(stamp: &str) -> Option<(u32, Option<u64>)> {
+    let is_digits = |part: &[u8]| !part.is_empty() && part.iter().all(u8::is_ascii_digit);
+    // checking bytes first makes sure that date ends at char boundary
+    if stamp.len() < 8 || !is_digits(&stamp.as_bytes()[..8]) {
+        return None;
+    }
+    let (date, number) = stamp.split_at(8);
+    let number = match number {
+        "" => None,
+        _ => {
+            let digits = number.strip_prefix('-')?;
+            if !is_digits(digits.as_bytes()) {
+                return None;
+            }
+            Some(digits.parse().ok()?)
+        }
+    };
+    Some((date.parse().ok()?, number))
+}
 
-    Ok(result)
+/// Ask the filesystem for identifier of file specified by `path` that is preserved across renames.
+///
+/// On unix this is the inode. On Windows this is the file index combined with serial number of the volume. Throughout the
+/// crate this identifier is called inode regardless of platform.
+pub fn file_id(path: impl AsRef<Path>) -> io::Result<u64> {
+    platform_file_id(path.as_ref())
 }
 
-/// Ask the filesystem for metadata and return inode for fs object specified by `path`.
+/// Alias of `file_id`, kept for compatibility.
 pub fn get_inode_by_path(path: impl AsRef<Path>) -> io::Result<u64> {
-    let metadata = std::fs::metadata(&path)?;
-    Ok(metadata.ino())
+    file_id(path)
+}
+
+#[cfg(unix)]
+fn platform_file_id(path: &Path) -> io::Result<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    Ok(fs::metadata(path)?.ino())
+}
+
+#[cfg(windows)]
+fn platform_file_id(path: &Path) -> io::Result<u64> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+    };
+
+    let file = fs::File::open(path)?;
+    let mut info = BY_HANDLE_FILE_INFORMATION::default();
+    // SAFETY: handle is valid while `file` is alive and `info` is a properly sized out parameter
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let file_index = (info.nFileIndexHigh as u64) << 32 | info.nFileIndexLow as u64;
+    // rotated logs normally share a volume, so serial number only guards against collisions and can be folded in
+    Ok(file_index ^ (info.dwVolumeSerialNumber as u64).rotate_left(48))
 }
 
 /// Add extension to existing PathBuf.
@@ -51,3 +496,158 @@ pub fn append_extension(path: PathBuf, ext: impl AsRef<std::ffi::OsStr>) -> Path
     os_string.push(ext.as_ref());
     os_string.into()
 }
+
+/// Append `suffix` to the file name as is, eg. `.gz` turns `mail.log.1` into `mail.log.1.gz`.
+fn append_suffix(path: PathBuf, suffix: &str) -> PathBuf {
+    let mut os_string: std::ffi::OsString = path.into();
+    os_string.push(suffix);
+    os_string.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use rstest::rstest;
+
+    use super::{
+        file_id, glob_rotated_logs_with_naming, glob_rotated_logs_with_olddir, OldDir,
+        RotationNaming, RotationScheme,
+    };
+
+    fn glob_names(files: &[&str], max_depth: usize, naming: RotationNaming) -> Vec<String> {
+        let dir = tempfile::tempdir().unwrap();
+        for file in files {
+            fs::write(dir.path().join(file), b"").unwrap();
+        }
+        glob_rotated_logs_with_naming(dir.path().join("log"), max_depth, naming, &[".gz"])
+            .unwrap()
+            .into_iter()
+            .map(|(path, _)| path.file_name().unwrap().to_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[rstest]
+    #[case(
+        &["log", "log-20240115", "log-20231231", "log-20240114.gz"],
+        5,
+        &["log-20231231", "log-20240114.gz", "log-20240115", "log"]
+    )]
+    #[case(
+        &["log", "log-20240115-10", "log-20240115-09", "log-20240114"],
+        2,
+        &["log-20240115-09", "log-20240115-10", "log"]
+    )]
+    #[case(
+        &["log", "log-20240115-10", "log-20240115-9", "log-20240115"],
+        5,
+        &["log-20240115", "log-20240115-9", "log-20240115-10", "log"]
+    )]
+    #[case(
+        &["log", "log-日本語x", "log-2024011é", "log-20240115"],
+        5,
+        &["log-20240115", "log"]
+    )]
+    #[case(
+        &["log", "log-2024011", "log-20240115-", "log-20240115.old", "other-20240115", "log.1"],
+        5,
+        &["log"]
+    )]
+    fn dateext_logs_are_ordered_by_date(
+        #[case] files: &[&str],
+        #[case] max_depth: usize,
+        #[case] expected: &[&str],
+    ) {
+        assert_eq!(
+            glob_names(files, max_depth, RotationNaming::DateExt),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(&["log", "log.1", "log-20240115"], &["log.1", "log"])]
+    #[case(&["log", "log-20240115", "log-20240114"], &["log-20240114", "log-20240115", "log"])]
+    fn auto_naming_prefers_numeric(#[case] files: &[&str], #[case] expected: &[&str]) {
+        assert_eq!(glob_names(files, 2, RotationNaming::Auto), expected);
+    }
+
+    #[test]
+    fn file_id_survives_rename_but_not_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let (log, rotated, copy) = (
+            dir.path().join("log"),
+            dir.path().join("log.1"),
+            dir.path().join("log.copy"),
+        );
+        fs::write(&log, b"content").unwrap();
+        let id = file_id(&log).unwrap();
+        assert_eq!(file_id(&log).unwrap(), id);
+
+        fs::rename(&log, &rotated).unwrap();
+        assert_eq!(file_id(&rotated).unwrap(), id);
+
+        fs::copy(&rotated, &copy).unwrap();
+        assert_ne!(file_id(&copy).unwrap(), id);
+        assert!(file_id(&log).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_id_is_inode_on_unix() {
+        use std::os::unix::fs::MetadataExt;
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        assert_eq!(
+            file_id(file.path()).unwrap(),
+            fs::metadata(file.path()).unwrap().ino()
+        );
+    }
+
+    #[test]
+    fn generations_are_mixed_between_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let olddir = tempfile::tempdir().unwrap();
+        for file in ["log", "log.1"] {
+            fs::write(dir.path().join(file), b"").unwrap();
+        }
+        for file in ["log.2.gz", "log.3", "log.5"] {
+            fs::write(olddir.path().join(file), b"").unwrap();
+        }
+        let found =
+            glob_rotated_logs_with_olddir(dir.path().join("log"), 5, olddir.path(), &[".gz"])
+                .unwrap()
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                olddir.path().join("log.3"),
+                olddir.path().join("log.2.gz"),
+                dir.path().join("log.1"),
+                dir.path().join("log"),
+            ]
+        );
+    }
+
+    #[test]
+    fn dateext_logs_are_found_in_olddir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("old")).unwrap();
+        fs::write(dir.path().join("log-20240102"), b"").unwrap();
+        fs::write(dir.path().join("old/log-20240101"), b"").unwrap();
+        fs::write(dir.path().join("old/log-20240103"), b"").unwrap();
+        let scheme = OldDir {
+            naming: RotationNaming::DateExt,
+            dir: "old".into(),
+        };
+        let found = scheme.candidates(&dir.path().join("log"), 2, &[]).unwrap();
+        assert_eq!(
+            found,
+            [
+                dir.path().join("log-20240102"),
+                dir.path().join("old/log-20240103"),
+            ]
+        );
+    }
+}
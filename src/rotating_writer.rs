@@ -0,0 +1,189 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::path_utils::append_extension;
+
+/// Writer that simulates logrotate, mostly useful for testing consumers of this crate.
+///
+/// Rotated files are named the same way `path_utils::glob_rotated_logs` expects: `path.1` is the most recent rotated
+/// file, `path.2` is the one before it and so on up to `max_depth`. Older files are removed.
+///
+/// ```rust no_run
+/// # use std::io::Write;
+/// # use filetrack::RotatingWriter;
+/// let mut writer = RotatingWriter::new("examples/file.txt")?;
+/// writeln!(writer, "first")?;
+/// // examples/file.txt is moved to examples/file.txt.1 and a new file is created
+/// writer.rotate()?;
+/// writeln!(writer, "second")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct RotatingWriter {
+    path: PathBuf,
+    file: File,
+    max_depth: usize,
+}
+
+impl RotatingWriter {
+    /// Open `path` for appending (creating it if needed), keeping one rotated file.
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::with_max_depth(path, 1)
+    }
+
+    /// Like `::new` but allows specifying how many rotated files to keep.
+    ///
+    /// `max_depth` of 2 means that `path.1` and `path.2` are kept. With `max_depth` of 0 content is discarded on rotation.
+    pub fn with_max_depth(path: impl AsRef<Path>, max_depth: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = open_for_append(&path)?;
+        Ok(Self {
+            path,
+            file,
+            max_depth,
+        })
+    }
+
+    /// Path of the file that is currently written to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Rotate by renaming current file to `path.1` (shifting older files further) and creating a new file under `path`.
+    ///
+    /// Readers that have current file open keep reading it under the new name, as with `create` strategy of logrotate.
+    pub fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.shift_rotated()?;
+        match self.max_depth {
+            0 => fs::remove_file(&self.path)?,
+            _ => fs::rename(&self.path, self.rotated_path(1))?,
+        }
+        self.file = open_for_append(&self.path)?;
+        Ok(())
+    }
+
+    /// Rotate by copying current file to `path.1` (shifting older files further) and truncating it in place, as with
+    /// `copytruncate` strategy of logrotate. Inode of `path` is preserved.
+    pub fn rotate_copytruncate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.shift_rotated()?;
+        if self.max_depth > 0 {
+            fs::copy(&self.path, self.rotated_path(1))?;
+        }
+        // file is opened for appending, so following writes start at the new end
+        self.file.set_len(0)?;
+        Ok(())
+    }
+
+    /// Move `path.i` to `path.{i+1}` for all rotated files, dropping the oldest one.
+    fn shift_rotated(&self) -> io::Result<()> {
+        for depth in (1..self.max_depth).rev() {
+            let rotated_path = self.rotated_path(depth);
+            if rotated_path.exists() {
+                fs::rename(rotated_path, self.rotated_path(depth + 1))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn rotated_path(&self, depth: usize) -> PathBuf {
+        append_extension(self.path.clone(), depth.to_string())
+    }
+}
+
+fn open_for_append(path: &Path) -> io::Result<File> {
+    File::options().append(true).create(true).open(path)
+}
+
+impl Write for RotatingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, Write},
+    };
+
+    use tempfile::TempDir;
+
+    use super::RotatingWriter;
+    use crate::{
+        path_utils::{file_id, glob_rotated_logs},
+        InodeAwareReader,
+    };
+
+    fn rotated_contents(dir: &TempDir, max_depth: usize) -> Vec<String> {
+        glob_rotated_logs(dir.path().join("log"), max_depth)
+            .unwrap()
+            .into_iter()
+            .map(|(path, _)| fs::read_to_string(path).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rotate_shifts_files_up_to_max_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = RotatingWriter::with_max_depth(dir.path().join("log"), 2).unwrap();
+        for content in ["first\n", "second\n", "third\n"] {
+            writer.write_all(content.as_bytes()).unwrap();
+            writer.rotate().unwrap();
+        }
+        writer.write_all(b"fourth\n").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(
+            rotated_contents(&dir, 3),
+            vec!["second\n", "third\n", "fourth\n"]
+        );
+    }
+
+    #[test]
+    fn rotate_copytruncate_preserves_inode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        let mut writer = RotatingWriter::new(&path).unwrap();
+        let inode = file_id(&path).unwrap();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate_copytruncate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        assert_eq!(file_id(&path).unwrap(), inode);
+        assert_eq!(rotated_contents(&dir, 1), vec!["first\n", "second\n"]);
+    }
+
+    #[test]
+    fn zero_depth_discards_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = RotatingWriter::with_max_depth(dir.path().join("log"), 0).unwrap();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        assert_eq!(rotated_contents(&dir, 1), vec!["second\n"]);
+    }
+
+    #[test]
+    fn inode_aware_reader_reads_rotated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = RotatingWriter::new(dir.path().join("log")).unwrap();
+        writer.write_all(b"first\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+
+        let mut reader = InodeAwareReader::from_rotated_logs(writer.path()).unwrap();
+        assert_eq!(reader.get_inodes().len(), 2);
+        let lines = (&mut *reader)
+            .lines()
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(lines, vec!["first", "second"]);
+    }
+}
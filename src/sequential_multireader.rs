@@ -0,0 +1,327 @@
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+
+use crate::multireader::produce_total_offsets;
+
+/// Sequential-only counterpart of `Multireader` for readers that do not implement `Seek` (eg. pipes or network streams).
+///
+/// Sizes of all items except for the last one must be supplied upfront since they cannot be discovered without seeking.
+/// Every item but the last is read up to its declared size and no further; an item ending early produces UnexpectedEof io
+/// error. Last item is read until it reports EOF.
+///
+/// ```rust
+/// # use std::io::Read;
+/// # use filetrack::SequentialMultireader;
+/// let items: Vec<&[u8]> = vec![b"hello ", b"world"];
+/// let mut reader = SequentialMultireader::with_exact_sizes(items, vec![6])?;
+/// let mut content = String::new();
+/// reader.read_to_string(&mut content)?;
+/// assert_eq!(content, "hello world");
+/// assert_eq!(reader.get_current_item_index(), 1);
+/// assert_eq!(reader.get_local_offset(), 5);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// `Seek` is implemented only to report current position (`SeekFrom::Current(0)`), any other seek fails with Unsupported
+/// io error.
+pub struct SequentialMultireader<R> {
+    items: Vec<R>,
+    /// global offsets at which every item except for last ends
+    offsets: Vec<u64>,
+    global_offset: u64,
+    current_item: usize,
+    /// global offset up to which current item returned data from its last `fill_buf`, used to clamp `consume`
+    buffered_end: u64,
+}
+
+impl<R: Read> SequentialMultireader<R> {
+    /// Create a reader from a collection of readers and sizes of all of them except for the last one.
+    ///
+    /// Returns InvalidInput io error if number of sizes does not match.
+    pub fn with_exact_sizes(items: Vec<R>, sizes: Vec<u64>) -> io::Result<Self> {
+        if sizes.len() != items.len().saturating_sub(1) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "got {} sizes for {} items, expected sizes of all items except for the last one",
+                    sizes.len(),
+                    items.len()
+                ),
+            ));
+        }
+        Ok(Self {
+            items,
+            offsets: produce_total_offsets(sizes),
+            global_offset: 0,
+            current_item: 0,
+            buffered_end: 0,
+        })
+    }
+
+    /// Get total number of items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Get offset in the virtual buffer built from all items.
+    pub fn get_global_offset(&self) -> u64 {
+        self.global_offset
+    }
+
+    /// Get index of an item that is currently being read.
+    pub fn get_current_item_index(&self) -> usize {
+        self.current_item
+    }
+
+    /// Get total size of all items before current one.
+    pub fn get_bytes_before_current_item(&self) -> u64 {
+        match self.current_item {
+            0 => 0,
+            item_index => self.offsets[item_index - 1],
+        }
+    }
+
+    /// Get offset inside current item.
+    pub fn get_local_offset(&self) -> u64 {
+        self.global_offset - self.get_bytes_before_current_item()
+    }
+
+    /// Destroy reader and return underlying items.
+    pub fn into_inner(self) -> Vec<R> {
+        self.items
+    }
+
+    /// Number of bytes left in current item according to declared sizes, None for the last item.
+    fn remaining_in_current_item(&self) -> Option<u64> {
+        self.offsets
+            .get(self.current_item)
+            .map(|&item_end| item_end - self.global_offset)
+    }
+
+    /// Move to the next item if current one was read up to its declared size.
+    fn advance_current_item_index(&mut self) {
+        while self.remaining_in_current_item() == Some(0) {
+            self.current_item += 1;
+            self.buffered_end = 0;
+        }
+    }
+}
+
+fn early_eof_error(item_index: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        format!("item {item_index} ended before reaching its declared size"),
+    )
+}
+
+impl<R: Read> Read for SequentialMultireader<R> {
+    /// Read from current item, moving on to the next one once current item is read up to its declared size.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.advance_current_item_index();
+        let max_len = match self.remaining_in_current_item() {
+            Some(remaining) => buf.len().min(remaining as usize),
+            None => buf.len(),
+        };
+        let Some(item) = self.items.get_mut(self.current_item) else {
+            return Ok(0);
+        };
+        let size_read = item.read(&mut buf[..max_len])?;
+        if size_read == 0 && max_len > 0 && self.remaining_in_current_item().is_some() {
+            return Err(early_eof_error(self.current_item));
+        }
+        self.global_offset += size_read as u64;
+        self.advance_current_item_index();
+        Ok(size_read)
+    }
+}
+
+impl<R: BufRead> BufRead for SequentialMultireader<R> {
+    /// Fill buffer of current item, limited to its declared size.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.advance_current_item_index();
+        let remaining = self.remaining_in_current_item();
+        if self.current_item >= self.items.len() {
+            return Ok(&[]);
+        }
+        let buf = self.items[self.current_item].fill_buf()?;
+        if buf.is_empty() && remaining.is_some() {
+            return Err(early_eof_error(self.current_item));
+        }
+        let buf = match remaining {
+            Some(remaining) => &buf[..buf.len().min(remaining as usize)],
+            None => buf,
+        };
+        self.buffered_end = self.global_offset + buf.len() as u64;
+        Ok(buf)
+    }
+
+    /// Mark `amt` bytes returned by `fill_buf` as read, clamping it to the number of bytes returned.
+    fn consume(&mut self, amt: usize) {
+        let outstanding = self.buffered_end.saturating_sub(self.global_offset);
+        let amt = amt.min(outstanding as usize);
+        if amt == 0 {
+            return;
+        }
+        self.items[self.current_item].consume(amt);
+        self.global_offset += amt as u64;
+        self.advance_current_item_index();
+    }
+}
+
+impl<R> Seek for SequentialMultireader<R> {
+    /// Only `SeekFrom::Current(0)` (used to query position) is supported.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match pos {
+            SeekFrom::Current(0) => Ok(self.global_offset),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "SequentialMultireader does not support seeking",
+            )),
+        }
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.global_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, BufRead, Cursor, Read, Seek, SeekFrom};
+
+    use rstest::{fixture, rstest};
+
+    use super::SequentialMultireader;
+
+    /// Reader that hides `Seek` implementation of a cursor.
+    struct NonSeekable(Cursor<Vec<u8>>);
+
+    impl Read for NonSeekable {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.read(buf)
+        }
+    }
+
+    impl BufRead for NonSeekable {
+        fn fill_buf(&mut self) -> io::Result<&[u8]> {
+            self.0.fill_buf()
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.0.consume(amt)
+        }
+    }
+
+    type FakeReader = SequentialMultireader<NonSeekable>;
+
+    fn reader(items: &[&str], sizes: Vec<u64>) -> FakeReader {
+        let items = items
+            .iter()
+            .map(|item| NonSeekable(Cursor::new(item.as_bytes().to_vec())))
+            .collect();
+        SequentialMultireader::with_exact_sizes(items, sizes).unwrap()
+    }
+
+    #[fixture]
+    fn split_line_reader() -> FakeReader {
+        reader(&["hel", "", "lo\nworld"], vec![3, 0])
+    }
+
+    #[rstest]
+    fn reads_all_items_in_order(mut split_line_reader: FakeReader) {
+        let mut content = String::new();
+        split_line_reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "hello\nworld");
+        assert_eq!(split_line_reader.get_global_offset(), 11);
+        assert_eq!(split_line_reader.get_current_item_index(), 2);
+        assert_eq!(split_line_reader.get_bytes_before_current_item(), 3);
+        assert_eq!(split_line_reader.get_local_offset(), 8);
+    }
+
+    #[rstest]
+    fn lines_continue_across_items(split_line_reader: FakeReader) {
+        let lines = split_line_reader
+            .lines()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(lines, vec!["hello", "world"]);
+    }
+
+    #[rstest]
+    fn tracks_offsets_while_reading(mut split_line_reader: FakeReader) {
+        let mut buf = [0; 2];
+        split_line_reader.read_exact(&mut buf).unwrap();
+        assert_eq!(split_line_reader.get_current_item_index(), 0);
+        assert_eq!(split_line_reader.get_local_offset(), 2);
+        split_line_reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ll");
+        assert_eq!(split_line_reader.get_current_item_index(), 2);
+        assert_eq!(split_line_reader.get_local_offset(), 1);
+        assert_eq!(split_line_reader.stream_position().unwrap(), 4);
+    }
+
+    #[test]
+    fn data_past_declared_size_is_ignored() {
+        let mut reader = reader(&["abcdef", "gh"], vec![3]);
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "abcgh");
+
+        let mut reader = self::reader(&["abcdef", "gh"], vec![3]);
+        assert_eq!(reader.fill_buf().unwrap(), b"abc");
+        reader.consume(10);
+        assert_eq!(reader.get_global_offset(), 3);
+        assert_eq!(reader.fill_buf().unwrap(), b"gh");
+    }
+
+    #[test]
+    fn item_shorter_than_declared_size_is_an_error() {
+        let mut reader = reader(&["ab", "cd"], vec![3]);
+        let mut content = String::new();
+        let error = reader.read_to_string(&mut content).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(reader.get_global_offset(), 2);
+
+        let mut reader = self::reader(&["ab", "cd"], vec![3]);
+        reader.consume(0);
+        assert_eq!(reader.fill_buf().unwrap(), b"ab");
+        reader.consume(2);
+        assert!(reader.fill_buf().is_err());
+    }
+
+    #[rstest]
+    #[case(&["a", "b"], vec![])]
+    #[case(&["a", "b"], vec![1, 1])]
+    #[case(&[], vec![1])]
+    fn mismatched_sizes_are_rejected(#[case] items: &[&str], #[case] sizes: Vec<u64>) {
+        let items = items
+            .iter()
+            .map(|item| NonSeekable(Cursor::new(item.as_bytes().to_vec())))
+            .collect();
+        let error = SequentialMultireader::with_exact_sizes(items, sizes)
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[rstest]
+    fn seeking_is_unsupported(mut split_line_reader: FakeReader) {
+        assert_eq!(split_line_reader.stream_position().unwrap(), 0);
+        for pos in [SeekFrom::Start(0), SeekFrom::Current(1), SeekFrom::End(0)] {
+            let error = split_line_reader.seek(pos).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        }
+    }
+
+    #[test]
+    fn empty_reader_yields_eof() {
+        let mut reader = reader(&[], vec![]);
+        assert!(reader.is_empty());
+        assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+        assert_eq!(reader.fill_buf().unwrap(), b"");
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     fs::File,
-    io::Seek,
+    io::{Read, Seek},
     ops::{Deref, DerefMut},
     path::Path,
 };
@@ -8,7 +8,13 @@ use std::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::inode_aware::{InodeAwareOffset, InodeAwareReader};
+use crate::{
+    inode_aware::{
+        InodeAwareOffset, InodeAwareReader, InodeAwareReaderBuilder, MissingInodePolicy,
+        SeekOutcome, Truncation, TruncationPolicy,
+    },
+    path_utils::{RotationNaming, RotationScheme},
+};
 
 /// Structure used by `TrackedReader` for simple file persistence.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -26,25 +32,56 @@ pub enum StateSerdeError {
     Serde(#[from] bincode::Error),
 }
 
+/// State as it was persisted before fingerprints were introduced.
+#[derive(Deserialize)]
+struct LegacyState {
+    inode: u64,
+    offset: u64,
+}
+
+impl From<LegacyState> for State {
+    fn from(legacy: LegacyState) -> Self {
+        State {
+            offset: InodeAwareOffset {
+                inode: legacy.inode,
+                offset: legacy.offset,
+                fingerprint: None,
+                file_stamp: None,
+            },
+        }
+    }
+}
+
 impl State {
     /// deserialize State from a file
+    ///
+    /// States written by older versions (bare inode and offset) are accepted as well.
     pub fn load(file: &mut File) -> Result<Self, StateSerdeError> {
         file.rewind()?;
-        let state = bincode::deserialize_from(file)?;
-        Ok(state)
+        let mut content = vec![];
+        file.read_to_end(&mut content)?;
+        match bincode::deserialize(&content) {
+            Ok(state) => Ok(state),
+            Err(e) => match bincode::deserialize::<LegacyState>(&content) {
+                Ok(legacy) => Ok(legacy.into()),
+                Err(_) => Err(e.into()),
+            },
+        }
     }
 
     /// serialize and write State to a file
     pub fn persist(&self, file: &mut File) -> std::io::Result<()> {
         file.rewind()?;
-        match bincode::serialize_into(file, self) {
+        match bincode::serialize_into(&mut *file, self) {
             Ok(_) => {}
             Err(e) => match *e {
                 bincode::ErrorKind::Io(ioerr) => return Err(ioerr),
                 _ => unreachable!(),
             },
         }
-        Ok(())
+        // state size depends on presence of fingerprint, so leftovers of a longer state must be dropped
+        let len = file.stream_position()?;
+        file.set_len(len)
     }
 }
 
@@ -80,7 +117,7 @@ impl State {
 ///
 /// * **explicit** by calling `.close()`. This will allow you to handle any errors that may happen in the process
 /// * **implicitly** by relying on `Drop`. Note that errors generated while working with the filesystem cannot be handled and will
-/// cause a panic in this case.
+///   cause a panic in this case.
 ///
 ///
 /// ## Working principles
@@ -91,19 +128,24 @@ impl State {
 /// a file identified by path appended by `.1` (eg. `mail.log` and `mail.log.1`) and so on. After that you are given a file-like structure that allows
 /// buffered reading and seeking in up to specified number of files files.
 ///
+/// If the log may change its inode without being rotated (eg. when it is copied), enable fingerprinting with
+/// `set_fingerprint_length` so that the file can still be found by its content.
+///
 /// ## Limitations
 ///
 /// * You can only expect this to work if logrotation happened not more than the number you specified as search_depth. This means that if you are
-/// creating a log processor for example, it should be run frequently enough to keep up with logs that are written and rotated.
+///   creating a log processor for example, it should be run frequently enough to keep up with logs that are written and rotated.
 ///
 /// * Due to simple scheme of persistence, we cannot seek back into rotated file version after saving state while reading from current
-/// log file. This means that if your program must do some conditional seeking in a file, you should perform any pointer rollback before
-/// performing final save (done by `.close()` or Drop). Overall, this library is intended to be used for mostly forward reading of
-/// log files.
+///   log file. This means that if your program must do some conditional seeking in a file, you should perform any pointer rollback before
+///   performing final save (done by `.close()` or Drop). Overall, this library is intended to be used for mostly forward reading of
+///   log files.
 pub struct TrackedReader {
     inner: InodeAwareReader,
     registry: File,
     already_freed: bool,
+    /// result of restoring persisted offset, None if there was no registry
+    seek_outcome: Option<SeekOutcome>,
 }
 
 /// Possible errors that could happen while working with `TrackedReader`.
@@ -115,6 +157,8 @@ pub enum TrackedReaderError {
     Persistence(#[from] StateSerdeError),
     #[error("trying to resolve logrotated file")]
     RotationResolution(String),
+    #[error("persisted offset does not fit into the file")]
+    Truncated(#[source] Truncation),
 }
 
 impl TrackedReader {
@@ -139,19 +183,116 @@ impl TrackedReader {
         filepath: impl AsRef<Path>,
         registry: impl AsRef<Path>,
         search_depth: usize,
+    ) -> Result<Self, TrackedReaderError> {
+        Self::with_naming(filepath, registry, search_depth, RotationNaming::default())
+    }
+
+    /// Like `::with_search_depth` but allows specifying how rotated logs are named, see `RotationNaming`.
+    pub fn with_naming(
+        filepath: impl AsRef<Path>,
+        registry: impl AsRef<Path>,
+        search_depth: usize,
+        naming: RotationNaming,
+    ) -> Result<Self, TrackedReaderError> {
+        Self::with_scheme(filepath, registry, search_depth, naming)
+    }
+
+    /// Like `::with_search_depth` but rotated logs are found by custom `scheme`, see `RotationScheme`.
+    pub fn with_scheme(
+        filepath: impl AsRef<Path>,
+        registry: impl AsRef<Path>,
+        search_depth: usize,
+        scheme: impl RotationScheme + Send + 'static,
+    ) -> Result<Self, TrackedReaderError> {
+        Self::from_builder(
+            InodeAwareReader::builder()
+                .path(filepath)
+                .search_depth(search_depth)
+                .scheme(scheme),
+            registry,
+        )
+    }
+
+    /// Like `::with_search_depth` but allows specifying what happens if log was truncated in place (eg. by `copytruncate`
+    /// option of logrotate) so that persisted offset is past its end, see `TruncationPolicy`. With `TruncationPolicy::Error`
+    /// construction fails with `TrackedReaderError::Truncated` and registry is left untouched.
+    pub fn with_truncation_policy(
+        filepath: impl AsRef<Path>,
+        registry: impl AsRef<Path>,
+        search_depth: usize,
+        policy: TruncationPolicy,
+    ) -> Result<Self, TrackedReaderError> {
+        Self::from_builder(
+            InodeAwareReader::builder()
+                .path(filepath)
+                .search_depth(search_depth)
+                .truncation_policy(policy),
+            registry,
+        )
+    }
+
+    /// Like `::with_search_depth` but allows specifying what happens if file of persisted offset is gone (typically because
+    /// reader was not run for longer than rotation window), see `MissingInodePolicy`. Use `get_seek_outcome` to find out
+    /// whether fallback was applied.
+    pub fn with_missing_inode_policy(
+        filepath: impl AsRef<Path>,
+        registry: impl AsRef<Path>,
+        search_depth: usize,
+        policy: MissingInodePolicy,
+    ) -> Result<Self, TrackedReaderError> {
+        Self::from_builder(
+            InodeAwareReader::builder()
+                .path(filepath)
+                .search_depth(search_depth)
+                .missing_inode_policy(policy),
+            registry,
+        )
+    }
+
+    /// Create `TrackedReader` over reader configured by `builder`, which allows to combine all options of
+    /// `InodeAwareReader`. Persisted offset is restored according to truncation and missing inode policies of the
+    /// builder.
+    ///
+    /// If builder allows missing log (see `InodeAwareReaderBuilder::allow_missing`), persisted offset is not restored and
+    /// state is not saved while reader has no files at all, so registry keeps offset of previous run, if any. Use
+    /// `try_attach` or auto refresh to pick the log up once it appears.
+    ///
+    /// ```rust no_run
+    /// # use filetrack::{InodeAwareReader, TrackedReader, TrackedReaderError};
+    /// let builder = InodeAwareReader::builder()
+    ///     .path("/var/log/mail.log")
+    ///     .search_depth(3)
+    ///     .allow_missing(true)
+    ///     .buffer_capacity(64 * 1024);
+    /// let reader = TrackedReader::from_builder(builder, "registry")?;
+    /// # Ok::<(), TrackedReaderError>(())
+    /// ```
+    pub fn from_builder(
+        builder: InodeAwareReaderBuilder,
+        registry: impl AsRef<Path>,
     ) -> Result<Self, TrackedReaderError> {
         let state_from_disk = maybe_read_state(registry.as_ref())?;
-        let reader = InodeAwareReader::from_rotated_logs_with_depth(filepath, search_depth)?;
-        // now that we know that open_files did not fail, we can create registry file
+        let mut reader = builder.open()?;
+        let missing_inode_policy = reader.get_missing_inode_policy();
+        let seek_outcome = match state_from_disk {
+            // there is nothing to seek in until the log appears
+            Some(_) if reader.is_empty() => None,
+            Some(state) => Some(
+                reader
+                    .seek_persistent_or(state.offset, missing_inode_policy)
+                    .map_err(truncation_error)?,
+            ),
+            None => None,
+        };
+        // now that we know that open_files and seek did not fail, we can create registry file
         let registry = open_state_file(registry)?;
         let mut reader = Self {
             inner: reader,
             registry,
             already_freed: false,
+            seek_outcome,
         };
-        if let Some(state) = state_from_disk {
-            reader.seek_persistent(state.offset)?;
-        } else {
+        if state_from_disk.is_none() {
             // If state did not exist previously, registry file is created empty. We should additionally initialize file content.
             // This will make struct work correctly even if close/Drop will never happen (eg in case of mem::forget).
             reader.persist()?;
@@ -160,8 +301,25 @@ impl TrackedReader {
         Ok(reader)
     }
 
-    /// Explicitly save current state into registry file and return any errors generated.
+    /// Truncation of the log detected while restoring persisted offset, if any. Useful for logging.
+    pub fn get_truncation(&self) -> Option<&Truncation> {
+        match &self.seek_outcome {
+            Some(SeekOutcome::Truncated(truncation)) => Some(truncation),
+            _ => None,
+        }
+    }
+
+    /// How persisted offset was restored during construction, None if registry did not exist.
+    pub fn get_seek_outcome(&self) -> Option<&SeekOutcome> {
+        self.seek_outcome.as_ref()
+    }
+
+    /// Explicitly save current state into registry file and return any errors generated. Nothing is saved while reader has
+    /// no files, see `from_builder`.
     pub fn persist(&mut self) -> std::io::Result<()> {
+        if self.inner.is_empty() {
+            return Ok(());
+        }
         self.get_persistent_state().persist(&mut self.registry)
     }
 
@@ -186,15 +344,31 @@ fn maybe_read_state(path: &Path) -> Result<Option<State>, TrackedReaderError> {
     }
 
     let mut file = File::options().read(true).open(path)?;
+    // registry is left empty if reader never had anything to persist
+    if file.metadata()?.len() == 0 {
+        return Ok(None);
+    }
     let state = State::load(&mut file)?;
     Ok(Some(state))
 }
 
+/// Extract `Truncation` from io error produced by `InodeAwareReader::seek_persistent`.
+fn truncation_error(error: std::io::Error) -> TrackedReaderError {
+    match error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<Truncation>())
+    {
+        Some(truncation) => TrackedReaderError::Truncated(truncation.clone()),
+        None => error.into(),
+    }
+}
+
 fn open_state_file(path: impl AsRef<Path>) -> std::io::Result<File> {
     File::options()
         .read(true)
         .write(true)
         .create(true)
+        .truncate(false)
         .open(path)
 }
 
@@ -221,3 +395,233 @@ impl Drop for TrackedReader {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, Write},
+    };
+
+    use rstest::rstest;
+    use tempfile::TempDir;
+
+    use super::{State, TrackedReader, TrackedReaderError};
+    use crate::{
+        path_utils::SkipReason, FileStamp, Fingerprint, InodeAwareOffset, InodeAwareReader,
+        MissingInodePolicy, RotatingWriter, SeekOutcome, TruncationPolicy,
+    };
+
+    #[test]
+    fn state_round_trips_with_fingerprint() {
+        let mut file = tempfile::tempfile().unwrap();
+        let state = State {
+            offset: InodeAwareOffset {
+                inode: 1,
+                offset: 2,
+                fingerprint: Some(Fingerprint {
+                    hash: 3,
+                    prefix_len: 4,
+                    size: 5,
+                }),
+                file_stamp: Some(FileStamp {
+                    size: 6,
+                    modified: 7,
+                }),
+            },
+        };
+        state.persist(&mut file).unwrap();
+        assert_eq!(State::load(&mut file).unwrap(), state);
+
+        let without_fingerprint = State {
+            offset: InodeAwareOffset {
+                fingerprint: None,
+                file_stamp: None,
+                ..state.offset
+            },
+        };
+        without_fingerprint.persist(&mut file).unwrap();
+        assert_eq!(State::load(&mut file).unwrap(), without_fingerprint);
+    }
+
+    #[test]
+    fn legacy_state_is_loaded() {
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(&7u64.to_le_bytes()).unwrap();
+        file.write_all(&42u64.to_le_bytes()).unwrap();
+        let state = State::load(&mut file).unwrap();
+        assert_eq!(
+            state.offset,
+            InodeAwareOffset {
+                inode: 7,
+                offset: 42,
+                fingerprint: None,
+                file_stamp: None,
+            }
+        );
+    }
+
+    #[test]
+    fn copytruncate_between_runs_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = dir.path().join("registry");
+        let mut writer = RotatingWriter::new(dir.path().join("log")).unwrap();
+        writer.write_all(b"first\nsecond\n").unwrap();
+        let mut reader = TrackedReader::new(writer.path(), &registry).unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        reader.read_line(&mut line).unwrap();
+        reader.close().unwrap();
+
+        // copy gets a new inode, so only the truncated live file matters
+        writer.rotate_copytruncate().unwrap();
+        fs::remove_file(dir.path().join("log.1")).unwrap();
+        writer.write_all(b"third\n").unwrap();
+        let persisted = fs::read(&registry).unwrap();
+
+        let error = TrackedReader::with_truncation_policy(
+            writer.path(),
+            &registry,
+            1,
+            TruncationPolicy::Error,
+        )
+        .err()
+        .unwrap();
+        assert!(
+            matches!(error, TrackedReaderError::Truncated(truncation) if truncation.offset == 13 && truncation.size == 6)
+        );
+        assert_eq!(fs::read(&registry).unwrap(), persisted);
+
+        let mut reader = TrackedReader::new(writer.path(), &registry).unwrap();
+        assert_eq!(reader.get_truncation().map(|t| t.offset), Some(13));
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "third\n");
+    }
+
+    /// Log whose registry points at a file that was rotated away. Returned file keeps the inode from being reused.
+    fn log_with_vanished_inode() -> (TempDir, RotatingWriter, fs::File) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = RotatingWriter::new(dir.path().join("log")).unwrap();
+        writer.write_all(b"first\n").unwrap();
+        let vanished = fs::File::open(writer.path()).unwrap();
+        let mut reader = TrackedReader::new(writer.path(), dir.path().join("registry")).unwrap();
+        reader.read_line(&mut String::new()).unwrap();
+        reader.close().unwrap();
+
+        writer.rotate().unwrap();
+        writer.write_all(b"second\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_all(b"third\n").unwrap();
+        (dir, writer, vanished)
+    }
+
+    #[rstest]
+    #[case(MissingInodePolicy::StartOfOldest, "second\n")]
+    #[case(MissingInodePolicy::EndOfNewest, "")]
+    fn missing_inode_policy_is_applied(#[case] policy: MissingInodePolicy, #[case] expected: &str) {
+        let (dir, writer, _vanished) = log_with_vanished_inode();
+        let mut reader = TrackedReader::with_missing_inode_policy(
+            writer.path(),
+            dir.path().join("registry"),
+            1,
+            policy,
+        )
+        .unwrap();
+        assert_eq!(
+            reader.get_seek_outcome(),
+            Some(&SeekOutcome::FellBack(policy))
+        );
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, expected);
+    }
+
+    #[test]
+    fn missing_inode_is_an_error_by_default() {
+        let (dir, writer, _vanished) = log_with_vanished_inode();
+        let registry = dir.path().join("registry");
+        let persisted = fs::read(&registry).unwrap();
+        let error = TrackedReader::new(writer.path(), &registry).err().unwrap();
+        assert!(
+            matches!(error, TrackedReaderError::IO(e) if e.kind() == std::io::ErrorKind::NotFound)
+        );
+        assert_eq!(fs::read(&registry).unwrap(), persisted);
+
+        let reader = TrackedReader::new(writer.path(), dir.path().join("new_registry")).unwrap();
+        assert_eq!(reader.get_seek_outcome(), None);
+    }
+
+    #[test]
+    fn missing_log_is_picked_up_later() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = dir.path().join("registry");
+        let log = dir.path().join("log");
+        let mut reader = TrackedReader::from_builder(
+            InodeAwareReader::builder().path(&log).allow_missing(true),
+            &registry,
+        )
+        .unwrap();
+        let mut line = String::new();
+        assert_eq!(reader.read_line(&mut line).unwrap(), 0);
+        reader.close().unwrap();
+        assert_eq!(fs::read(&registry).unwrap(), b"");
+
+        let mut reader = TrackedReader::from_builder(
+            InodeAwareReader::builder().path(&log).allow_missing(true),
+            &registry,
+        )
+        .unwrap();
+        fs::write(&log, "first\nsecond\n").unwrap();
+        assert!(reader.try_attach().unwrap());
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "first\n");
+        reader.close().unwrap();
+
+        let mut reader = TrackedReader::new(&log, &registry).unwrap();
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "second\n");
+    }
+
+    #[test]
+    fn buffer_capacity_is_passed_through() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = dir.path().join("registry");
+        let log = dir.path().join("log");
+        fs::write(&log, "first\nsecond\n").unwrap();
+        let mut reader = TrackedReader::from_builder(
+            InodeAwareReader::builder().path(&log).buffer_capacity(1),
+            &registry,
+        )
+        .unwrap();
+        assert_eq!(reader.get_buffer_capacity(), 1);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "first\n");
+        reader.close().unwrap();
+
+        let mut reader = TrackedReader::from_builder(
+            InodeAwareReader::builder().path(&log).buffer_capacity(1),
+            &registry,
+        )
+        .unwrap();
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "second\n");
+    }
+
+    #[test]
+    fn resolution_report_is_available() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("log");
+        fs::write(&log, "live\n").unwrap();
+        fs::write(dir.path().join("log.2"), "old\n").unwrap();
+        let reader =
+            TrackedReader::with_search_depth(&log, dir.path().join("registry"), 2).unwrap();
+        let report = reader.resolution_report();
+        assert_eq!(report.entries.len(), 3);
+        assert!(!report.entries[1].existed);
+        assert_eq!(report.entries[2].skipped, Some(SkipReason::AfterGap));
+    }
+}